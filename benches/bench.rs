@@ -0,0 +1,134 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+// Benchmarks of rust-oracle
+//
+// They connect to the database used by tests. See tests/common/mod.rs for
+// environment variables to set connection parameters.
+//
+//   cargo bench
+
+#[macro_use]
+extern crate criterion;
+extern crate oracle;
+
+#[path = "../tests/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+
+use criterion::Criterion;
+use oracle::Connection;
+use oracle::OracleType;
+
+fn bulk_insert(c: &mut Criterion) {
+    let conn = common::connect().unwrap();
+    for batch_size in [1, 100, 1000].iter() {
+        c.bench_function(&format!("bulk insert 1000 rows (batch size {})", batch_size), |b| b.iter(|| {
+            let mut batch = conn.batch("insert into TestTempTable values (:1, :2)", *batch_size).unwrap();
+            for i in 0..1000 {
+                batch.append_row(&[&i, &"a string value"]).unwrap();
+            }
+            batch.execute().unwrap();
+            conn.rollback().unwrap();
+        }));
+    }
+}
+
+fn wide_select(conn: &Connection, fetch_array_size: u32) {
+    let mut stmt = conn.prepare("select level, 'abcdefghij' || level, sysdate, systimestamp,
+                                        level * 1.5, level / 3, to_char(level), rpad('x', 100, 'x'),
+                                        interval '1' day * level, cast(level as binary_double)
+                                   from dual connect by level <= 10000").unwrap();
+    stmt.set_fetch_array_size(fetch_array_size);
+    stmt.execute(&[]).unwrap();
+    while let Ok(row) = stmt.fetch() {
+        for i in 0..10 {
+            let _: Option<String> = row.get(i).unwrap();
+        }
+    }
+}
+
+fn select(c: &mut Criterion) {
+    let conn = common::connect().unwrap();
+    for fetch_array_size in [10, 100, 1000].iter() {
+        c.bench_function(&format!("wide select 10000 rows (fetch array size {})", fetch_array_size), |b| b.iter(|| {
+            wide_select(&conn, *fetch_array_size)
+        }));
+    }
+}
+
+fn numeric_select(c: &mut Criterion) {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select level, level * 1000003, level / 8, -level * 1.5
+                                   from dual connect by level <= 10000").unwrap();
+    stmt.set_fetch_array_size(1000);
+    c.bench_function("select 10000 rows of NUMBER as i64", |b| b.iter(|| {
+        stmt.execute(&[]).unwrap();
+        while let Ok(row) = stmt.fetch() {
+            for i in 0..2 {
+                let _: i64 = row.get(i).unwrap();
+            }
+        }
+    }));
+    c.bench_function("select 10000 rows of NUMBER as f64", |b| b.iter(|| {
+        stmt.execute(&[]).unwrap();
+        while let Ok(row) = stmt.fetch() {
+            for i in 0..4 {
+                let _: f64 = row.get(i).unwrap();
+            }
+        }
+    }));
+}
+
+fn lob(c: &mut Criterion) {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :1 := to_clob(rpad('x', 32767, 'x')); for i in 1..31 loop dbms_lob.append(:1, rpad('x', 32767, 'x')); end loop; end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB]).unwrap();
+    c.bench_function("read 1MB CLOB", |b| b.iter(|| {
+        let _: String = stmt.bind_value(1).unwrap();
+    }));
+}
+
+fn object_bind(c: &mut Criterion) {
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_OBJECT").unwrap();
+    let mut stmt = conn.prepare("begin :1 := :2; end;").unwrap();
+    c.bench_function("object bind", |b| b.iter(|| {
+        let mut obj = objtype.new_object().unwrap();
+        obj.set("NUMBERVALUE", &1).unwrap();
+        obj.set("STRINGVALUE", &"a string value").unwrap();
+        stmt.execute(&[&OracleType::Object(objtype.clone()), &obj]).unwrap();
+    }));
+}
+
+criterion_group!(benches, bulk_insert, select, numeric_select, lob, object_bind);
+criterion_main!(benches);
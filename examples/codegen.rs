@@ -0,0 +1,71 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+// Prints rust structs for tables and object types.
+//
+//   cargo run --example codegen -- scott tiger "" EMP DEPT -t UDT_PERSON
+//
+// Names after `-t` are object types. Others are tables or views.
+
+extern crate oracle;
+
+use std::env;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 4 {
+        eprintln!("usage: codegen USERNAME PASSWORD CONNECT_STRING [TABLE | -t OBJECT_TYPE]...");
+        process::exit(1);
+    }
+    let conn = oracle::Connection::new(&args[0], &args[1], &args[2]).unwrap();
+    let mut is_object_type = false;
+    for name in &args[3..] {
+        if name == "-t" {
+            is_object_type = true;
+            continue;
+        }
+        let code = if is_object_type {
+            oracle::codegen::object_type_struct(&conn, name)
+        } else {
+            oracle::codegen::table_struct(&conn, name)
+        };
+        match code {
+            Ok(code) => println!("{}", code),
+            Err(err) => {
+                eprintln!("{}: {}", name, err);
+                process::exit(1);
+            },
+        }
+        is_object_type = false;
+    }
+}
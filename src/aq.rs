@@ -0,0 +1,1576 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cmp;
+use std::os::raw::c_char;
+use std::ptr;
+use std::result;
+use std::slice;
+
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+
+use binding::*;
+
+use Connection;
+use Context;
+use Error;
+use Object;
+use ObjectType;
+use OracleType;
+use Result;
+use Statement;
+use Timestamp;
+use ToSql;
+
+use new_odpi_str;
+use to_odpi_str;
+use OdpiStr;
+
+//
+// Visibility
+//
+
+/// Transactional behavior of enqueue and dequeue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// The operation is a part of the current transaction.
+    /// This is the default.
+    OnCommit,
+    /// The operation is committed in an autonomous transaction.
+    Immediate,
+}
+
+impl Visibility {
+    fn to_dpi_value(&self) -> dpiVisibility {
+        match *self {
+            Visibility::OnCommit => DPI_VISIBILITY_ON_COMMIT,
+            Visibility::Immediate => DPI_VISIBILITY_IMMEDIATE,
+        }
+    }
+}
+
+//
+// DeqMode
+//
+
+/// Locking behavior of dequeue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeqMode {
+    /// Reads a message without locking it, like `SELECT`.
+    Browse,
+    /// Reads and locks a message, like `SELECT ... FOR UPDATE`.
+    Locked,
+    /// Reads and removes a message. This is the default.
+    Remove,
+    /// Removes a message without returning its payload.
+    RemoveNoData,
+}
+
+impl DeqMode {
+    fn to_dpi_value(&self) -> dpiDeqMode {
+        match *self {
+            DeqMode::Browse => DPI_MODE_DEQ_BROWSE,
+            DeqMode::Locked => DPI_MODE_DEQ_LOCKED,
+            DeqMode::Remove => DPI_MODE_DEQ_REMOVE,
+            DeqMode::RemoveNoData => DPI_MODE_DEQ_REMOVE_NO_DATA,
+        }
+    }
+}
+
+//
+// DeqNavigation
+//
+
+/// Position of the message to be dequeued
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeqNavigation {
+    /// The first available message matching the search criteria.
+    /// This resets the position to the beginning of the queue.
+    FirstMessage,
+    /// The first message of the next transaction group.
+    NextTransaction,
+    /// The next available message matching the search criteria.
+    /// This is the default.
+    NextMessage,
+}
+
+impl DeqNavigation {
+    fn to_dpi_value(&self) -> dpiDeqNavigation {
+        match *self {
+            DeqNavigation::FirstMessage => DPI_DEQ_NAV_FIRST_MSG,
+            DeqNavigation::NextTransaction => DPI_DEQ_NAV_NEXT_TRANSACTION,
+            DeqNavigation::NextMessage => DPI_DEQ_NAV_NEXT_MSG,
+        }
+    }
+}
+
+//
+// Agent
+//
+
+/// Agent, a queue and a consumer name, to listen on by [Connection.listen][]
+///
+/// [Connection.listen]: struct.Connection.html#method.listen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Agent {
+    name: Option<String>,
+    address: String,
+}
+
+impl Agent {
+    /// Creates an agent of a single-consumer queue.
+    pub fn new(queue_name: &str) -> Agent {
+        Agent {
+            name: None,
+            address: queue_name.to_string(),
+        }
+    }
+
+    /// Creates an agent of a consumer of a multi-consumer queue.
+    pub fn with_consumer(queue_name: &str, consumer_name: &str) -> Agent {
+        Agent {
+            name: Some(consumer_name.to_string()),
+            address: queue_name.to_string(),
+        }
+    }
+
+    /// Returns the consumer name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns the queue name.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+pub(crate) fn listen(conn: &Connection, agents: &[Agent], wait: Option<u32>) -> Result<Option<Agent>> {
+    let wait = match wait {
+        Some(secs) => secs as i64,
+        None => -1, // DBMS_AQ.FOREVER
+    };
+    let bind_names: Vec<(String, String)> = (1..(agents.len() + 1))
+        .map(|i| (format!("agent_name{}", i), format!("agent_address{}", i)))
+        .collect();
+    let mut assignments = String::new();
+    let mut params: Vec<(&str, &ToSql)> = Vec::new();
+    for (i, (agent, names)) in agents.iter().zip(bind_names.iter()).enumerate() {
+        assignments.push_str(&format!("    agents({}) := sys.aq$_agent(:{}, :{}, null);\n", i + 1, names.0, names.1));
+        params.push((&names.0, &agent.name));
+        params.push((&names.1, &agent.address));
+    }
+    let name_type = OracleType::Varchar2(128);
+    let address_type = OracleType::Varchar2(1024);
+    params.push(("name", &name_type));
+    params.push(("address", &address_type));
+    let sql = format!("\
+declare
+    agents dbms_aq.aq$_agent_list_t;
+    agent sys.aq$_agent;
+    no_messages exception;
+    pragma exception_init(no_messages, -25254);
+begin
+{}    dbms_aq.listen(agents, {}, agent);
+    :name := agent.name;
+    :address := agent.address;
+exception
+    when no_messages then
+        :address := null;
+end;", assignments, wait);
+    let stmt = conn.execute_named_internal(&sql, &params)?;
+    let address: Option<String> = stmt.bind_value("address")?;
+    match address {
+        Some(address) => Ok(Some(Agent {
+            name: stmt.bind_value("name")?,
+            address: address,
+        })),
+        None => Ok(None),
+    }
+}
+
+//
+// DeqOptions
+//
+
+/// Options to dequeue messages
+///
+/// Options which are not set are left to the defaults of Oracle.
+///
+/// # Examples
+///
+/// Peek at messages without removing them and then remove one selected
+/// by its message identifier.
+///
+/// ```no_run
+/// # use oracle::*;
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let objtype = conn.object_type("UDT_BOOK").unwrap();
+/// let mut queue = conn.queue("BOOKS", &objtype);
+/// // Return None when no message arrives in 5 seconds.
+/// queue.deq_options_mut()
+///     .wait(5)
+///     .mode(DeqMode::Browse)
+///     .navigation(DeqNavigation::FirstMessage);
+/// let msg = queue.dequeue().unwrap().unwrap();
+///
+/// queue.deq_options_mut()
+///     .mode(DeqMode::Remove)
+///     .msgid(msg.msgid());
+/// queue.dequeue().unwrap();
+/// conn.commit().unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeqOptions {
+    wait: Option<u32>,
+    mode: Option<DeqMode>,
+    navigation: Option<DeqNavigation>,
+    visibility: Option<Visibility>,
+    consumer_name: Option<String>,
+    correlation: Option<String>,
+    condition: Option<String>,
+    msgid: Option<Vec<u8>>,
+    delivery_mode: Option<DeliveryMode>,
+}
+
+impl DeqOptions {
+    /// Creates dequeue options with default values.
+    pub fn new() -> DeqOptions {
+        DeqOptions {
+            wait: None,
+            mode: None,
+            navigation: None,
+            visibility: None,
+            consumer_name: None,
+            correlation: None,
+            condition: None,
+            msgid: None,
+            delivery_mode: None,
+        }
+    }
+
+    /// Sets the time in seconds to wait for a message to arrive.
+    /// Zero means no wait. The default is to wait forever.
+    pub fn wait<'a>(&'a mut self, secs: u32) -> &'a mut DeqOptions {
+        self.wait = Some(secs);
+        self
+    }
+
+    /// Sets the locking behavior of dequeue.
+    pub fn mode<'a>(&'a mut self, mode: DeqMode) -> &'a mut DeqOptions {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the position of the message to be dequeued.
+    pub fn navigation<'a>(&'a mut self, navigation: DeqNavigation) -> &'a mut DeqOptions {
+        self.navigation = Some(navigation);
+        self
+    }
+
+    /// Sets whether dequeue is a part of the current transaction.
+    pub fn visibility<'a>(&'a mut self, visibility: Visibility) -> &'a mut DeqOptions {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Sets the consumer name, which is required by multi-consumer queues.
+    pub fn consumer_name<'a>(&'a mut self, name: &str) -> &'a mut DeqOptions {
+        self.consumer_name = Some(name.to_string());
+        self
+    }
+
+    /// Dequeues only messages whose correlation identifiers match `correlation`.
+    /// Wildcards `%` and `_` may be used.
+    pub fn correlation<'a>(&'a mut self, correlation: &str) -> &'a mut DeqOptions {
+        self.correlation = Some(correlation.to_string());
+        self
+    }
+
+    /// Dequeues only messages satisfying `condition`, a boolean expression
+    /// similar to the where clause of SQL.
+    pub fn condition<'a>(&'a mut self, condition: &str) -> &'a mut DeqOptions {
+        self.condition = Some(condition.to_string());
+        self
+    }
+
+    /// Dequeues only the message whose identifier is `msgid`.
+    pub fn msgid<'a>(&'a mut self, msgid: &[u8]) -> &'a mut DeqOptions {
+        self.msgid = Some(msgid.to_vec());
+        self
+    }
+
+    /// Clears the criterion set by [msgid](#method.msgid).
+    pub fn clear_msgid<'a>(&'a mut self) -> &'a mut DeqOptions {
+        self.msgid = None;
+        self
+    }
+
+    /// Sets the delivery mode of messages to be dequeued.
+    /// Buffered messages must be dequeued with [Visibility::Immediate][].
+    ///
+    /// [Visibility::Immediate]: enum.Visibility.html#variant.Immediate
+    pub fn delivery_mode<'a>(&'a mut self, mode: DeliveryMode) -> &'a mut DeqOptions {
+        self.delivery_mode = Some(mode);
+        self
+    }
+
+    // PL/SQL statements setting the options to `deq_opts` of
+    // DBMS_AQ.DEQUEUE_OPTIONS_T. Numbers are embedded in the statements
+    // and strings are appended to `params` to be bound by name.
+    fn plsql_assignments<'a>(&'a self, params: &mut Vec<(&'a str, &'a ToSql)>) -> String {
+        let mut s = String::new();
+        let wait = match self.wait {
+            Some(secs) => secs as i64,
+            None => -1, // DBMS_AQ.FOREVER
+        };
+        s.push_str(&format!("    deq_opts.wait := {};\n", wait));
+        // The values of DBMS_AQ constants are same with those of ODPI-C.
+        if let Some(mode) = self.mode {
+            s.push_str(&format!("    deq_opts.dequeue_mode := {};\n", mode.to_dpi_value()));
+        }
+        if let Some(navigation) = self.navigation {
+            s.push_str(&format!("    deq_opts.navigation := {};\n", navigation.to_dpi_value()));
+        }
+        if let Some(visibility) = self.visibility {
+            s.push_str(&format!("    deq_opts.visibility := {};\n", visibility.to_dpi_value()));
+        }
+        if let Some(mode) = self.delivery_mode {
+            s.push_str(&format!("    deq_opts.delivery_mode := {};\n", mode.to_dpi_value()));
+        }
+        if let Some(ref name) = self.consumer_name {
+            s.push_str("    deq_opts.consumer_name := :deq_consumer_name;\n");
+            params.push(("deq_consumer_name", name));
+        }
+        if let Some(ref correlation) = self.correlation {
+            s.push_str("    deq_opts.correlation := :deq_correlation;\n");
+            params.push(("deq_correlation", correlation));
+        }
+        if let Some(ref condition) = self.condition {
+            s.push_str("    deq_opts.deq_condition := :deq_condition;\n");
+            params.push(("deq_condition", condition));
+        }
+        if let Some(ref msgid) = self.msgid {
+            s.push_str("    deq_opts.msgid := :deq_msgid;\n");
+            params.push(("deq_msgid", msgid));
+        }
+        s
+    }
+
+    fn new_handle(&self, conn: &Connection) -> Result<DeqOptionsHandle> {
+        let mut handle = DeqOptionsHandle(ptr::null_mut());
+        chkerr!(conn.ctxt,
+                dpiConn_newDeqOptions(conn.handle, &mut handle.0));
+        if let Some(secs) = self.wait {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setWait(handle.0, secs));
+        }
+        if let Some(mode) = self.mode {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setMode(handle.0, mode.to_dpi_value()));
+        }
+        if let Some(navigation) = self.navigation {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setNavigation(handle.0, navigation.to_dpi_value()));
+        }
+        if let Some(visibility) = self.visibility {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setVisibility(handle.0, visibility.to_dpi_value()));
+        }
+        if let Some(ref name) = self.consumer_name {
+            let s = to_odpi_str(name);
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setConsumerName(handle.0, s.ptr, s.len));
+        }
+        if let Some(ref correlation) = self.correlation {
+            let s = to_odpi_str(correlation);
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setCorrelation(handle.0, s.ptr, s.len));
+        }
+        if let Some(ref condition) = self.condition {
+            let s = to_odpi_str(condition);
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setCondition(handle.0, s.ptr, s.len));
+        }
+        if let Some(ref msgid) = self.msgid {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setMsgId(handle.0, msgid.as_ptr() as *const c_char, msgid.len() as u32));
+        }
+        if let Some(mode) = self.delivery_mode {
+            chkerr!(conn.ctxt,
+                    dpiDeqOptions_setDeliveryMode(handle.0, mode.to_dpi_value()));
+        }
+        Ok(handle)
+    }
+}
+
+//
+// DeliveryMode
+//
+
+/// Delivery mode of messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// Messages are stored in the queue table. This is the default.
+    Persistent,
+    /// Messages are kept in memory and may be lost at instance failure.
+    Buffered,
+    /// Both persistent and buffered messages. This is valid only for dequeue.
+    PersistentOrBuffered,
+}
+
+impl DeliveryMode {
+    fn to_dpi_value(&self) -> dpiMessageDeliveryMode {
+        match *self {
+            DeliveryMode::Persistent => DPI_MODE_MSG_PERSISTENT,
+            DeliveryMode::Buffered => DPI_MODE_MSG_BUFFERED,
+            DeliveryMode::PersistentOrBuffered => DPI_MODE_MSG_PERSISTENT_OR_BUFFERED,
+        }
+    }
+
+    fn from_dpi_value(val: dpiMessageDeliveryMode) -> Result<DeliveryMode> {
+        match val {
+            DPI_MODE_MSG_PERSISTENT => Ok(DeliveryMode::Persistent),
+            DPI_MODE_MSG_BUFFERED => Ok(DeliveryMode::Buffered),
+            DPI_MODE_MSG_PERSISTENT_OR_BUFFERED => Ok(DeliveryMode::PersistentOrBuffered),
+            _ => Err(Error::InternalError(format!("unknown delivery mode {}", val))),
+        }
+    }
+}
+
+//
+// EnqOptions
+//
+
+/// Options to enqueue messages
+///
+/// Options which are not set are left to the defaults of Oracle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnqOptions {
+    visibility: Option<Visibility>,
+    delivery_mode: Option<DeliveryMode>,
+}
+
+impl EnqOptions {
+    /// Creates enqueue options with default values.
+    pub fn new() -> EnqOptions {
+        EnqOptions {
+            visibility: None,
+            delivery_mode: None,
+        }
+    }
+
+    /// Sets whether enqueue is a part of the current transaction.
+    pub fn visibility<'a>(&'a mut self, visibility: Visibility) -> &'a mut EnqOptions {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Sets the delivery mode of enqueued messages.
+    /// Buffered messages must be enqueued with [Visibility::Immediate][]
+    /// and are lost when the instance shuts down.
+    /// [DeliveryMode::PersistentOrBuffered][] is invalid for enqueue.
+    ///
+    /// [Visibility::Immediate]: enum.Visibility.html#variant.Immediate
+    /// [DeliveryMode::PersistentOrBuffered]: enum.DeliveryMode.html#variant.PersistentOrBuffered
+    pub fn delivery_mode<'a>(&'a mut self, mode: DeliveryMode) -> &'a mut EnqOptions {
+        self.delivery_mode = Some(mode);
+        self
+    }
+
+    // PL/SQL statements setting the options to `enq_opts` of
+    // DBMS_AQ.ENQUEUE_OPTIONS_T.
+    fn plsql_assignments(&self) -> String {
+        let mut s = String::new();
+        if let Some(visibility) = self.visibility {
+            s.push_str(&format!("    enq_opts.visibility := {};\n", visibility.to_dpi_value()));
+        }
+        if let Some(mode) = self.delivery_mode {
+            s.push_str(&format!("    enq_opts.delivery_mode := {};\n", mode.to_dpi_value()));
+        }
+        s
+    }
+
+    fn new_handle(&self, conn: &Connection) -> Result<EnqOptionsHandle> {
+        let mut handle = EnqOptionsHandle(ptr::null_mut());
+        chkerr!(conn.ctxt,
+                dpiConn_newEnqOptions(conn.handle, &mut handle.0));
+        if let Some(visibility) = self.visibility {
+            chkerr!(conn.ctxt,
+                    dpiEnqOptions_setVisibility(handle.0, visibility.to_dpi_value()));
+        }
+        if let Some(mode) = self.delivery_mode {
+            chkerr!(conn.ctxt,
+                    dpiEnqOptions_setDeliveryMode(handle.0, mode.to_dpi_value()));
+        }
+        Ok(handle)
+    }
+}
+
+//
+// MessageState
+//
+
+/// State of a message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageState {
+    /// The message is ready to be dequeued.
+    Ready,
+    /// The message is waiting for the delay to expire.
+    Waiting,
+    /// The message has been processed and is retained.
+    Processed,
+    /// The message has been moved to the exception queue.
+    Expired,
+}
+
+impl MessageState {
+    fn from_dpi_value(val: dpiMessageState) -> Result<MessageState> {
+        match val {
+            DPI_MSG_STATE_READY => Ok(MessageState::Ready),
+            DPI_MSG_STATE_WAITING => Ok(MessageState::Waiting),
+            DPI_MSG_STATE_PROCESSED => Ok(MessageState::Processed),
+            DPI_MSG_STATE_EXPIRED => Ok(MessageState::Expired),
+            _ => Err(Error::InternalError(format!("unknown message state {}", val))),
+        }
+    }
+}
+
+//
+// MsgProps
+//
+
+/// Properties of a message
+///
+/// Properties set by setters are sent with messages enqueued by methods
+/// such as [Queue.enqueue_with][]. All properties are available by getters
+/// of messages dequeued by [Queue.dequeue][].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use oracle::*;
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let queue = conn.raw_queue("RAW_QUEUE");
+/// let mut props = MsgProps::new();
+/// props.set_priority(10);
+/// props.set_expiration(3600);
+/// props.set_correlation("order-123");
+/// queue.enqueue_raw_with(b"payload", &props).unwrap();
+/// conn.commit().unwrap();
+/// ```
+///
+/// [Queue.enqueue_with]: struct.Queue.html#method.enqueue_with
+/// [Queue.dequeue]: struct.Queue.html#method.dequeue
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgProps {
+    priority: i32,
+    delay: i32,
+    expiration: i32,
+    correlation: Option<String>,
+    exception_queue: Option<String>,
+    num_attempts: i32,
+    enq_time: Option<Timestamp>,
+    original_msgid: Option<Vec<u8>>,
+    state: Option<MessageState>,
+    delivery_mode: Option<DeliveryMode>,
+    recipients: Vec<String>,
+    // bind variable names of recipients in plsql_assignments()
+    recipient_binds: Vec<String>,
+}
+
+impl MsgProps {
+    /// Creates message properties with default values.
+    pub fn new() -> MsgProps {
+        MsgProps {
+            priority: 1,
+            delay: 0,
+            expiration: -1,
+            correlation: None,
+            exception_queue: None,
+            num_attempts: 0,
+            enq_time: None,
+            original_msgid: None,
+            state: None,
+            delivery_mode: None,
+            recipients: Vec::new(),
+            recipient_binds: Vec::new(),
+        }
+    }
+
+    /// Returns the priority. Smaller numbers have higher priority.
+    /// The default is 1.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Sets the priority.
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// Returns the number of seconds before the message becomes available.
+    /// The default is 0.
+    pub fn delay(&self) -> i32 {
+        self.delay
+    }
+
+    /// Sets the number of seconds before the message becomes available.
+    pub fn set_delay(&mut self, secs: i32) {
+        self.delay = secs;
+    }
+
+    /// Returns the number of seconds during which the message is available
+    /// after the delay. -1, the default, means that it never expires.
+    pub fn expiration(&self) -> i32 {
+        self.expiration
+    }
+
+    /// Sets the number of seconds during which the message is available.
+    pub fn set_expiration(&mut self, secs: i32) {
+        self.expiration = secs;
+    }
+
+    /// Returns the correlation identifier.
+    pub fn correlation(&self) -> Option<&str> {
+        self.correlation.as_ref().map(|s| s.as_str())
+    }
+
+    /// Sets the correlation identifier, which may be used by
+    /// [DeqOptions.correlation][] to select messages.
+    ///
+    /// [DeqOptions.correlation]: struct.DeqOptions.html#method.correlation
+    pub fn set_correlation(&mut self, correlation: &str) {
+        self.correlation = Some(correlation.to_string());
+    }
+
+    /// Returns the name of the queue to which the message is moved when
+    /// it expires or can't be processed.
+    pub fn exception_queue(&self) -> Option<&str> {
+        self.exception_queue.as_ref().map(|s| s.as_str())
+    }
+
+    /// Sets the name of the exception queue.
+    pub fn set_exception_queue(&mut self, name: &str) {
+        self.exception_queue = Some(name.to_string());
+    }
+
+    /// Returns the number of attempts to dequeue the message.
+    pub fn num_attempts(&self) -> i32 {
+        self.num_attempts
+    }
+
+    /// Returns the time when the message was enqueued.
+    /// This is available for dequeued messages.
+    pub fn enq_time(&self) -> Option<&Timestamp> {
+        self.enq_time.as_ref()
+    }
+
+    /// Returns the identifier of the message in the last queue which
+    /// propagated the message. This is available for dequeued messages.
+    pub fn original_msgid(&self) -> Option<&[u8]> {
+        self.original_msgid.as_ref().map(|v| v.as_slice())
+    }
+
+    /// Returns the state of the message. This is available for dequeued
+    /// messages.
+    pub fn state(&self) -> Option<MessageState> {
+        self.state
+    }
+
+    /// Returns the delivery mode of the message. This is available for
+    /// dequeued messages.
+    pub fn delivery_mode(&self) -> Option<DeliveryMode> {
+        self.delivery_mode
+    }
+
+    /// Returns the recipients set by [set_recipients](#method.set_recipients).
+    pub fn recipients(&self) -> &[String] {
+        &self.recipients
+    }
+
+    /// Sets the consumer names of a multi-consumer queue which receive
+    /// the message instead of the subscribers of the queue.
+    ///
+    /// Messages with recipients are enqueued by `DBMS_AQ` PL/SQL package
+    /// because the bundled ODPI-C doesn't support recipient lists.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let queue = conn.raw_queue("MULTI_RAW_QUEUE");
+    /// let mut props = MsgProps::new();
+    /// props.set_recipients(&["BILLING", "SHIPPING"]);
+    /// queue.enqueue_raw_with(b"order 123", &props).unwrap();
+    /// conn.commit().unwrap();
+    ///
+    /// let mut queue = conn.raw_queue("MULTI_RAW_QUEUE");
+    /// queue.deq_options_mut().consumer_name("SHIPPING");
+    /// let msg = queue.dequeue().unwrap();
+    /// ```
+    pub fn set_recipients(&mut self, recipients: &[&str]) {
+        self.recipients = recipients.iter().map(|name| name.to_string()).collect();
+        self.recipient_binds = (1..(recipients.len() + 1)).map(|i| format!("msg_recipient{}", i)).collect();
+    }
+
+    // PL/SQL statements setting the properties to `props` of
+    // DBMS_AQ.MESSAGE_PROPERTIES_T.
+    fn plsql_assignments<'a>(&'a self, params: &mut Vec<(&'a str, &'a ToSql)>) -> String {
+        let mut s = format!("    props.priority := {};\n    props.delay := {};\n    props.expiration := {};\n",
+                            self.priority, self.delay, self.expiration);
+        if let Some(ref correlation) = self.correlation {
+            s.push_str("    props.correlation := :msg_correlation;\n");
+            params.push(("msg_correlation", correlation));
+        }
+        if let Some(ref name) = self.exception_queue {
+            s.push_str("    props.exception_queue := :msg_exception_queue;\n");
+            params.push(("msg_exception_queue", name));
+        }
+        if !self.recipients.is_empty() {
+            let agents: Vec<String> = self.recipient_binds.iter()
+                .map(|bind| format!("sys.aq$_agent(:{}, null, null)", bind))
+                .collect();
+            s.push_str(&format!("    props.recipient_list := sys.aq$_recipient_list_t({});\n", agents.join(", ")));
+            for (bind, name) in self.recipient_binds.iter().zip(self.recipients.iter()) {
+                params.push((bind, name));
+            }
+        }
+        s
+    }
+
+    fn new_handle(&self, conn: &Connection) -> Result<MsgPropsHandle> {
+        let mut handle = MsgPropsHandle(ptr::null_mut());
+        chkerr!(conn.ctxt,
+                dpiConn_newMsgProps(conn.handle, &mut handle.0));
+        chkerr!(conn.ctxt,
+                dpiMsgProps_setPriority(handle.0, self.priority));
+        chkerr!(conn.ctxt,
+                dpiMsgProps_setDelay(handle.0, self.delay));
+        chkerr!(conn.ctxt,
+                dpiMsgProps_setExpiration(handle.0, self.expiration));
+        if let Some(ref correlation) = self.correlation {
+            let s = to_odpi_str(correlation);
+            chkerr!(conn.ctxt,
+                    dpiMsgProps_setCorrelation(handle.0, s.ptr, s.len));
+        }
+        if let Some(ref name) = self.exception_queue {
+            let s = to_odpi_str(name);
+            chkerr!(conn.ctxt,
+                    dpiMsgProps_setExceptionQ(handle.0, s.ptr, s.len));
+        }
+        Ok(handle)
+    }
+
+    fn from_handle(ctxt: &'static Context, handle: *mut dpiMsgProps) -> Result<MsgProps> {
+        let mut props = MsgProps::new();
+        let mut s = new_odpi_str();
+        let mut ts = dpiTimestamp {
+            year: 0, month: 0, day: 0, hour: 0, minute: 0, second: 0,
+            fsecond: 0, tzHourOffset: 0, tzMinuteOffset: 0,
+        };
+        let mut state = 0;
+        chkerr!(ctxt,
+                dpiMsgProps_getPriority(handle, &mut props.priority));
+        chkerr!(ctxt,
+                dpiMsgProps_getDelay(handle, &mut props.delay));
+        chkerr!(ctxt,
+                dpiMsgProps_getExpiration(handle, &mut props.expiration));
+        chkerr!(ctxt,
+                dpiMsgProps_getCorrelation(handle, &mut s.ptr, &mut s.len));
+        props.correlation = to_opt_string(&s);
+        chkerr!(ctxt,
+                dpiMsgProps_getExceptionQ(handle, &mut s.ptr, &mut s.len));
+        props.exception_queue = to_opt_string(&s);
+        chkerr!(ctxt,
+                dpiMsgProps_getNumAttempts(handle, &mut props.num_attempts));
+        chkerr!(ctxt,
+                dpiMsgProps_getEnqTime(handle, &mut ts));
+        props.enq_time = Some(Timestamp::from_dpi_timestamp(&ts, &OracleType::Date));
+        chkerr!(ctxt,
+                dpiMsgProps_getOriginalMsgId(handle, &mut s.ptr, &mut s.len));
+        if !s.ptr.is_null() && s.len > 0 {
+            props.original_msgid = Some(to_msgid(s.ptr, s.len));
+        }
+        chkerr!(ctxt,
+                dpiMsgProps_getState(handle, &mut state));
+        props.state = Some(MessageState::from_dpi_value(state)?);
+        let mut mode = 0;
+        chkerr!(ctxt,
+                dpiMsgProps_getDeliveryMode(handle, &mut mode));
+        props.delivery_mode = Some(DeliveryMode::from_dpi_value(mode)?);
+        Ok(props)
+    }
+
+    // PL/SQL statements getting `props` of DBMS_AQ.MESSAGE_PROPERTIES_T
+    // to out bind variables suffixed with `suffix`, which are read by
+    // `from_plsql()`.
+    fn plsql_out_assignments(suffix: &str) -> String {
+        MSG_PROPS_OUT_BINDS.iter()
+            .map(|&(name, field)| format!("    :{}{} := props.{};\n", name, suffix, field))
+            .collect()
+    }
+
+    fn from_plsql(stmt: &Statement, suffix: &str) -> Result<MsgProps> {
+        let name = |name: &str| format!("{}{}", name, suffix);
+        let state: i32 = stmt.bind_value(&name("msg_state")[..])?;
+        let mode: i32 = stmt.bind_value(&name("msg_delivery_mode")[..])?;
+        Ok(MsgProps {
+            priority: stmt.bind_value(&name("msg_priority")[..])?,
+            delay: stmt.bind_value(&name("msg_delay")[..])?,
+            expiration: stmt.bind_value(&name("msg_expiration")[..])?,
+            correlation: stmt.bind_value(&name("msg_correlation")[..])?,
+            exception_queue: stmt.bind_value(&name("msg_exception_queue")[..])?,
+            num_attempts: stmt.bind_value(&name("msg_attempts")[..])?,
+            enq_time: stmt.bind_value(&name("msg_enq_time")[..])?,
+            original_msgid: stmt.bind_value(&name("msg_original_msgid")[..])?,
+            state: Some(MessageState::from_dpi_value(state as dpiMessageState)?),
+            delivery_mode: Some(DeliveryMode::from_dpi_value(mode as dpiMessageDeliveryMode)?),
+            recipients: Vec::new(),
+            recipient_binds: Vec::new(),
+        })
+    }
+}
+
+fn to_opt_string(s: &OdpiStr) -> Option<String> {
+    if s.ptr.is_null() || s.len == 0 {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+// Names of out bind variables read by MsgProps::from_plsql() and fields
+// of DBMS_AQ.MESSAGE_PROPERTIES_T set to them
+const MSG_PROPS_OUT_BINDS: [(&str, &str); 10] = [
+    ("msg_priority", "priority"),
+    ("msg_delay", "delay"),
+    ("msg_expiration", "expiration"),
+    ("msg_correlation", "correlation"),
+    ("msg_exception_queue", "exception_queue"),
+    ("msg_attempts", "attempts"),
+    ("msg_enq_time", "enqueue_time"),
+    ("msg_original_msgid", "original_msgid"),
+    ("msg_state", "state"),
+    ("msg_delivery_mode", "delivery_mode"),
+];
+
+// Out bind variables read by MsgProps::from_plsql()
+struct MsgPropsOutBinds {
+    names: Vec<String>,
+    number: OracleType,
+    varchar: OracleType,
+    date: OracleType,
+    raw: OracleType,
+}
+
+impl MsgPropsOutBinds {
+    fn new(suffix: &str) -> MsgPropsOutBinds {
+        MsgPropsOutBinds {
+            names: MSG_PROPS_OUT_BINDS.iter().map(|&(name, _)| format!("{}{}", name, suffix)).collect(),
+            number: OracleType::Int64,
+            varchar: OracleType::Varchar2(128),
+            date: OracleType::Date,
+            raw: OracleType::Raw(16),
+        }
+    }
+
+    fn push_params<'a>(&'a self, params: &mut Vec<(&'a str, &'a ToSql)>) {
+        // in the order of MSG_PROPS_OUT_BINDS
+        let types: [&'a ToSql; 10] = [
+            &self.number, &self.number, &self.number, &self.varchar, &self.varchar,
+            &self.number, &self.date, &self.raw, &self.number, &self.number,
+        ];
+        for (name, oratype) in self.names.iter().zip(types.iter()) {
+            params.push((name, *oratype));
+        }
+    }
+}
+
+struct DeqOptionsHandle(*mut dpiDeqOptions);
+
+impl Drop for DeqOptionsHandle {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { dpiDeqOptions_release(self.0) };
+        }
+    }
+}
+
+struct EnqOptionsHandle(*mut dpiEnqOptions);
+
+impl Drop for EnqOptionsHandle {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { dpiEnqOptions_release(self.0) };
+        }
+    }
+}
+
+struct MsgPropsHandle(*mut dpiMsgProps);
+
+impl Drop for MsgPropsHandle {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { dpiMsgProps_release(self.0) };
+        }
+    }
+}
+
+//
+// Payload
+//
+
+/// Payload of a message
+#[derive(Debug)]
+pub enum Payload {
+    /// Object of the payload type of the queue
+    Object(Object),
+    /// Bytes of a RAW queue
+    Raw(Vec<u8>),
+    /// JSON of a JSON queue, available with `serde_json` feature
+    #[cfg(feature = "serde_json")]
+    Json(Value),
+}
+
+impl Payload {
+    /// Returns the object when the payload is an object.
+    pub fn as_object(&self) -> Option<&Object> {
+        match *self {
+            Payload::Object(ref obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes when the payload is RAW.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match *self {
+            Payload::Raw(ref bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON value when the payload is JSON.
+    #[cfg(feature = "serde_json")]
+    pub fn as_json(&self) -> Option<&Value> {
+        match *self {
+            Payload::Json(ref value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Payload types enqueued by [Queue.enqueue_many][]
+///
+/// This is implemented for [Object][] of object queues, `Vec<u8>` and
+/// `&[u8]` of RAW queues and `serde_json::Value` of JSON queues when
+/// `serde_json` feature is enabled.
+///
+/// [Queue.enqueue_many]: struct.Queue.html#method.enqueue_many
+/// [Object]: struct.Object.html
+pub trait EnqPayload: ToSql {
+    #[doc(hidden)]
+    fn payload_type(&self) -> PayloadType;
+}
+
+impl EnqPayload for Object {
+    fn payload_type(&self) -> PayloadType {
+        PayloadType::Object(self.object_type().clone())
+    }
+}
+
+impl EnqPayload for Vec<u8> {
+    fn payload_type(&self) -> PayloadType {
+        PayloadType::Raw
+    }
+}
+
+impl<'a> EnqPayload for &'a [u8] {
+    fn payload_type(&self) -> PayloadType {
+        PayloadType::Raw
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl EnqPayload for Value {
+    fn payload_type(&self) -> PayloadType {
+        PayloadType::Json
+    }
+}
+
+// Payload type of a queue. This is public only to be used in EnqPayload,
+// which is outside of this module.
+#[doc(hidden)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayloadType {
+    Object(ObjectType),
+    Raw,
+    #[cfg(feature = "serde_json")]
+    Json,
+}
+
+impl PayloadType {
+    fn name(&self) -> String {
+        match *self {
+            PayloadType::Object(ref objtype) => objtype.to_string(),
+            PayloadType::Raw => "RAW".to_string(),
+            #[cfg(feature = "serde_json")]
+            PayloadType::Json => "JSON".to_string(),
+        }
+    }
+
+    // Returns PL/SQL declarations and statements to convert a payload
+    // bound to `:payload` and an expression passed to DBMS_AQ.ENQUEUE.
+    fn enqueue_plsql(&self) -> (&'static str, &'static str, &'static str) {
+        match *self {
+            PayloadType::Object(_) | PayloadType::Raw =>
+                ("", "", ":payload"),
+            #[cfg(feature = "serde_json")]
+            PayloadType::Json =>
+                ("    payload json;\n",
+                 "    select json(:payload) into payload from dual;\n",
+                 "payload"),
+        }
+    }
+}
+
+//
+// Message
+//
+
+/// A message dequeued by [Queue.dequeue][]
+///
+/// [Queue.dequeue]: struct.Queue.html#method.dequeue
+#[derive(Debug)]
+pub struct Message {
+    payload: Payload,
+    msgid: Vec<u8>,
+    props: MsgProps,
+}
+
+impl Message {
+    /// Returns the payload of the message.
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    /// Returns the payload of the message, consuming the message.
+    pub fn into_payload(self) -> Payload {
+        self.payload
+    }
+
+    /// Returns the message identifier.
+    pub fn msgid(&self) -> &[u8] {
+        &self.msgid
+    }
+
+    /// Returns the message properties.
+    pub fn props(&self) -> &MsgProps {
+        &self.props
+    }
+}
+
+fn to_msgid(ptr: *const c_char, len: u32) -> Vec<u8> {
+    if ptr.is_null() {
+        Vec::new()
+    } else {
+        unsafe { slice::from_raw_parts(ptr as *const u8, len as usize) }.to_vec()
+    }
+}
+
+//
+// Queue
+//
+
+// The maximum number of messages enqueued or dequeued by a PL/SQL block
+const MAX_MESSAGES_PER_CALL: usize = 100;
+
+/// Advanced Queuing (AQ) queue
+///
+/// Payloads are objects of an object type, created by
+/// [Connection.queue][], bytes, created by [Connection.raw_queue][],
+/// or JSON values, created by [Connection.json_queue][] when `serde_json`
+/// feature is enabled.
+///
+/// Messages are enqueued and dequeued in the current transaction of
+/// the connection by default. Commit the connection to make enqueued
+/// messages visible to consumers and to remove dequeued messages.
+///
+/// RAW and JSON payloads are enqueued and dequeued by `DBMS_AQ` PL/SQL
+/// package because the bundled ODPI-C supports object payloads only.
+/// So are messages of [enqueue_many][] and [dequeue_many][] to send several
+/// messages in one round trip.
+/// RAW payloads are limited to 32767 bytes. JSON payloads need
+/// Transactional Event Queues of Oracle 21c or later.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use oracle::*;
+/// // create type udt_book as object (title varchar2(100), price number);
+/// // exec dbms_aqadm.create_queue_table('BOOK_QUEUE_TAB', 'UDT_BOOK');
+/// // exec dbms_aqadm.create_queue('BOOKS', 'BOOK_QUEUE_TAB');
+/// // exec dbms_aqadm.start_queue('BOOKS');
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let objtype = conn.object_type("UDT_BOOK").unwrap();
+/// let mut queue = conn.queue("BOOKS", &objtype);
+///
+/// let mut book = objtype.new_object().unwrap();
+/// book.set("TITLE", &"The Rust Programming Language").unwrap();
+/// book.set("PRICE", &39.95).unwrap();
+/// queue.enqueue(&book).unwrap();
+/// conn.commit().unwrap();
+///
+/// queue.deq_options_mut().wait(0);
+/// while let Some(msg) = queue.dequeue().unwrap() {
+///     let book = msg.payload().as_object().unwrap();
+///     let title: String = book.get("TITLE").unwrap();
+///     println!("{}", title);
+/// }
+/// conn.commit().unwrap();
+/// ```
+///
+/// [Connection.queue]: struct.Connection.html#method.queue
+/// [Connection.raw_queue]: struct.Connection.html#method.raw_queue
+/// [Connection.json_queue]: struct.Connection.html#method.json_queue
+/// [enqueue_many]: #method.enqueue_many
+/// [dequeue_many]: #method.dequeue_many
+pub struct Queue<'conn> {
+    conn: &'conn Connection,
+    name: String,
+    payload_type: PayloadType,
+    enq_options: EnqOptions,
+    deq_options: DeqOptions,
+}
+
+impl<'conn> Queue<'conn> {
+    pub(crate) fn new(conn: &'conn Connection, name: &str, payload_type: &ObjectType) -> Queue<'conn> {
+        Queue::with_payload_type(conn, name, PayloadType::Object(payload_type.clone()))
+    }
+
+    pub(crate) fn new_raw(conn: &'conn Connection, name: &str) -> Queue<'conn> {
+        Queue::with_payload_type(conn, name, PayloadType::Raw)
+    }
+
+    #[cfg(feature = "serde_json")]
+    pub(crate) fn new_json(conn: &'conn Connection, name: &str) -> Queue<'conn> {
+        Queue::with_payload_type(conn, name, PayloadType::Json)
+    }
+
+    fn with_payload_type(conn: &'conn Connection, name: &str, payload_type: PayloadType) -> Queue<'conn> {
+        Queue {
+            conn: conn,
+            name: name.to_string(),
+            payload_type: payload_type,
+            enq_options: EnqOptions::new(),
+            deq_options: DeqOptions::new(),
+        }
+    }
+
+    /// Returns the queue name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the object type of payloads. This returns `None` for
+    /// RAW and JSON queues.
+    pub fn payload_type(&self) -> Option<&ObjectType> {
+        match self.payload_type {
+            PayloadType::Object(ref objtype) => Some(objtype),
+            _ => None,
+        }
+    }
+
+    /// Returns options used by [enqueue](#method.enqueue).
+    pub fn enq_options(&self) -> &EnqOptions {
+        &self.enq_options
+    }
+
+    /// Returns mutable options used by [enqueue](#method.enqueue).
+    pub fn enq_options_mut(&mut self) -> &mut EnqOptions {
+        &mut self.enq_options
+    }
+
+    /// Returns options used by [dequeue](#method.dequeue).
+    pub fn deq_options(&self) -> &DeqOptions {
+        &self.deq_options
+    }
+
+    /// Returns mutable options used by [dequeue](#method.dequeue).
+    pub fn deq_options_mut(&mut self) -> &mut DeqOptions {
+        &mut self.deq_options
+    }
+
+    fn ctxt(&self) -> &'static Context {
+        self.conn.ctxt
+    }
+
+    fn check_payload_type(&self, payload_type: &PayloadType) -> Result<()> {
+        if *payload_type != self.payload_type {
+            return Err(Error::InvalidTypeConversion(payload_type.name(), self.payload_type.name()));
+        }
+        Ok(())
+    }
+
+    /// Enqueues a message and returns its message identifier.
+    ///
+    /// The object type of `payload` must be the payload type of the queue.
+    pub fn enqueue(&self, payload: &Object) -> Result<Vec<u8>> {
+        self.enqueue_with(payload, &MsgProps::new())
+    }
+
+    /// Enqueues a message with message properties and returns its
+    /// message identifier.
+    pub fn enqueue_with(&self, payload: &Object, props: &MsgProps) -> Result<Vec<u8>> {
+        self.check_payload_type(&PayloadType::Object(payload.object_type().clone()))?;
+        if !props.recipients.is_empty() {
+            return self.enqueue_plsql(props, vec![("payload", payload)]);
+        }
+        let options = self.enq_options.new_handle(self.conn)?;
+        let props = props.new_handle(self.conn)?;
+        let name = to_odpi_str(&self.name);
+        let mut msgid = ptr::null();
+        let mut msgid_len = 0;
+        chkerr!(self.ctxt(),
+                dpiConn_enqObject(self.conn.handle, name.ptr, name.len, options.0, props.0,
+                                  payload.handle, &mut msgid, &mut msgid_len));
+        Ok(to_msgid(msgid, msgid_len))
+    }
+
+    /// Enqueues messages and returns their message identifiers.
+    ///
+    /// Payloads are objects of the payload type for object queues,
+    /// bytes for RAW queues and JSON values for JSON queues. See
+    /// [EnqPayload](trait.EnqPayload.html).
+    ///
+    /// Messages are enqueued by a PL/SQL block calling `DBMS_AQ.ENQUEUE`
+    /// for each message, which needs one round trip per 100 messages.
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let queue = conn.raw_queue("RAW_QUEUE");
+    /// let payloads: Vec<&[u8]> = vec![b"message 1", b"message 2"];
+    /// let msgids = queue.enqueue_many(&payloads).unwrap();
+    /// conn.commit().unwrap();
+    /// ```
+    pub fn enqueue_many<'a, I, P>(&self, payloads: I) -> Result<Vec<Vec<u8>>> where I: IntoIterator<Item = &'a P>, P: 'a + EnqPayload {
+        let payloads: Vec<&P> = payloads.into_iter().collect();
+        for payload in &payloads {
+            self.check_payload_type(&payload.payload_type())?;
+        }
+        let mut msgids = Vec::with_capacity(payloads.len());
+        for chunk in payloads.chunks(MAX_MESSAGES_PER_CALL) {
+            msgids.extend(self.enqueue_many_plsql(chunk)?);
+        }
+        Ok(msgids)
+    }
+
+    /// Enqueues a message to a RAW queue and returns its message identifier.
+    pub fn enqueue_raw(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        self.enqueue_raw_with(payload, &MsgProps::new())
+    }
+
+    /// Enqueues a message with message properties to a RAW queue and
+    /// returns its message identifier.
+    pub fn enqueue_raw_with(&self, payload: &[u8], props: &MsgProps) -> Result<Vec<u8>> {
+        self.check_payload_type(&PayloadType::Raw)?;
+        self.enqueue_plsql(props, vec![("payload", &payload)])
+    }
+
+    /// Enqueues a message to a JSON queue and returns its message identifier.
+    #[cfg(feature = "serde_json")]
+    pub fn enqueue_json(&self, payload: &Value) -> Result<Vec<u8>> {
+        self.enqueue_json_with(payload, &MsgProps::new())
+    }
+
+    /// Enqueues a message with message properties to a JSON queue and
+    /// returns its message identifier.
+    #[cfg(feature = "serde_json")]
+    pub fn enqueue_json_with(&self, payload: &Value, props: &MsgProps) -> Result<Vec<u8>> {
+        self.check_payload_type(&PayloadType::Json)?;
+        self.enqueue_plsql(props, vec![("payload", payload)])
+    }
+
+    // Enqueues a message by DBMS_AQ.ENQUEUE. The payload is bound
+    // to `:payload` in `params`.
+    fn enqueue_plsql<'a>(&'a self, props: &'a MsgProps,
+                         params: Vec<(&'a str, &'a ToSql)>) -> Result<Vec<u8>> {
+        let (decl, prepare, payload) = self.payload_type.enqueue_plsql();
+        let msgid = OracleType::Raw(16);
+        let mut params: Vec<(&str, &ToSql)> = params;
+        params.push(("name", &self.name));
+        params.push(("msgid", &msgid));
+        let sql = format!("\
+declare
+    enq_opts dbms_aq.enqueue_options_t;
+    props dbms_aq.message_properties_t;
+{}begin
+{}{}{}    dbms_aq.enqueue(:name, enq_opts, props, {}, :msgid);
+end;", decl, self.enq_options.plsql_assignments(), props.plsql_assignments(&mut params), prepare, payload);
+        let stmt = self.conn.execute_named_internal(&sql, &params)?;
+        stmt.bind_value("msgid")
+    }
+
+    // Enqueues messages by a PL/SQL block calling DBMS_AQ.ENQUEUE for each
+    // message. Bind and PL/SQL variables of the n-th message are suffixed
+    // with `_n`.
+    fn enqueue_many_plsql<P>(&self, payloads: &[&P]) -> Result<Vec<Vec<u8>>> where P: EnqPayload {
+        let (decl, prepare, payload) = self.payload_type.enqueue_plsql();
+        let msgid = OracleType::Raw(16);
+        let props = MsgProps::new();
+        let payload_names: Vec<String> = (1..payloads.len() + 1).map(|n| format!("payload_{}", n)).collect();
+        let msgid_names: Vec<String> = (1..payloads.len() + 1).map(|n| format!("msgid_{}", n)).collect();
+        let mut params: Vec<(&str, &ToSql)> = vec![("name", &self.name)];
+        let mut decls = String::new();
+        let mut enqueues = String::new();
+        for i in 0..payloads.len() {
+            let name = &payload_names[i][..];
+            decls.push_str(&decl.replace("payload", name));
+            enqueues.push_str(&prepare.replace("payload", name));
+            enqueues.push_str(&format!("    dbms_aq.enqueue(:name, enq_opts, props, {}, :{});\n",
+                                       payload.replace("payload", name), msgid_names[i]));
+            params.push((name, payloads[i]));
+            params.push((&msgid_names[i], &msgid));
+        }
+        let sql = format!("\
+declare
+    enq_opts dbms_aq.enqueue_options_t;
+    props dbms_aq.message_properties_t;
+{}begin
+{}{}{}end;", decls, self.enq_options.plsql_assignments(), props.plsql_assignments(&mut params), enqueues);
+        let stmt = self.conn.execute_named_internal(&sql, &params)?;
+        msgid_names.iter().map(|name| stmt.bind_value(&name[..])).collect()
+    }
+
+    /// Dequeues a message. This returns `None` when no message is available
+    /// within the wait time set by [DeqOptions.wait][].
+    ///
+    /// [DeqOptions.wait]: struct.DeqOptions.html#method.wait
+    pub fn dequeue(&self) -> Result<Option<Message>> {
+        self.dequeue_with(&self.deq_options)
+    }
+
+    /// Dequeues at most `max_messages` messages.
+    ///
+    /// This waits for the first message as [dequeue](#method.dequeue)
+    /// and then dequeues messages already available without waiting.
+    /// Messages after the first one are dequeued with
+    /// [DeqNavigation::NextMessage][], so that messages are read one by
+    /// one also in [DeqMode::Browse][].
+    /// Messages are dequeued by a PL/SQL block calling `DBMS_AQ.DEQUEUE`
+    /// for each message, which needs one round trip per 100 messages.
+    ///
+    /// [DeqNavigation::NextMessage]: enum.DeqNavigation.html#variant.NextMessage
+    /// [DeqMode::Browse]: enum.DeqMode.html#variant.Browse
+    pub fn dequeue_many(&self, max_messages: usize) -> Result<Vec<Message>> {
+        let mut msgs = Vec::new();
+        let mut options = self.deq_options.clone();
+        while msgs.len() < max_messages {
+            let num = cmp::min(max_messages - msgs.len(), MAX_MESSAGES_PER_CALL);
+            let chunk = self.dequeue_plsql(&options, num)?;
+            let done = chunk.len() < num;
+            msgs.extend(chunk);
+            if done {
+                break;
+            }
+            options.wait(0).navigation(DeqNavigation::NextMessage);
+        }
+        Ok(msgs)
+    }
+
+    /// Dequeues a message and calls `f` with it. The dequeue is
+    /// committed when `f` returns `Ok` and rolled back otherwise,
+    /// so that the message is removed only when it is processed.
+    /// This returns `Ok(None)` without calling `f` when no message is
+    /// available within the wait time.
+    ///
+    /// Other changes in the connection's transaction, such as DML
+    /// executed in `f`, are committed or rolled back together. The dequeue
+    /// must not be done with [Visibility::Immediate][].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let queue = conn.raw_queue("ORDERS");
+    /// loop {
+    ///     let res: Result<Option<()>> = queue.process(|msg| {
+    ///         let order = msg.payload().as_bytes().unwrap();
+    ///         conn.execute("insert into order_log values (:1)", &[&order])?;
+    ///         Ok(())
+    ///     });
+    ///     match res {
+    ///         Ok(Some(())) => (),
+    ///         Ok(None) => break,
+    ///         Err(err) => println!("failed to process a message: {}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [Visibility::Immediate]: enum.Visibility.html#variant.Immediate
+    pub fn process<F, T, E>(&self, f: F) -> result::Result<Option<T>, E> where F: FnOnce(&Message) -> result::Result<T, E>, E: From<Error> {
+        let msg = match self.dequeue()? {
+            Some(msg) => msg,
+            None => return Ok(None),
+        };
+        match f(&msg) {
+            Ok(val) => {
+                self.conn.commit()?;
+                Ok(Some(val))
+            },
+            Err(err) => {
+                // The error of `f` is more useful than that of rollback.
+                let _ = self.conn.rollback();
+                Err(err)
+            },
+        }
+    }
+
+    fn dequeue_with(&self, options: &DeqOptions) -> Result<Option<Message>> {
+        match self.payload_type {
+            PayloadType::Object(ref objtype) => self.dequeue_object(objtype, options),
+            _ => Ok(self.dequeue_plsql(options, 1)?.pop()),
+        }
+    }
+
+    fn dequeue_object(&self, objtype: &ObjectType, options: &DeqOptions) -> Result<Option<Message>> {
+        let options = options.new_handle(self.conn)?;
+        let mut props = MsgPropsHandle(ptr::null_mut());
+        chkerr!(self.ctxt(),
+                dpiConn_newMsgProps(self.conn.handle, &mut props.0));
+        let payload = match objtype.new_object() {
+            Some(obj) => obj,
+            None => return Err(Error::InternalError(format!("failed to create an object of {}", objtype))),
+        };
+        let name = to_odpi_str(&self.name);
+        let mut msgid = ptr::null();
+        let mut msgid_len = 0;
+        chkerr!(self.ctxt(),
+                dpiConn_deqObject(self.conn.handle, name.ptr, name.len, options.0, props.0,
+                                  payload.handle, &mut msgid, &mut msgid_len));
+        if msgid.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(Message {
+            payload: Payload::Object(payload),
+            msgid: to_msgid(msgid, msgid_len),
+            props: MsgProps::from_handle(self.ctxt(), props.0)?,
+        }))
+    }
+
+    // Dequeues at most `num` messages by a PL/SQL block calling
+    // DBMS_AQ.DEQUEUE for each message. Messages after the first one are
+    // dequeued without waiting and from the position next to the previous
+    // one. Bind variables of the n-th message are suffixed with `_n`.
+    fn dequeue_plsql(&self, options: &DeqOptions, num: usize) -> Result<Vec<Message>> {
+        // `decl` declares PL/SQL variables, `payload` is passed to
+        // DBMS_AQ.DEQUEUE and `convert` sets it to `:payload`.
+        let (decl, payload, convert, payload_type) = match self.payload_type {
+            PayloadType::Object(ref objtype) =>
+                ("", ":payload", "", OracleType::Object(objtype.clone())),
+            PayloadType::Raw =>
+                ("", ":payload", "", OracleType::Raw(32767)),
+            #[cfg(feature = "serde_json")]
+            PayloadType::Json =>
+                ("    payload json;\n", "payload",
+                 "    select json_serialize(payload returning clob) into :payload from dual;\n",
+                 OracleType::CLOB),
+        };
+        let msgid = OracleType::Raw(16);
+        let suffixes: Vec<String> = (1..num + 1).map(|n| format!("_{}", n)).collect();
+        let payload_names: Vec<String> = suffixes.iter().map(|suffix| format!("payload{}", suffix)).collect();
+        let msgid_names: Vec<String> = suffixes.iter().map(|suffix| format!("msgid{}", suffix)).collect();
+        let props: Vec<MsgPropsOutBinds> = suffixes.iter().map(|suffix| MsgPropsOutBinds::new(suffix)).collect();
+        let mut params: Vec<(&str, &ToSql)> = vec![("name", &self.name)];
+        let assignments = options.plsql_assignments(&mut params);
+        let mut dequeues = String::new();
+        for i in 0..num {
+            if i == 1 {
+                dequeues.push_str("    deq_opts.wait := 0;\n");
+                dequeues.push_str("    deq_opts.navigation := dbms_aq.next_message;\n");
+            }
+            let bind = format!(":{}", payload_names[i]);
+            dequeues.push_str(&format!("    dbms_aq.dequeue(:name, deq_opts, props, {}, :{});\n",
+                                       payload.replace(":payload", &bind), msgid_names[i]));
+            dequeues.push_str(&convert.replace(":payload", &bind));
+            dequeues.push_str(&MsgProps::plsql_out_assignments(&suffixes[i]));
+            params.push((&payload_names[i], &payload_type));
+            params.push((&msgid_names[i], &msgid));
+            props[i].push_params(&mut params);
+        }
+        // Out bind variables of messages not dequeued are left null.
+        let sql = format!("\
+declare
+    deq_opts dbms_aq.dequeue_options_t;
+    props dbms_aq.message_properties_t;
+{}    no_messages exception;
+    pragma exception_init(no_messages, -25228);
+begin
+{}{}exception
+    when no_messages then
+        null;
+end;", decl, assignments, dequeues);
+        let stmt = self.conn.execute_named_internal(&sql, &params)?;
+        let mut msgs = Vec::new();
+        for i in 0..num {
+            let msgid: Option<Vec<u8>> = stmt.bind_value(&msgid_names[i][..])?;
+            let msgid = match msgid {
+                Some(msgid) => msgid,
+                None => break,
+            };
+            let name = &payload_names[i][..];
+            let payload = match self.payload_type {
+                PayloadType::Object(_) =>
+                    Payload::Object(stmt.bind_value(name)?),
+                PayloadType::Raw => {
+                    let payload: Option<Vec<u8>> = stmt.bind_value(name)?;
+                    Payload::Raw(payload.unwrap_or_default())
+                },
+                #[cfg(feature = "serde_json")]
+                PayloadType::Json => {
+                    let payload: Option<Value> = stmt.bind_value(name)?;
+                    Payload::Json(payload.unwrap_or(Value::Null))
+                },
+            };
+            msgs.push(Message {
+                payload: payload,
+                msgid: msgid,
+                props: MsgProps::from_plsql(&stmt, &suffixes[i])?,
+            });
+        }
+        Ok(msgs)
+    }
+}
@@ -0,0 +1,356 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cmp;
+use std::ptr;
+
+use binding::*;
+
+use Connection;
+use Error;
+use OracleType;
+use Result;
+use SqlValue;
+use Statement;
+use ToSql;
+
+use OdpiStr;
+use to_odpi_str;
+use error::ErrorContext;
+
+//
+// Batch
+//
+
+/// Bulk DML executor which sends rows to the server in chunks
+///
+/// Rows appended by [append_row][] are buffered in array bind variables
+/// and sent to the server by one round-trip per `batch_size` rows.
+/// Call [execute][] at the end to send rows remaining in the buffer.
+/// Rows which aren't sent by [execute][] are discarded when the batch
+/// is dropped.
+///
+/// When sending rows fails, rows processed before the failing row are
+/// removed from the buffer and the failing row and rows after it are kept.
+/// They are never sent again automatically. Send them again by [execute][]
+/// or discard them by [clear][]. The failing row is identified by
+/// [sent_rows][].
+///
+/// In [autocommit mode][], each chunk is committed when it is sent. Rows
+/// sent before a failed chunk are not rolled back. Disable autocommit and
+/// commit at the end to insert all rows or none.
+///
+/// # Examples
+///
+/// ```no_run
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut batch = conn.batch("insert into emp(empno, ename) values (:1, :2)", 100).unwrap();
+///
+/// for i in 0..1000 {
+///     let name = format!("EMP{}", i);
+///     batch.append_row(&[&i, &name]).unwrap();
+/// }
+/// // send remaining rows
+/// batch.execute().unwrap();
+/// conn.commit().unwrap();
+/// ```
+///
+/// [append_row]: #method.append_row
+/// [execute]: #method.execute
+/// [clear]: #method.clear
+/// [sent_rows]: #method.sent_rows
+/// [autocommit mode]: struct.Connection.html#method.set_autocommit
+pub struct Batch<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiStmt,
+    sql: String,
+    batch_size: u32,
+    bind_count: usize,
+    bind_values: Vec<SqlValue>,
+    row_index: u32,
+    sent_rows: u64,
+    send_failed: bool,
+    binary_float_bind: bool,
+}
+
+impl<'conn> Batch<'conn> {
+
+    pub(crate) fn new(conn: &'conn Connection, sql: &str, batch_size: u32) -> Result<Batch<'conn>> {
+        let batch_size = if batch_size == 0 { 1 } else { batch_size };
+        let sql_string = sql.to_string();
+        let sql = to_odpi_str(sql);
+        let mut handle: *mut dpiStmt = ptr::null_mut();
+        Batch::prepare(conn, &sql, &mut handle)
+            .context(|| format!("preparing \"{}\"", sql_string))?;
+        let mut num = 0;
+        chkerr!(conn.ctxt,
+                dpiStmt_getBindCount(handle, &mut num),
+                unsafe { dpiStmt_release(handle); });
+        let bind_count = num as usize;
+        Ok(Batch {
+            conn: conn,
+            handle: handle,
+            sql: sql_string,
+            batch_size: batch_size,
+            bind_count: bind_count,
+            bind_values: vec![SqlValue::new(conn.ctxt); bind_count],
+            row_index: 0,
+            sent_rows: 0,
+            send_failed: false,
+            binary_float_bind: conn.binary_float_bind(),
+        })
+    }
+
+    fn prepare(conn: &Connection, sql: &OdpiStr, handle: &mut *mut dpiStmt) -> Result<()> {
+        chkerr!(conn.ctxt,
+                dpiConn_prepareStmt(conn.handle, 0, sql.ptr, sql.len,
+                                    ptr::null(), 0, handle));
+        Ok(())
+    }
+
+    /// Returns the number of rows sent to the server at once.
+    pub fn batch_size(&self) -> u32 {
+        self.batch_size
+    }
+
+    /// Returns the number of rows in the buffer which have not been sent
+    /// to the server yet.
+    pub fn pending_rows(&self) -> u32 {
+        self.row_index
+    }
+
+    /// Returns the number of rows processed by the server successfully.
+    ///
+    /// When sending rows fails, this is the zero-based index of the failing
+    /// row in all rows appended to the batch. The row is the first one
+    /// in the buffer then.
+    pub fn sent_rows(&self) -> u64 {
+        self.sent_rows
+    }
+
+    /// Appends a row to the buffer. Values are bound by position and
+    /// the number of values must be equal to the number of bind variables.
+    /// Otherwise, this returns [Error::InvalidBindIndex][].
+    ///
+    /// Rows in the buffer are sent to the server when the buffer becomes full.
+    /// They are also sent before this method when bind variables must be
+    /// recreated, for example, when a string is longer than strings in
+    /// previous rows or when the Oracle type of a value is changed.
+    ///
+    /// When rows which failed to be sent remain in the buffer and they must
+    /// be sent by this method, this returns [Error::UnsentBatchRows][]
+    /// without appending the row. Call [execute](#method.execute) or
+    /// [clear](#method.clear) in advance.
+    ///
+    /// [Object][] and [Collection][] values may be bound as well. Values
+    /// at the same position must be of the same object type in order to
+    /// share a bind variable. Use `&OracleType::Object(objtype)` to bind
+    /// NULL of the object type.
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let objtype = conn.object_type("MDSYS.SDO_ELEM_INFO_ARRAY").unwrap();
+    /// let null = OracleType::Object(objtype.clone());
+    /// let mut batch = conn.batch("insert into elem_info_tab values (:1, :2)", 100).unwrap();
+    /// for i in 0..10 {
+    ///     if i % 2 == 0 {
+    ///         let coll = objtype.new_collection_from_iter(vec![i, 1, 1]).unwrap();
+    ///         batch.append_row(&[&i, &coll]).unwrap();
+    ///     } else {
+    ///         batch.append_row(&[&i, &null]).unwrap();
+    ///     }
+    /// }
+    /// batch.execute().unwrap();
+    /// ```
+    ///
+    /// [Object]: struct.Object.html
+    /// [Collection]: struct.Collection.html
+    /// [Error::InvalidBindIndex]: enum.Error.html#variant.InvalidBindIndex
+    /// [Error::UnsentBatchRows]: enum.Error.html#variant.UnsentBatchRows
+    pub fn append_row(&mut self, params: &[&ToSql]) -> Result<()> {
+        if params.len() != self.bind_count {
+            // one-based index of the first value or bind variable without its pair
+            return Err(Error::InvalidBindIndex(cmp::min(params.len(), self.bind_count) + 1));
+        }
+        if self.row_index == self.batch_size {
+            return Err(Error::UnsentBatchRows(self.row_index));
+        }
+        let mut oratypes = Vec::with_capacity(params.len());
+        let mut reusable = true;
+        for i in 0..params.len() {
+            let oratype = if self.binary_float_bind {
+                params[i].binary_float_oratype()?
+            } else {
+                params[i].oratype()?
+            };
+            if !self.bind_values[i].handle_is_reusable(&oratype, self.batch_size)? {
+                reusable = false;
+            }
+            oratypes.push(oratype);
+        }
+        if !reusable {
+            if self.send_failed {
+                return Err(Error::UnsentBatchRows(self.row_index));
+            }
+            self.execute()?;
+        }
+        for i in 0..params.len() {
+            self.bind_internal(i, &oratypes[i], params[i])
+                .context(|| format!("binding parameter {} of \"{}\"", i + 1, self.sql))?;
+        }
+        self.row_index += 1;
+        if self.row_index == self.batch_size {
+            self.execute()?;
+        }
+        Ok(())
+    }
+
+    fn bind_internal(&mut self, pos: usize, oratype: &OracleType, value: &ToSql) -> Result<()> {
+        if self.bind_values[pos].init_handle(self.conn, oratype, self.batch_size)? {
+            chkerr!(self.conn.ctxt,
+                    dpiStmt_bindByPos(self.handle, (pos + 1) as u32, self.bind_values[pos].handle));
+        }
+        self.bind_values[pos].buffer_row_index = self.row_index;
+        self.bind_values[pos].set(value)
+    }
+
+    /// Sends rows in the buffer to the server.
+    /// This does nothing when the buffer is empty.
+    ///
+    /// On error, rows processed before the failing row are removed
+    /// from the buffer because they have been applied. The failing row
+    /// and rows after it are kept, so they can be counted by
+    /// [pending_rows](#method.pending_rows) and sent again by this method
+    /// or discarded by [clear](#method.clear). The index of the failing
+    /// row is got by [sent_rows](#method.sent_rows).
+    pub fn execute(&mut self) -> Result<()> {
+        if self.row_index == 0 {
+            return Ok(());
+        }
+        let num_rows = self.row_index;
+        match self.execute_internal(num_rows) {
+            Ok(()) => {
+                self.sent_rows += num_rows as u64;
+                self.row_index = 0;
+                self.send_failed = false;
+                Ok(())
+            },
+            Err(err) => {
+                self.send_failed = true;
+                // The error of execution is more important than this.
+                let _ = self.remove_processed_rows(num_rows);
+                let failed_row = self.sent_rows;
+                Err(err.add_context(|| format!("executing {} rows of \"{}\" (failed at row {})",
+                                               num_rows, self.sql, failed_row)))
+            },
+        }
+    }
+
+    /// Discards rows in the buffer without sending them to the server.
+    pub fn clear(&mut self) {
+        self.row_index = 0;
+        self.send_failed = false;
+    }
+
+    fn execute_internal(&mut self, num_rows: u32) -> Result<()> {
+        chkerr!(self.conn.ctxt,
+                dpiStmt_executeMany(self.handle, self.conn.exec_mode(), num_rows));
+        Ok(())
+    }
+
+    // Removes rows processed before an error from the buffer and moves
+    // the remaining rows to the front.
+    fn remove_processed_rows(&mut self, num_rows: u32) -> Result<()> {
+        let mut count = 0;
+        chkerr!(self.conn.ctxt,
+                dpiStmt_getRowCount(self.handle, &mut count));
+        let processed = cmp::min(count, num_rows as u64) as u32;
+        if processed > 0 {
+            for val in &self.bind_values {
+                for i in 0..(num_rows - processed) {
+                    chkerr!(self.conn.ctxt,
+                            dpiVar_copyData(val.handle, i, val.handle, processed + i));
+                }
+            }
+        }
+        self.sent_rows += processed as u64;
+        self.row_index = num_rows - processed;
+        Ok(())
+    }
+}
+
+impl<'conn> Drop for Batch<'conn> {
+    fn drop(&mut self) {
+        let _ = unsafe { dpiStmt_release(self.handle) };
+    }
+}
+
+/// Copies rows fetched by a query to another table, possibly in another
+/// connection, and returns the number of copied rows.
+///
+/// `src_stmt` must be an executed query. The remaining rows are fetched
+/// and inserted by `insert_sql`, whose bind variables are set by column
+/// values by position. Values are copied in the Oracle types of the fetched
+/// columns without conversion via rust types. Rows are sent to the server
+/// by one round-trip per 100 rows.
+///
+/// This doesn't commit the transaction.
+///
+/// # Examples
+///
+/// ```no_run
+/// let src_conn = oracle::Connection::new("scott", "tiger", "server1/orcl").unwrap();
+/// let dst_conn = oracle::Connection::new("scott", "tiger", "server2/orcl").unwrap();
+/// let mut stmt = src_conn.execute("select empno, ename, hiredate from emp", &[]).unwrap();
+/// let num_rows = oracle::copy_rows(&mut stmt, &dst_conn,
+///                                  "insert into emp(empno, ename, hiredate) values (:1, :2, :3)").unwrap();
+/// dst_conn.commit().unwrap();
+/// println!("{} rows are copied.", num_rows);
+/// ```
+pub fn copy_rows(src_stmt: &mut Statement, dst_conn: &Connection, insert_sql: &str) -> Result<u64> {
+    let mut batch = dst_conn.batch(insert_sql, DPI_DEFAULT_FETCH_ARRAY_SIZE)?;
+    let mut num_rows = 0;
+    loop {
+        match src_stmt.fetch() {
+            Ok(row) => {
+                let params: Vec<&ToSql> = row.columns().iter().map(|val| val as &ToSql).collect();
+                batch.append_row(&params)?;
+                num_rows += 1;
+            },
+            Err(Error::NoMoreData) => break,
+            Err(err) => return Err(err),
+        }
+    }
+    batch.execute()?;
+    Ok(num_rows)
+}
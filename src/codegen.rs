@@ -0,0 +1,409 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+//! Generators of rust structs for tables and object types
+//!
+//! The generated code contains a struct whose fields correspond to
+//! columns or attributes and implementations of [RowValue][] for rows
+//! or [FromObject][] and [FromSql][] for objects. Names are converted
+//! to `CamelCase` for structs and `snake_case` for fields.
+//!
+//! Metadata may be got from a database or created by `new_offline`
+//! methods of [ColumnInfo][] and [ObjectType][].
+//!
+//! Attributes of object types are converted to the structs named after
+//! the object types. Generate them too when they are used.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+//! print!("{}", oracle::codegen::table_struct(&conn, "EMP").unwrap());
+//! // #[derive(Debug)]
+//! // pub struct Emp {
+//! //     pub empno: i32,
+//! //     pub ename: Option<String>,
+//! //     ...
+//! // }
+//! //
+//! // impl oracle::RowValue for Emp {
+//! //     ...
+//! // }
+//! ```
+//!
+//! [RowValue]: ../trait.RowValue.html
+//! [FromObject]: ../trait.FromObject.html
+//! [FromSql]: ../trait.FromSql.html
+//! [ColumnInfo]: ../struct.ColumnInfo.html
+//! [ObjectType]: ../struct.ObjectType.html
+
+use std::fmt::Write;
+
+use util;
+use ColumnInfo;
+use Connection;
+use Error;
+use ObjectType;
+use OracleType;
+use Result;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const",
+    "continue", "crate", "do", "dyn", "else", "enum", "extern", "false",
+    "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try",
+    "type", "typeof", "unsafe", "unsized", "use", "virtual", "where",
+    "while", "yield",
+];
+
+/// Returns the rust type name which an Oracle type is fetched as
+/// in generated code. Nullable types are wrapped in `Option`.
+///
+/// This returns `Error::InvalidTypeConversion` for types which cannot be
+/// fetched as owned values such as `REF CURSOR`.
+pub fn rust_type(oratype: &OracleType, nullable: bool) -> Result<String> {
+    let name = match *oratype {
+        OracleType::Varchar2(_) |
+        OracleType::NVarchar2(_) |
+        OracleType::Char(_) |
+        OracleType::NChar(_) |
+        OracleType::CLOB |
+        OracleType::NCLOB |
+        OracleType::Long => "String".to_string(),
+        OracleType::Rowid => "oracle::RowId".to_string(),
+        OracleType::Raw(_) |
+        OracleType::LongRaw |
+        OracleType::BLOB => "Vec<u8>".to_string(),
+        OracleType::BFILE => "oracle::Bfile".to_string(),
+        OracleType::BinaryFloat => "f32".to_string(),
+        OracleType::BinaryDouble => "f64".to_string(),
+        OracleType::Number(prec, scale) if prec > 0 && scale <= 0 && prec as i32 - scale as i32 <= 9 =>
+            "i32".to_string(),
+        OracleType::Number(prec, scale) if prec > 0 && scale <= 0 && prec as i32 - scale as i32 <= 18 =>
+            "i64".to_string(),
+        OracleType::Number(prec, _) if prec > 0 && prec <= 15 =>
+            "f64".to_string(),
+        OracleType::Float(prec) if prec > 0 && prec <= 53 =>
+            "f64".to_string(),
+        // i64 and f64 lose precision.
+        OracleType::Number(_, _) |
+        OracleType::Float(_) => "oracle::OracleNumber".to_string(),
+        OracleType::Date |
+        OracleType::Timestamp(_) |
+        OracleType::TimestampTZ(_) |
+        OracleType::TimestampLTZ(_) => "oracle::Timestamp".to_string(),
+        OracleType::IntervalDS(_, _) => "oracle::IntervalDS".to_string(),
+        OracleType::IntervalYM(_) => "oracle::IntervalYM".to_string(),
+        OracleType::Boolean => "bool".to_string(),
+        OracleType::Object(ref objtype) =>
+            if objtype.is_collection() {
+                "oracle::Collection".to_string()
+            } else {
+                struct_name(objtype.name())
+            },
+        OracleType::Int64 => "i64".to_string(),
+        OracleType::UInt64 => "u64".to_string(),
+        OracleType::RefCursor =>
+            return Err(Error::InvalidTypeConversion(oratype.to_string(), "owned rust type".to_string())),
+    };
+    if nullable {
+        Ok(format!("Option<{}>", name))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Converts an Oracle identifier such as `EMP_HISTORY` to a struct name
+/// such as `EmpHistory`.
+pub fn struct_name(name: &str) -> String {
+    let mut s = String::with_capacity(name.len());
+    let mut upper = true;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if upper {
+                s.push(c.to_ascii_uppercase());
+            } else {
+                s.push(c.to_ascii_lowercase());
+            }
+            upper = c.is_ascii_digit();
+        } else {
+            upper = true;
+        }
+    }
+    if s.is_empty() || s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+/// Converts an Oracle identifier such as `HIREDATE` to a field name
+/// such as `hiredate`. Rust keywords are suffixed with `_`.
+pub fn field_name(name: &str) -> String {
+    let mut s: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if s.is_empty() || s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&s.as_str()) {
+        s.push('_');
+    }
+    s
+}
+
+// Converts names to field names. Names converted to the same field name,
+// such as `A-B` and `A_B`, are suffixed with `_2`, `_3` and so on.
+fn field_names<'a, I>(names: I) -> Vec<String> where I: IntoIterator<Item = &'a str> {
+    let mut fields: Vec<String> = Vec::new();
+    for name in names {
+        let field = field_name(name);
+        let mut unique = field.clone();
+        let mut n = 1;
+        while fields.contains(&unique) {
+            n += 1;
+            unique = format!("{}_{}", field, n);
+        }
+        fields.push(unique);
+    }
+    fields
+}
+
+// Checks whether `name` is `table` or `schema.table`, each of which is
+// a nonquoted identifier or a quoted identifier without dots.
+fn is_table_name(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('.').collect();
+    parts.len() <= 2 && parts.iter().all(|part| {
+        util::is_identifier(part)
+            || (part.len() > 2 && part.starts_with('"') && part.ends_with('"')
+                && !part[1..part.len() - 1].contains(|c| c == '"' || c == '\0'))
+    })
+}
+
+/// Generates a struct implementing [RowValue][] for rows with the
+/// specified columns.
+///
+/// This fails when a column type has no rust type. See [rust_type][].
+///
+/// [RowValue]: ../trait.RowValue.html
+/// [rust_type]: fn.rust_type.html
+pub fn row_struct(name: &str, columns: &[ColumnInfo]) -> Result<String> {
+    let name = struct_name(name);
+    let fields = field_names(columns.iter().map(|col| col.name().as_str()));
+    let mut s = String::new();
+    writeln!(s, "#[derive(Debug)]").unwrap();
+    writeln!(s, "pub struct {} {{", name).unwrap();
+    for (col, field) in columns.iter().zip(&fields) {
+        writeln!(s, "    pub {}: {},", field, rust_type(col.oracle_type(), col.nullable())?).unwrap();
+    }
+    writeln!(s, "}}").unwrap();
+    writeln!(s, "").unwrap();
+    writeln!(s, "impl oracle::RowValue for {} {{", name).unwrap();
+    writeln!(s, "    fn get(row: &oracle::Row) -> oracle::Result<{}> {{", name).unwrap();
+    writeln!(s, "        Ok({} {{", name).unwrap();
+    for (col, field) in columns.iter().zip(&fields) {
+        writeln!(s, "            {}: row.get({:?})?,", field, col.name()).unwrap();
+    }
+    writeln!(s, "        }})").unwrap();
+    writeln!(s, "    }}").unwrap();
+    writeln!(s, "}}").unwrap();
+    Ok(s)
+}
+
+/// Generates a struct implementing [FromObject][] and [FromSql][] for
+/// an object type. Attributes are always wrapped in `Option` because
+/// they may be NULL.
+///
+/// This returns `None` for collection types and fails when an attribute
+/// type has no rust type. See [rust_type][].
+///
+/// [FromObject]: ../trait.FromObject.html
+/// [FromSql]: ../trait.FromSql.html
+/// [rust_type]: fn.rust_type.html
+pub fn object_struct(objtype: &ObjectType) -> Result<Option<String>> {
+    if objtype.is_collection() {
+        return Ok(None);
+    }
+    let name = struct_name(objtype.name());
+    let fields = field_names(objtype.attributes().iter().map(|attr| attr.name().as_str()));
+    let mut s = String::new();
+    writeln!(s, "#[derive(Debug)]").unwrap();
+    writeln!(s, "pub struct {} {{", name).unwrap();
+    for (attr, field) in objtype.attributes().iter().zip(&fields) {
+        writeln!(s, "    pub {}: {},", field, rust_type(attr.oracle_type(), true)?).unwrap();
+    }
+    writeln!(s, "}}").unwrap();
+    writeln!(s, "").unwrap();
+    writeln!(s, "impl oracle::FromObject for {} {{", name).unwrap();
+    writeln!(s, "    fn from_object(obj: &oracle::Object) -> oracle::Result<{}> {{", name).unwrap();
+    writeln!(s, "        Ok({} {{", name).unwrap();
+    for (attr, field) in objtype.attributes().iter().zip(&fields) {
+        writeln!(s, "            {}: obj.get({:?})?,", field, attr.name()).unwrap();
+    }
+    writeln!(s, "        }})").unwrap();
+    writeln!(s, "    }}").unwrap();
+    writeln!(s, "}}").unwrap();
+    writeln!(s, "").unwrap();
+    writeln!(s, "impl oracle::FromSql for {} {{", name).unwrap();
+    writeln!(s, "    fn from_sql(val: &oracle::SqlValue) -> oracle::Result<{}> {{", name).unwrap();
+    writeln!(s, "        val.as_object_of()").unwrap();
+    writeln!(s, "    }}").unwrap();
+    writeln!(s, "}}").unwrap();
+    Ok(Some(s))
+}
+
+/// Generates a struct for rows of a table or a view by describing
+/// `select * from <table>`.
+///
+/// `table` must be `table` or `schema.table`, each of which is a
+/// nonquoted identifier or a quoted identifier such as `"MixedCase"`.
+/// Otherwise this returns `Error::InvalidIdentifier`.
+pub fn table_struct(conn: &Connection, table: &str) -> Result<String> {
+    if !is_table_name(table) {
+        return Err(Error::InvalidIdentifier(table.to_string()));
+    }
+    let mut stmt = conn.prepare_internal(&format!("select * from {}", table))?;
+    let columns = stmt.column_info_only()?;
+    let name = table.rsplit('.').next().unwrap_or(table);
+    row_struct(name, columns)
+}
+
+/// Generates a struct for an object type specified by name.
+/// This returns an empty string for collection types.
+pub fn object_type_struct(conn: &Connection, name: &str) -> Result<String> {
+    let objtype = conn.object_type(name)?;
+    Ok(object_struct(&objtype)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ObjectTypeAttr;
+
+    #[test]
+    fn names() {
+        assert_eq!(struct_name("EMP_HISTORY"), "EmpHistory");
+        assert_eq!(struct_name("UDT_2D_POINT"), "Udt2DPoint");
+        assert_eq!(struct_name("Mixed$Case#"), "MixedCase");
+        assert_eq!(field_name("HIREDATE"), "hiredate");
+        assert_eq!(field_name("TYPE"), "type_");
+        assert_eq!(field_name("SAL$"), "sal_");
+        assert_eq!(field_name("1ST"), "_1st");
+        assert_eq!(field_names(vec!["A-B", "A_B", "A$B", "A_B_2"]), vec!["a_b", "a_b_2", "a_b_3", "a_b_2_2"]);
+    }
+
+    #[test]
+    fn table_names() {
+        assert!(is_table_name("EMP"));
+        assert!(is_table_name("scott.emp"));
+        assert!(is_table_name("\"Scott\".\"Mixed Case\""));
+        assert!(!is_table_name(""));
+        assert!(!is_table_name("a.b.c"));
+        assert!(!is_table_name("emp where 1=0"));
+        assert!(!is_table_name("emp; drop table emp"));
+        assert!(!is_table_name("\"a\"\"b\""));
+        assert!(!is_table_name("\"\""));
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(rust_type(&OracleType::Number(4, 0), false).unwrap(), "i32");
+        assert_eq!(rust_type(&OracleType::Number(15, 0), false).unwrap(), "i64");
+        assert_eq!(rust_type(&OracleType::Number(7, 2), true).unwrap(), "Option<f64>");
+        assert_eq!(rust_type(&OracleType::Number(0, -127), false).unwrap(), "oracle::OracleNumber");
+        assert_eq!(rust_type(&OracleType::Number(20, 0), false).unwrap(), "oracle::OracleNumber");
+        assert_eq!(rust_type(&OracleType::Number(38, 10), true).unwrap(), "Option<oracle::OracleNumber>");
+        assert_eq!(rust_type(&OracleType::Float(53), false).unwrap(), "f64");
+        assert_eq!(rust_type(&OracleType::Float(126), false).unwrap(), "oracle::OracleNumber");
+        assert_eq!(rust_type(&OracleType::Varchar2(10), true).unwrap(), "Option<String>");
+        assert_eq!(rust_type(&OracleType::Date, false).unwrap(), "oracle::Timestamp");
+        assert_eq!(rust_type(&OracleType::BLOB, false).unwrap(), "Vec<u8>");
+        let objtype = ObjectType::new_offline("SCOTT", "UDT_PERSON", Vec::new());
+        assert_eq!(rust_type(&OracleType::Object(objtype), true).unwrap(), "Option<UdtPerson>");
+        let objtype = ObjectType::new_offline_collection("SCOTT", "UDT_ARRAY", OracleType::Number(0, -127));
+        assert_eq!(rust_type(&OracleType::Object(objtype), false).unwrap(), "oracle::Collection");
+        assert!(rust_type(&OracleType::RefCursor, true).is_err());
+    }
+
+    #[test]
+    fn row() {
+        let columns = vec![
+            ColumnInfo::new_offline("EMPNO", OracleType::Number(4, 0), false),
+            ColumnInfo::new_offline("TYPE", OracleType::Varchar2(10), true),
+        ];
+        assert_eq!(row_struct("EMP", &columns).unwrap(), "\
+#[derive(Debug)]
+pub struct Emp {
+    pub empno: i32,
+    pub type_: Option<String>,
+}
+
+impl oracle::RowValue for Emp {
+    fn get(row: &oracle::Row) -> oracle::Result<Emp> {
+        Ok(Emp {
+            empno: row.get(\"EMPNO\")?,
+            type_: row.get(\"TYPE\")?,
+        })
+    }
+}
+");
+    }
+
+    #[test]
+    fn object() {
+        let objtype = ObjectType::new_offline("SCOTT", "UDT_PERSON", vec![
+            ObjectTypeAttr::new_offline("NAME", OracleType::Varchar2(30)),
+        ]);
+        assert_eq!(object_struct(&objtype).unwrap().unwrap(), "\
+#[derive(Debug)]
+pub struct UdtPerson {
+    pub name: Option<String>,
+}
+
+impl oracle::FromObject for UdtPerson {
+    fn from_object(obj: &oracle::Object) -> oracle::Result<UdtPerson> {
+        Ok(UdtPerson {
+            name: obj.get(\"NAME\")?,
+        })
+    }
+}
+
+impl oracle::FromSql for UdtPerson {
+    fn from_sql(val: &oracle::SqlValue) -> oracle::Result<UdtPerson> {
+        val.as_object_of()
+    }
+}
+");
+        let objtype = ObjectType::new_offline_collection("SCOTT", "UDT_ARRAY", OracleType::Number(0, -127));
+        assert!(object_struct(&objtype).unwrap().is_none());
+    }
+}
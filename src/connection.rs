@@ -30,20 +30,48 @@
 // authors and should not be interpreted as representing official policies, either expressed
 // or implied, of the authors.
 
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::raw::c_char;
+use std::os::raw::c_long;
 use std::ptr;
-
+use std::rc::Rc;
+use std::slice;
+use std::time::Duration;
+
+use Agent;
+use Batch;
+use Queue;
+use BoolConvention;
+use FloatToInt;
+use NumberDefine;
 use Version;
 use Statement;
+use StatementMetrics;
+use SubscrOptions;
+use Subscription;
+use Event;
 
 use binding::*;
+use Collection;
 use Context;
+use Error;
 use ObjectType;
+use OracleType;
 use Result;
+use RetryPolicy;
 use ToSql;
+use Xid;
 
 use OdpiStr;
 use new_odpi_str;
 use to_odpi_str;
+use aq;
+use statement::Watchdog;
+use error::ErrorContext;
+use util;
+use xa;
 
 /// Authorization mode
 ///
@@ -132,6 +160,94 @@ pub enum Purity {
     Self_,
 }
 
+/// Isolation level of transactions set by
+/// [Connection.set_transaction_isolation](struct.Connection.html#method.set_transaction_isolation)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Each query sees data committed before the query began.
+    /// This is the default.
+    ReadCommitted,
+
+    /// Each query sees data committed before the transaction began
+    /// and changes made by the transaction itself.
+    Serializable,
+}
+
+/// Options of [Connection.commit_with](struct.Connection.html#method.commit_with)
+///
+/// Options which are not set are left to the `COMMIT_WAIT` and
+/// `COMMIT_LOGGING` parameters of the session.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CommitOptions {
+    wait: Option<bool>,
+    batch: Option<bool>,
+}
+
+impl CommitOptions {
+    /// Creates commit options with default values.
+    pub fn new() -> CommitOptions {
+        CommitOptions {
+            wait: None,
+            batch: None,
+        }
+    }
+
+    /// Sets whether the commit waits until the redo is written to disk
+    /// (`WAIT`) or returns immediately (`NOWAIT`). A committed transaction
+    /// may be lost on instance failure with `NOWAIT`.
+    pub fn wait<'a>(&'a mut self, wait: bool) -> &'a mut CommitOptions {
+        self.wait = Some(wait);
+        self
+    }
+
+    /// Sets whether the redo is buffered and written with other
+    /// transactions (`BATCH`) or written immediately (`IMMEDIATE`).
+    pub fn batch<'a>(&'a mut self, batch: bool) -> &'a mut CommitOptions {
+        self.batch = Some(batch);
+        self
+    }
+
+    fn to_sql(&self) -> String {
+        let mut sql = "COMMIT".to_string();
+        if self.wait.is_some() || self.batch.is_some() {
+            sql.push_str(" WRITE");
+        }
+        match self.wait {
+            Some(true) => sql.push_str(" WAIT"),
+            Some(false) => sql.push_str(" NOWAIT"),
+            None => (),
+        }
+        match self.batch {
+            Some(true) => sql.push_str(" BATCH"),
+            Some(false) => sql.push_str(" IMMEDIATE"),
+            None => (),
+        }
+        sql
+    }
+}
+
+/// Outcome of the last transaction of a failed session returned by
+/// [Connection.ltxid_outcome](struct.Connection.html#method.ltxid_outcome)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LtxidOutcome {
+    committed: bool,
+    user_call_completed: bool,
+}
+
+impl LtxidOutcome {
+    /// Returns true when the transaction was committed.
+    pub fn committed(&self) -> bool {
+        self.committed
+    }
+
+    /// Returns true when the user call which committed the transaction
+    /// completed, including returning values of PL/SQL blocks.
+    /// This is meaningful only when [committed](#method.committed) is true.
+    pub fn user_call_completed(&self) -> bool {
+        self.user_call_completed
+    }
+}
+
 //
 // Connector
 //
@@ -268,6 +384,7 @@ impl Connector {
         conn_params.appContext = app_context.as_mut_ptr();
         conn_params.numAppContext = app_context.len() as u32;
         Connection::connect_internal(ctxt, &self.username, &self.password, &self.connect_string, &common_params, &conn_params)
+            .context(|| format!("connecting to \"{}\" as {}", self.connect_string, self.username))
     }
 
     /// Sets a system privilege such as SYSDBA.
@@ -292,6 +409,8 @@ impl Connector {
         self
     }
 
+    /// Sets events mode, which is required to [subscribe](struct.Connection.html#method.subscribe)
+    /// to continuous query notification.
     pub fn events<'a>(&'a mut self, events: bool) -> &'a mut Connector {
         self.events = events;
         self
@@ -414,6 +533,17 @@ pub struct Connection {
     pub(crate) handle: *mut dpiConn,
     tag: String,
     tag_found: bool,
+    metrics_callback: RefCell<Option<Box<Fn(&str, &StatementMetrics)>>>,
+    bool_convention: RefCell<Option<Rc<BoolConvention>>>,
+    number_define: Cell<NumberDefine>,
+    binary_float_bind: Cell<bool>,
+    strict_utf8: Cell<bool>,
+    strict_conversion: Cell<bool>,
+    float_to_int: Cell<FloatToInt>,
+    max_lob_fetch_size: Cell<Option<u64>>,
+    autocommit: Cell<bool>,
+    object_type_cache: RefCell<HashMap<String, ObjectType>>,
+    watchdog: RefCell<Option<Watchdog>>,
 }
 
 impl Connection {
@@ -498,6 +628,119 @@ impl Connection {
         Ok(stmt)
     }
 
+    // Prepares a statement which this crate executes on behalf of users,
+    // such as PL/SQL blocks calling DBMS_AQ. It isn't passed to the
+    // metrics callback.
+    pub(crate) fn prepare_internal(&self, sql: &str) -> Result<Statement> {
+        let mut stmt = self.prepare(sql)?;
+        stmt.internal = true;
+        Ok(stmt)
+    }
+
+    pub(crate) fn execute_internal(&self, sql: &str, params: &[&ToSql]) -> Result<Statement> {
+        let mut stmt = self.prepare_internal(sql)?;
+        stmt.execute(params)?;
+        Ok(stmt)
+    }
+
+    pub(crate) fn execute_named_internal(&self, sql: &str, params: &[(&str, &ToSql)]) -> Result<Statement> {
+        let mut stmt = self.prepare_internal(sql)?;
+        stmt.execute_named(params)?;
+        Ok(stmt)
+    }
+
+    /// Prepares a statement, binds values by position and executes it
+    /// with retries according to the policy.
+    ///
+    /// The statement is prepared again on each retry.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let policy = oracle::RetryPolicy::new();
+    ///
+    /// // retry on transient errors such as ORA-00054 and ORA-00060.
+    /// conn.execute_with_retry("update emp set sal = sal * 1.1 where empno = :1", &[&7369], &policy).unwrap();
+    /// ```
+    pub fn execute_with_retry(&self, sql: &str, params: &[&ToSql], policy: &RetryPolicy) -> Result<Statement> {
+        policy.retry(|| self.execute(sql, params))
+    }
+
+    /// Creates a batch to execute a DML statement with many rows.
+    ///
+    /// Appended rows are sent to the server by one round-trip per
+    /// `batch_size` rows. Zero is treated as one. See [Batch](struct.Batch.html).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut batch = conn.batch("insert into emp(empno, ename) values (:1, :2)", 100).unwrap();
+    /// batch.append_row(&[&113, &"John"]).unwrap();
+    /// batch.append_row(&[&114, &"Smith"]).unwrap();
+    /// batch.execute().unwrap();
+    /// ```
+    pub fn batch(&self, sql: &str, batch_size: u32) -> Result<Batch> {
+        Batch::new(self, sql, batch_size)
+    }
+
+    /// Creates an Advanced Queuing queue handle whose payloads are
+    /// objects of `payload_type`. See [Queue](struct.Queue.html).
+    pub fn queue(&self, name: &str, payload_type: &ObjectType) -> Queue {
+        Queue::new(self, name, payload_type)
+    }
+
+    /// Creates an Advanced Queuing queue handle whose payloads are RAW.
+    /// See [Queue](struct.Queue.html).
+    pub fn raw_queue(&self, name: &str) -> Queue {
+        Queue::new_raw(self, name)
+    }
+
+    /// Creates an Advanced Queuing queue handle whose payloads are JSON.
+    /// This is available when `serde_json` feature is enabled.
+    /// See [Queue](struct.Queue.html).
+    #[cfg(feature = "serde_json")]
+    pub fn json_queue(&self, name: &str) -> Queue {
+        Queue::new_json(self, name)
+    }
+
+    /// Waits for a message to arrive at any of queues specified by `agents`
+    /// and returns the agent of the queue. This returns `None` when no
+    /// message arrives within `wait` seconds. `None` as `wait` means
+    /// waiting forever.
+    ///
+    /// The message is not dequeued. Dequeue it by the [Queue](struct.Queue.html)
+    /// whose name is [Agent.address](struct.Agent.html#method.address).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let agents = [Agent::new("ORDERS"), Agent::with_consumer("EVENTS", "BILLING")];
+    /// if let Some(agent) = conn.listen(&agents, Some(10)).unwrap() {
+    ///     println!("a message arrived at {}", agent.address());
+    /// }
+    /// ```
+    pub fn listen(&self, agents: &[Agent], wait: Option<u32>) -> Result<Option<Agent>> {
+        aq::listen(self, agents, wait)
+    }
+
+    /// Creates a subscription for continuous query notification.
+    /// `callback` is called with notified events in a thread created by
+    /// the Oracle client. The connection must be created with
+    /// [Connector.events](struct.Connector.html#method.events) set to
+    /// `true`. See [Subscription](struct.Subscription.html).
+    ///
+    /// Notifications of Advanced Queuing messages aren't supported because
+    /// the bundled ODPI-C has no `DPI_SUBSCR_NAMESPACE_AQ`. Wait for
+    /// messages by [listen](#method.listen) or `Queue::dequeue()` with
+    /// `DeqOptions::wait()` instead.
+    pub fn subscribe<F>(&self, options: &SubscrOptions, callback: F) -> Result<Subscription> where F: FnMut(&Event) + Send + 'static {
+        Subscription::new(self, options, callback)
+    }
+
     /// Cancels execution of running statements in the connection
     pub fn break_execution(&self) -> Result<()> {
         chkerr!(self.ctxt,
@@ -512,6 +755,23 @@ impl Connection {
         Ok(())
     }
 
+    /// Commits the current active transaction with `options`, which
+    /// trade durability for throughput.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// conn.execute("insert into access_log values (sysdate, 'login')", &[]).unwrap();
+    /// // COMMIT WRITE NOWAIT BATCH
+    /// conn.commit_with(CommitOptions::new().wait(false).batch(true)).unwrap();
+    /// ```
+    pub fn commit_with(&self, options: &CommitOptions) -> Result<()> {
+        self.execute(&options.to_sql(), &[])?;
+        Ok(())
+    }
+
     /// Rolls back the current active transaction
     pub fn rollback(&self) -> Result<()> {
         chkerr!(self.ctxt,
@@ -519,6 +779,318 @@ impl Connection {
         Ok(())
     }
 
+    /// Commits the transaction automatically when a statement or a batch
+    /// is executed successfully if `autocommit` is true.
+    ///
+    /// It is disabled by default. The commit is done in the same
+    /// network round-trip as the execution. It is applied to executions
+    /// after this call.
+    ///
+    /// Statements executed internally by methods such as
+    /// [set_transaction_isolation][], [savepoint][] and [Queue.process][]
+    /// aren't committed. A [Batch][] commits each chunk of rows sent to
+    /// the server, so rows of earlier chunks stay committed when a later
+    /// chunk fails.
+    ///
+    /// [set_transaction_isolation]: #method.set_transaction_isolation
+    /// [savepoint]: #method.savepoint
+    /// [Queue.process]: struct.Queue.html#method.process
+    /// [Batch]: struct.Batch.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_autocommit(true);
+    /// // committed without calling conn.commit().
+    /// conn.execute("insert into emp(empno, ename) values (9001, 'KING')", &[]).unwrap();
+    /// ```
+    pub fn set_autocommit(&self, autocommit: bool) {
+        self.autocommit.set(autocommit);
+    }
+
+    /// Returns true when autocommit mode is enabled.
+    /// See [set_autocommit](#method.set_autocommit).
+    pub fn autocommit(&self) -> bool {
+        self.autocommit.get()
+    }
+
+    pub(crate) fn exec_mode(&self) -> dpiExecMode {
+        if self.autocommit.get() {
+            DPI_MODE_EXEC_COMMIT_ON_SUCCESS
+        } else {
+            DPI_MODE_EXEC_DEFAULT
+        }
+    }
+
+    // Starts watching an execution by the watchdog thread, which is
+    // started on the first call.
+    pub(crate) fn start_watchdog(&self, timeout: Duration) {
+        let mut watchdog = self.watchdog.borrow_mut();
+        if watchdog.is_none() {
+            *watchdog = Some(Watchdog::new(self));
+        }
+        if let Some(ref watchdog) = *watchdog {
+            watchdog.start(timeout);
+        }
+    }
+
+    pub(crate) fn disarm_watchdog(&self) {
+        if let Some(ref watchdog) = *self.watchdog.borrow() {
+            watchdog.disarm();
+        }
+    }
+
+    // Returns true when the execution was broken.
+    pub(crate) fn stop_watchdog(&self) -> bool {
+        match *self.watchdog.borrow() {
+            Some(ref watchdog) => watchdog.stop(),
+            None => false,
+        }
+    }
+
+    /// Makes the next transaction read-only. Queries in it see data
+    /// committed before it began and DML in it fails.
+    ///
+    /// This must be called before the transaction starts. Otherwise
+    /// this returns [Error::TransactionInProgress](enum.Error.html#variant.TransactionInProgress).
+    /// The setting ends at the end of the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_transaction_read_only().unwrap();
+    /// // The two queries see the same snapshot.
+    /// let total: f64 = conn.execute("select sum(sal) from emp", &[]).unwrap().fetch().unwrap().get(0).unwrap();
+    /// let count: u32 = conn.execute("select count(*) from emp", &[]).unwrap().fetch().unwrap().get(0).unwrap();
+    /// println!("average: {}", total / count as f64);
+    /// conn.commit().unwrap(); // ends the read-only transaction
+    /// ```
+    pub fn set_transaction_read_only(&self) -> Result<()> {
+        self.set_transaction("SET TRANSACTION READ ONLY")
+    }
+
+    /// Sets the isolation level of the next transaction.
+    ///
+    /// This must be called before the transaction starts. Otherwise
+    /// this returns [Error::TransactionInProgress](enum.Error.html#variant.TransactionInProgress).
+    /// The setting ends at the end of the transaction.
+    pub fn set_transaction_isolation(&self, level: IsolationLevel) -> Result<()> {
+        self.set_transaction(match level {
+            IsolationLevel::ReadCommitted => "SET TRANSACTION ISOLATION LEVEL READ COMMITTED",
+            IsolationLevel::Serializable => "SET TRANSACTION ISOLATION LEVEL SERIALIZABLE",
+        })
+    }
+
+    fn set_transaction(&self, sql: &str) -> Result<()> {
+        if self.transaction_in_progress()? {
+            return Err(Error::TransactionInProgress);
+        }
+        self.execute_internal(sql, &[])?;
+        Ok(())
+    }
+
+    /// Returns true when the session has an open transaction, which
+    /// has uncommitted changes or has been started by `SET TRANSACTION`.
+    ///
+    /// This is useful to detect a transaction left open before
+    /// reusing the connection for another task.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// // ... the connection is returned from an unknown task ...
+    /// if conn.transaction_in_progress().unwrap() {
+    ///     conn.rollback().unwrap();
+    /// }
+    /// ```
+    pub fn transaction_in_progress(&self) -> Result<bool> {
+        Ok(self.transaction_id()?.is_some())
+    }
+
+    // Returns the local transaction id of the current transaction.
+    fn transaction_id(&self) -> Result<Option<String>> {
+        let mut stmt = self.execute_internal("select dbms_transaction.local_transaction_id from dual", &[])?;
+        stmt.fetch()?.get(0)
+    }
+
+    /// Resets session state left by a task before the connection is
+    /// reused by another task, such as when an application-side pool
+    /// takes it back.
+    ///
+    /// This rolls back the transaction in progress, clears module, action
+    /// and client identifier and restores the current schema to the
+    /// session user's. Other state such as package variables and session
+    /// parameters is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_module("billing").unwrap();
+    /// conn.set_current_schema("hr").unwrap();
+    /// conn.execute("insert into emp(empno, ename) values (9001, 'KING')", &[]).unwrap();
+    ///
+    /// // The insert is rolled back and the module and schema are cleared.
+    /// conn.reset_session_state().unwrap();
+    /// ```
+    pub fn reset_session_state(&self) -> Result<()> {
+        // Cleared values are piggybacked to the server with the query below.
+        self.set_module("")?;
+        self.set_action("")?;
+        self.set_client_identifier("")?;
+        let (txn_id, user, schema) = {
+            let mut stmt = self.execute_internal("select dbms_transaction.local_transaction_id, sys_context('USERENV', 'SESSION_USER'), sys_context('USERENV', 'CURRENT_SCHEMA') from dual", &[])?;
+            stmt.fetch()?.get_as::<(Option<String>, String, String)>()?
+        };
+        if txn_id.is_some() {
+            self.rollback()?;
+        }
+        if user != schema {
+            self.set_current_schema(&user)?;
+        }
+        Ok(())
+    }
+
+    /// Creates a savepoint in the current transaction. Changes after
+    /// this can be rolled back by [rollback_to](#method.rollback_to).
+    ///
+    /// `name` must be a nonquoted identifier. Otherwise this returns
+    /// [Error::InvalidIdentifier](enum.Error.html#variant.InvalidIdentifier).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.execute("insert into emp(empno, ename) values (9001, 'KING')", &[]).unwrap();
+    /// conn.savepoint("after_king").unwrap();
+    /// conn.execute("insert into emp(empno, ename) values (9002, 'QUEEN')", &[]).unwrap();
+    /// conn.rollback_to("after_king").unwrap(); // rolls back QUEEN only
+    /// conn.commit().unwrap();
+    /// ```
+    pub fn savepoint(&self, name: &str) -> Result<()> {
+        Connection::check_identifier(name)?;
+        self.execute_internal(&format!("SAVEPOINT {}", name), &[])?;
+        Ok(())
+    }
+
+    /// Rolls back changes after the savepoint `name` created by
+    /// [savepoint](#method.savepoint). The transaction and the savepoint
+    /// remain active.
+    pub fn rollback_to(&self, name: &str) -> Result<()> {
+        Connection::check_identifier(name)?;
+        self.execute_internal(&format!("ROLLBACK TO SAVEPOINT {}", name), &[])?;
+        Ok(())
+    }
+
+    fn check_identifier(name: &str) -> Result<()> {
+        if util::is_identifier(name) {
+            Ok(())
+        } else {
+            Err(Error::InvalidIdentifier(name.to_string()))
+        }
+    }
+
+    /// Begins a branch of a distributed transaction identified by `xid`.
+    /// See [Xid](struct.Xid.html).
+    pub fn begin_distrib_trans(&self, xid: &Xid) -> Result<()> {
+        let gtrid = xid.global_transaction_id();
+        let bqual = xid.branch_qualifier();
+        chkerr!(self.ctxt,
+                dpiConn_beginDistribTrans(self.handle, xid.format_id() as c_long,
+                                          gtrid.as_ptr() as *const c_char, gtrid.len() as u32,
+                                          bqual.as_ptr() as *const c_char, bqual.len() as u32));
+        Ok(())
+    }
+
+    /// Prepares the distributed transaction for commit, the first phase
+    /// of two-phase commit. This returns false when the transaction
+    /// changed nothing and no commit is needed. Otherwise commit it
+    /// by [commit](#method.commit) or [commit_prepared](#method.commit_prepared).
+    pub fn prepare_distrib_trans(&self) -> Result<bool> {
+        let mut commit_needed = 0;
+        chkerr!(self.ctxt,
+                dpiConn_prepareDistribTrans(self.handle, &mut commit_needed));
+        Ok(commit_needed != 0)
+    }
+
+    /// Commits the prepared branch identified by `xid` by `DBMS_XA.XA_COMMIT`.
+    /// The branch may have been prepared by another session.
+    ///
+    /// This returns [Error::XaError](enum.Error.html#variant.XaError)
+    /// when `DBMS_XA.XA_COMMIT` fails.
+    pub fn commit_prepared(&self, xid: &Xid) -> Result<()> {
+        xa::call_dbms_xa(self, "xa_commit", xid, ", false")
+    }
+
+    /// Rolls back the prepared branch identified by `xid` by `DBMS_XA.XA_ROLLBACK`.
+    /// The branch may have been prepared by another session.
+    ///
+    /// This returns [Error::XaError](enum.Error.html#variant.XaError)
+    /// when `DBMS_XA.XA_ROLLBACK` fails.
+    pub fn rollback_prepared(&self, xid: &Xid) -> Result<()> {
+        xa::call_dbms_xa(self, "xa_rollback", xid, "")
+    }
+
+    /// Suspends the branch identified by `xid`, which is associated with
+    /// this session, by `DBMS_XA.XA_END` with `TMSUSPEND`. Changes after
+    /// this are not part of the branch until it is resumed by
+    /// [resume_distrib_trans](#method.resume_distrib_trans).
+    ///
+    /// This returns [Error::XaError](enum.Error.html#variant.XaError)
+    /// when `DBMS_XA.XA_END` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let xid = Xid::new(0x1234, b"global-transaction-1", b"branch-1");
+    /// conn.begin_distrib_trans(&xid).unwrap();
+    /// conn.execute("update emp set sal = sal * 1.1 where empno = 7839", &[]).unwrap();
+    /// conn.suspend_distrib_trans(&xid).unwrap();
+    /// // ... work outside of the branch ...
+    /// conn.resume_distrib_trans(&xid).unwrap();
+    /// conn.execute("update emp set sal = sal * 1.1 where empno = 7698", &[]).unwrap();
+    /// ```
+    pub fn suspend_distrib_trans(&self, xid: &Xid) -> Result<()> {
+        xa::call_dbms_xa(self, "xa_end", xid, ", dbms_xa.tmsuspend")
+    }
+
+    /// Resumes the branch identified by `xid`, which was suspended by
+    /// [suspend_distrib_trans](#method.suspend_distrib_trans), by
+    /// `DBMS_XA.XA_START` with `TMRESUME`. The branch may have been
+    /// suspended by another session.
+    ///
+    /// This returns [Error::XaError](enum.Error.html#variant.XaError)
+    /// when `DBMS_XA.XA_START` fails.
+    pub fn resume_distrib_trans(&self, xid: &Xid) -> Result<()> {
+        xa::call_dbms_xa(self, "xa_start", xid, ", dbms_xa.tmresume")
+    }
+
+    /// Returns identifiers of prepared branches which have been neither
+    /// committed nor rolled back, like `xa_recover()` of the XA
+    /// specification. A transaction manager recovering from failure
+    /// completes them by [commit_prepared](#method.commit_prepared) or
+    /// [rollback_prepared](#method.rollback_prepared).
+    ///
+    /// This queries `DBA_PENDING_TRANSACTIONS`. The user needs the
+    /// privilege to select it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// for xid in conn.recover_distrib_trans().unwrap() {
+    ///     conn.rollback_prepared(&xid).unwrap();
+    /// }
+    /// ```
+    pub fn recover_distrib_trans(&self) -> Result<Vec<Xid>> {
+        xa::recover(self)
+    }
+
     /// Closes the connection before the end of lifetime.
     ///
     /// This fails when open statements or LOBs exist.
@@ -586,6 +1158,40 @@ impl Connection {
         Ok(())
     }
 
+    /// Gets the session time zone such as `+09:00` or `Asia/Tokyo`.
+    ///
+    /// Values of `TIMESTAMP WITH LOCAL TIME ZONE` are fetched in the session
+    /// time zone. Their time zone offsets are those of the session time zone
+    /// at the fetched times.
+    pub fn session_time_zone(&self) -> Result<String> {
+        let mut stmt = self.execute_internal("select sessiontimezone from dual", &[])?;
+        let tz = stmt.fetch()?.get(0)?;
+        Ok(tz)
+    }
+
+    /// Sets the session time zone by `ALTER SESSION SET TIME_ZONE`.
+    ///
+    /// `time_zone` is an offset such as `+09:00`, a region name such as
+    /// `Asia/Tokyo`, `LOCAL` or `DBTIMEZONE`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_session_time_zone("UTC").unwrap();
+    /// assert_eq!(conn.session_time_zone().unwrap(), "UTC");
+    /// ```
+    pub fn set_session_time_zone(&self, time_zone: &str) -> Result<()> {
+        let sql = match time_zone.to_uppercase().as_str() {
+            "LOCAL" | "DBTIMEZONE" =>
+                format!("alter session set time_zone = {}", time_zone),
+            _ =>
+                format!("alter session set time_zone = '{}'", time_zone.replace("'", "''")),
+        };
+        self.execute_internal(&sql, &[])?;
+        Ok(())
+    }
+
     /// Gets edition associated with the connection
     pub fn edition(&self) -> Result<String> {
         let mut s = new_odpi_str();
@@ -626,7 +1232,66 @@ impl Connection {
         Ok(())
     }
 
-    //pub fn dpiConn_getLTXID
+    /// Gets the logical transaction id (LTXID) of Transaction Guard.
+    ///
+    /// This is empty unless the connection is to a service whose
+    /// `COMMIT_OUTCOME` attribute is enabled. Keep it before a commit to
+    /// check the outcome by [ltxid_outcome](#method.ltxid_outcome) when
+    /// the commit fails with a recoverable error such as a network failure.
+    pub fn ltxid(&self) -> Result<Vec<u8>> {
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        chkerr!(self.ctxt,
+                dpiConn_getLTXID(self.handle, &mut ptr, &mut len));
+        if ptr.is_null() {
+            Ok(Vec::new())
+        } else {
+            Ok(unsafe { slice::from_raw_parts(ptr as *const u8, len as usize) }.to_vec())
+        }
+    }
+
+    /// Gets the outcome of the last transaction of a failed session
+    /// whose LTXID is `ltxid` by `DBMS_APP_CONT.GET_LTXID_OUTCOME`.
+    /// This must be called in a new session. It also blocks the failed
+    /// session's transaction from committing later, so the transaction
+    /// can be safely resubmitted when it wasn't committed.
+    ///
+    /// The user needs EXECUTE privilege on `DBMS_APP_CONT`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// conn.execute("update emp set sal = sal * 1.1 where empno = 7839", &[]).unwrap();
+    /// let ltxid = conn.ltxid().unwrap();
+    /// if conn.commit().is_err() {
+    ///     // Check the outcome after the session failed.
+    ///     let conn = Connection::new("scott", "tiger", "").unwrap();
+    ///     if !conn.ltxid_outcome(&ltxid).unwrap().committed() {
+    ///         // resubmit the update.
+    ///     }
+    /// }
+    /// ```
+    pub fn ltxid_outcome(&self, ltxid: &[u8]) -> Result<LtxidOutcome> {
+        let sql = "\
+declare
+    committed boolean;
+    user_call_completed boolean;
+begin
+    dbms_app_cont.get_ltxid_outcome(:ltxid, committed, user_call_completed);
+    :committed := sys.diutil.bool_to_int(committed);
+    :user_call_completed := sys.diutil.bool_to_int(user_call_completed);
+end;";
+        let stmt = self.execute_named_internal(sql, &[("ltxid", &ltxid),
+                                                      ("committed", &OracleType::Int64),
+                                                      ("user_call_completed", &OracleType::Int64)])?;
+        Ok(LtxidOutcome {
+            committed: stmt.bind_value::<_, i32>("committed")? != 0,
+            user_call_completed: stmt.bind_value::<_, i32>("user_call_completed")? != 0,
+        })
+    }
+
     //pub fn dpiConn_getObjectType
 
     /// Gets the statement cache size
@@ -647,9 +1312,7 @@ impl Connection {
     //pub fn dpiConn_newDeqOptions
     //pub fn dpiConn_newEnqOptions
     //pub fn dpiConn_newMsgProps
-    //pub fn dpiConn_newSubscription
     //pub fn dpiConn_newTempLob
-    //pub fn dpiConn_prepareDistribTrans
 
     /// Sets module associated with the connection
     ///
@@ -728,18 +1391,314 @@ impl Connection {
 
     /// Gets an object type information from name
     ///
+    /// Object types are cached by the name in the connection because
+    /// getting them needs round trips to the server. Call
+    /// [invalidate_object_type](#method.invalidate_object_type) or
+    /// [clear_object_type_cache](#method.clear_object_type_cache) after
+    /// the type is altered.
+    ///
     /// ```no_run
     /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
     /// let objtype = conn.object_type("MDSYS.SDO_GEOMETRY");
     /// ```
     pub fn object_type(&self, name: &str) -> Result<ObjectType> {
-        let name = to_odpi_str(name);
+        if let Some(objtype) = self.object_type_cache.borrow().get(name) {
+            return Ok(objtype.clone());
+        }
+        let odpi_name = to_odpi_str(name);
         let mut handle = ptr::null_mut();
         chkerr!(self.ctxt,
-                dpiConn_getObjectType(self.handle, name.ptr, name.len, &mut handle));
+                dpiConn_getObjectType(self.handle, odpi_name.ptr, odpi_name.len, &mut handle));
         let res = ObjectType::from_dpiObjectType(self.ctxt, handle);
         unsafe { dpiObjectType_release(handle); }
-        res
+        let objtype = res?;
+        self.object_type_cache.borrow_mut().insert(name.to_string(), objtype.clone());
+        Ok(objtype)
+    }
+
+    /// Removes the object type cached by [object_type](#method.object_type)
+    /// with the name.
+    pub fn invalidate_object_type(&self, name: &str) {
+        self.object_type_cache.borrow_mut().remove(name);
+    }
+
+    /// Removes all object types cached by [object_type](#method.object_type).
+    pub fn clear_object_type_cache(&self) {
+        self.object_type_cache.borrow_mut().clear();
+    }
+
+    /// Sets a function called with the SQL text and [execution metrics][]
+    /// when a statement in the connection is dropped.
+    ///
+    /// Statements which this crate executes internally, such as those of
+    /// [dbms_output_lines](#method.dbms_output_lines) and [Queue][], aren't
+    /// passed to the function. A panic in the function is caught and ignored.
+    ///
+    /// # Examples
+    ///
+    /// Logs slow queries.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_metrics_callback(|sql, metrics| {
+    ///     if metrics.execute_time() + metrics.fetch_time() > Duration::from_secs(1) {
+    ///         println!("slow query: {} ({:?})", sql, metrics);
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [execution metrics]: struct.StatementMetrics.html
+    /// [Queue]: struct.Queue.html
+    pub fn set_metrics_callback<F>(&self, f: F) where F: Fn(&str, &StatementMetrics) + 'static {
+        *self.metrics_callback.borrow_mut() = Some(Box::new(f));
+    }
+
+    /// Removes the function set by [set_metrics_callback](#method.set_metrics_callback).
+    pub fn unset_metrics_callback(&self) {
+        *self.metrics_callback.borrow_mut() = None;
+    }
+
+    pub(crate) fn call_metrics_callback(&self, sql: &str, metrics: &StatementMetrics) {
+        if let Ok(callback) = self.metrics_callback.try_borrow() {
+            if let Some(ref f) = *callback {
+                f(sql, metrics);
+            }
+        }
+    }
+
+    /// Sets the convention converting `bool` to and from NUMBER and
+    /// character columns. See [BoolConvention][] for details.
+    ///
+    /// It is applied to statements prepared after this call.
+    ///
+    /// [BoolConvention]: struct.BoolConvention.html
+    pub fn set_bool_convention(&self, conv: BoolConvention) {
+        *self.bool_convention.borrow_mut() = if conv == BoolConvention::new() {
+            None
+        } else {
+            Some(Rc::new(conv))
+        };
+    }
+
+    /// Gets the convention set by [set_bool_convention](#method.set_bool_convention).
+    pub fn bool_convention(&self) -> BoolConvention {
+        match *self.bool_convention.borrow() {
+            Some(ref conv) => (**conv).clone(),
+            None => BoolConvention::new(),
+        }
+    }
+
+    pub(crate) fn bool_convention_rc(&self) -> Option<Rc<BoolConvention>> {
+        self.bool_convention.borrow().clone()
+    }
+
+    /// Sets the strategy to define NUMBER columns of queries. See
+    /// [NumberDefine][] for details.
+    ///
+    /// It is applied to statements prepared after this call.
+    /// Use [Statement.set_number_define()][] to change it per statement.
+    ///
+    /// [NumberDefine]: enum.NumberDefine.html
+    /// [Statement.set_number_define()]: struct.Statement.html#method.set_number_define
+    pub fn set_number_define(&self, number_define: NumberDefine) {
+        self.number_define.set(number_define);
+    }
+
+    /// Gets the strategy set by [set_number_define](#method.set_number_define).
+    pub fn number_define(&self) -> NumberDefine {
+        self.number_define.get()
+    }
+
+    /// Binds `f32` and `f64` as BINARY_FLOAT and BINARY_DOUBLE instead of
+    /// NUMBER when `enable` is true.
+    ///
+    /// NUMBER is used by default. The choice affects implicit conversion in
+    /// the server. For example, a BINARY_DOUBLE value compared with a NUMBER
+    /// column prevents an index on the column from being used whereas a
+    /// NUMBER value compared with a BINARY_DOUBLE column causes rounding.
+    /// Explicit Oracle types such as `&(&val, &OracleType::BinaryDouble)`
+    /// take precedence over this.
+    ///
+    /// It is applied to statements and batches prepared after this call.
+    pub fn set_binary_float_bind(&self, enable: bool) {
+        self.binary_float_bind.set(enable);
+    }
+
+    /// Returns true when `f32` and `f64` are bound as BINARY_FLOAT and BINARY_DOUBLE.
+    /// See [set_binary_float_bind](#method.set_binary_float_bind).
+    pub fn binary_float_bind(&self) -> bool {
+        self.binary_float_bind.get()
+    }
+
+    /// Makes conversion from character data types to `String` fail with
+    /// [Error::InvalidUtf8][] when `enable` is true.
+    ///
+    /// Invalid UTF-8 byte sequences are replaced with U+FFFD by default.
+    /// The error includes the column name and the byte offset of the first
+    /// invalid sequence.
+    ///
+    /// It is applied to statements prepared after this call.
+    ///
+    /// [Error::InvalidUtf8]: enum.Error.html#variant.InvalidUtf8
+    pub fn set_strict_utf8(&self, enable: bool) {
+        self.strict_utf8.set(enable);
+    }
+
+    /// Returns true when strict UTF-8 conversion is enabled.
+    /// See [set_strict_utf8](#method.set_strict_utf8).
+    pub fn strict_utf8(&self) -> bool {
+        self.strict_utf8.get()
+    }
+
+    /// Sets the upper limit of the size of LOBs read as a whole, such as
+    /// CLOB and NCLOB fetched as `String` and BLOB fetched as `Vec<u8>`.
+    /// The size is in characters for CLOB and NCLOB and in bytes for BLOB.
+    /// `None`, the default, means no limit.
+    ///
+    /// Conversion of a larger LOB fails with [Error::LobTooLarge][] without
+    /// reading its content. Use [Clob][] or [Blob][] to read it in pieces.
+    ///
+    /// It is applied to statements prepared after this call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_max_lob_fetch_size(Some(1024 * 1024));
+    /// let mut stmt = conn.execute("select id, document from documents", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     let (id, doc) = row.get_as::<(i32, String)>().unwrap();
+    ///     println!("{}: {}", id, doc);
+    /// }
+    /// ```
+    ///
+    /// [Error::LobTooLarge]: enum.Error.html#variant.LobTooLarge
+    /// [Clob]: struct.Clob.html
+    /// [Blob]: struct.Blob.html
+    pub fn set_max_lob_fetch_size(&self, size: Option<u64>) {
+        self.max_lob_fetch_size.set(size);
+    }
+
+    /// Returns the upper limit of the size of LOBs read as a whole.
+    /// See [set_max_lob_fetch_size](#method.set_max_lob_fetch_size).
+    pub fn max_lob_fetch_size(&self) -> Option<u64> {
+        self.max_lob_fetch_size.get()
+    }
+
+    /// Disallows implicit conversions which may lose data or succeed by
+    /// accident when `enable` is true.
+    ///
+    /// In strict mode, getting integers from BINARY_FLOAT and BINARY_DOUBLE
+    /// and getting numbers, [Timestamp][], intervals, [RowId][] and `Vec<u8>`
+    /// by parsing character data types return [Error::InvalidTypeConversion][].
+    /// Get them as `String` and parse it explicitly if you need it.
+    ///
+    /// It is applied to statements prepared after this call.
+    /// Use [Statement.set_strict_conversion()][] to change it per statement.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_strict_conversion(true);
+    /// let mut stmt = conn.execute("select '123', 1.5d from dual", &[]).unwrap();
+    /// let row = stmt.fetch().unwrap();
+    /// assert!(row.get::<_, i32>(0).is_err());
+    /// assert!(row.get::<_, i32>(1).is_err());
+    /// assert_eq!(row.get::<_, String>(0).unwrap().parse::<i32>().unwrap(), 123);
+    /// ```
+    ///
+    /// [Timestamp]: struct.Timestamp.html
+    /// [RowId]: struct.RowId.html
+    /// [Error::InvalidTypeConversion]: enum.Error.html#variant.InvalidTypeConversion
+    /// [Statement.set_strict_conversion()]: struct.Statement.html#method.set_strict_conversion
+    pub fn set_strict_conversion(&self, enable: bool) {
+        self.strict_conversion.set(enable);
+    }
+
+    /// Returns true when strict conversion is enabled.
+    /// See [set_strict_conversion](#method.set_strict_conversion).
+    pub fn strict_conversion(&self) -> bool {
+        self.strict_conversion.get()
+    }
+
+    /// Sets the policy to convert floating-point numbers to integers.
+    /// See [FloatToInt][] for details.
+    ///
+    /// It is applied to statements prepared after this call.
+    /// Use [Statement.set_float_to_int()][] to change it per statement.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oracle::{Connection, FloatToInt};
+    ///
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// conn.set_float_to_int(FloatToInt::Round);
+    /// let mut stmt = conn.execute("select 2.5d, -2.5d from dual", &[]).unwrap();
+    /// let row = stmt.fetch().unwrap();
+    /// assert_eq!(row.get_as::<(i32, i32)>().unwrap(), (3, -3));
+    /// ```
+    ///
+    /// [FloatToInt]: enum.FloatToInt.html
+    /// [Statement.set_float_to_int()]: struct.Statement.html#method.set_float_to_int
+    pub fn set_float_to_int(&self, policy: FloatToInt) {
+        self.float_to_int.set(policy);
+    }
+
+    /// Gets the policy set by [set_float_to_int](#method.set_float_to_int).
+    pub fn float_to_int(&self) -> FloatToInt {
+        self.float_to_int.get()
+    }
+
+    /// Enables [DBMS_OUTPUT][] in the session without buffer size limit.
+    ///
+    /// Use [dbms_output_lines](#method.dbms_output_lines) to get lines
+    /// written by `DBMS_OUTPUT.PUT_LINE`.
+    ///
+    /// [DBMS_OUTPUT]: https://docs.oracle.com/database/122/ARPLS/DBMS_OUTPUT.htm
+    pub fn enable_dbms_output(&self) -> Result<()> {
+        self.execute_internal("begin dbms_output.enable(null); end;", &[])?;
+        Ok(())
+    }
+
+    /// Gets and removes lines buffered by `DBMS_OUTPUT.PUT_LINE`.
+    ///
+    /// Lines are retrieved by `DBMS_OUTPUT.GET_LINES` with 100 lines
+    /// per round-trip. An empty vector is returned when [DBMS_OUTPUT][] isn't
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// conn.enable_dbms_output().unwrap();
+    /// conn.execute("begin dbms_output.put_line('hello'); end;", &[]).unwrap();
+    /// for line in conn.dbms_output_lines().unwrap() {
+    ///     println!("{}", line);
+    /// }
+    /// ```
+    ///
+    /// [DBMS_OUTPUT]: https://docs.oracle.com/database/122/ARPLS/DBMS_OUTPUT.htm
+    pub fn dbms_output_lines(&self) -> Result<Vec<String>> {
+        const NUM_LINES: i32 = 100;
+        let oratype = OracleType::Object(self.object_type("SYS.DBMSOUTPUT_LINESARRAY")?);
+        let mut stmt = self.prepare_internal("begin dbms_output.get_lines(:lines, :numlines); end;")?;
+        let mut lines = Vec::new();
+        loop {
+            stmt.execute(&[&oratype, &NUM_LINES])?;
+            let num: i32 = stmt.bind_value(2)?;
+            let coll: Collection = stmt.bind_value(1)?;
+            for i in 0..num {
+                let line: Option<String> = coll.get(i)?;
+                lines.push(line.unwrap_or_default());
+            }
+            if num < NUM_LINES {
+                return Ok(lines);
+            }
+        }
     }
 
     /// Starts up a database
@@ -895,12 +1854,9 @@ impl Connection {
                                password.ptr, password.len, connect_string.ptr,
                                connect_string.len, common_param,
                                &mut param, &mut handle));
-        Ok(Connection{
-            ctxt: ctxt,
-            handle: handle,
-            tag: OdpiStr::new(param.outTag, param.outTagLength).to_string(),
-            tag_found: conn_param.outTagFound != 0,
-        })
+        Ok(Connection::from_handle(ctxt, handle,
+                                   OdpiStr::new(param.outTag, param.outTagLength).to_string(),
+                                   conn_param.outTagFound != 0))
     }
 
     fn close_internal(&self, mode: dpiConnCloseMode, tag: &str) -> Result<()> {
@@ -909,10 +1865,52 @@ impl Connection {
                 dpiConn_close(self.handle, mode, tag.ptr, tag.len));
         Ok(())
     }
+
+    fn from_handle(ctxt: &'static Context, handle: *mut dpiConn, tag: String, tag_found: bool) -> Connection {
+        Connection {
+            ctxt: ctxt,
+            handle: handle,
+            tag: tag,
+            tag_found: tag_found,
+            metrics_callback: RefCell::new(None),
+            bool_convention: RefCell::new(None),
+            number_define: Cell::new(NumberDefine::Auto),
+            binary_float_bind: Cell::new(false),
+            strict_utf8: Cell::new(false),
+            strict_conversion: Cell::new(false),
+            float_to_int: Cell::new(FloatToInt::Checked),
+            max_lob_fetch_size: Cell::new(None),
+            autocommit: Cell::new(false),
+            object_type_cache: RefCell::new(HashMap::new()),
+            watchdog: RefCell::new(None),
+        }
+    }
+
+    // Creates a connection sharing the handle with a connection which
+    // created a LOB.
+    pub(crate) fn from_lob_conn(ctxt: &'static Context, handle: *mut dpiConn) -> Connection {
+        unsafe { dpiConn_addRef(handle) };
+        Connection::from_handle(ctxt, handle, String::new(), false)
+    }
 }
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        // Join the watchdog thread before the handle is released.
+        self.watchdog.borrow_mut().take();
         let _ = unsafe { dpiConn_release(self.handle) };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_options() {
+        assert_eq!(CommitOptions::new().to_sql(), "COMMIT");
+        assert_eq!(CommitOptions::new().wait(false).to_sql(), "COMMIT WRITE NOWAIT");
+        assert_eq!(CommitOptions::new().batch(false).to_sql(), "COMMIT WRITE IMMEDIATE");
+        assert_eq!(CommitOptions::new().wait(true).batch(true).to_sql(), "COMMIT WRITE WAIT BATCH");
+    }
+}
@@ -33,6 +33,7 @@
 use std::ffi::CStr;
 use std::error;
 use std::fmt;
+use std::io;
 use std::num;
 use std::slice;
 use std::str;
@@ -40,6 +41,8 @@ use try_from;
 use binding::dpiErrorInfo;
 use binding::dpiContext_getError;
 use Context;
+use Result;
+use XaErrorCode;
 
 /// Enum listing possible errors from rust-oracle.
 pub enum Error {
@@ -74,9 +77,45 @@ pub enum Error {
     /// Error when the column name is not in the SQL.
     InvalidColumnName(String),
 
+    /// Error when the column name matches more than one column.
+    /// Use suffixed names returned by [Statement.column_names][] in this case.
+    /// (The first matching column was used in earlier versions.)
+    ///
+    /// [Statement.column_names]: struct.Statement.html#method.column_names
+    AmbiguousColumnName(String),
+
     /// Error when the specified attribute name is not found.
     InvalidAttributeName(String),
 
+    /// Error when a name embedded in SQL statements, such as a savepoint
+    /// name or a table name passed to [codegen::table_struct][], isn't
+    /// a valid identifier.
+    ///
+    /// [codegen::table_struct]: codegen/fn.table_struct.html
+    InvalidIdentifier(String),
+
+    /// Error when a string contains invalid UTF-8 byte sequences in strict mode
+    /// set by [Connection.set_strict_utf8][]. The first is the column name,
+    /// which is empty when it is unknown, and the second is the byte offset of
+    /// the invalid sequence.
+    ///
+    /// [Connection.set_strict_utf8]: struct.Connection.html#method.set_strict_utf8
+    InvalidUtf8(String, usize),
+
+    /// Error when a fetched code doesn't match any variant of an enum
+    /// defined by [impl_sql_for_enum!][]. The first is the code and the
+    /// second is the enum name.
+    ///
+    /// [impl_sql_for_enum!]: macro.impl_sql_for_enum.html
+    UnknownEnumCode(String, &'static str),
+
+    /// Error when the size of a LOB fetched as a whole exceeds the limit
+    /// set by [Connection.set_max_lob_fetch_size][]. The first is the size
+    /// of the LOB and the second is the limit.
+    ///
+    /// [Connection.set_max_lob_fetch_size]: struct.Connection.html#method.set_max_lob_fetch_size
+    LobTooLarge(u64, u64),
+
     /// Error when an uninitialized bind value is accessed. Bind values
     /// must be initialized by [Statement.bind][], [Statement.execute][]
     /// or [Connection.execute][] in advance.
@@ -89,6 +128,34 @@ pub enum Error {
     /// Error when no more rows exist in the SQL.
     NoMoreData,
 
+    /// Error when writing to or reading from `std::io` fails.
+    IoError(io::Error),
+
+    /// Error when an operation which must be done at the start of a
+    /// transaction is requested after the transaction started.
+    TransactionInProgress,
+
+    /// Error when [Batch.append_row][] must send rows to the server but
+    /// rows which failed to be sent are still in the buffer. The value is
+    /// the number of the rows. Send them again by [Batch.execute][] or
+    /// discard them by [Batch.clear][] in advance.
+    ///
+    /// [Batch.append_row]: struct.Batch.html#method.append_row
+    /// [Batch.execute]: struct.Batch.html#method.execute
+    /// [Batch.clear]: struct.Batch.html#method.clear
+    UnsentBatchRows(u32),
+
+    /// Error when an operation needs the connection which a value belongs
+    /// to but it is unknown, such as [Lob.is_temporary][] of a LOB got
+    /// from an object attribute.
+    ///
+    /// [Lob.is_temporary]: trait.Lob.html#method.is_temporary
+    UnknownConnection,
+
+    /// Error when a function of `DBMS_XA` returns an XA return code
+    /// other than `XA_OK`.
+    XaError(XaErrorCode),
+
     /// Internal error. When you get this error, please report it with a test case to reproduce it.
     InternalError(String),
 }
@@ -139,6 +206,7 @@ pub struct DbError {
     message: String,
     fn_name: String,
     action: String,
+    context: Vec<String>,
 }
 
 /// Oracle database or ODPI-C error
@@ -150,6 +218,7 @@ impl DbError {
             message: message,
             fn_name: fn_name,
             action: action,
+            context: Vec::new(),
         }
     }
 
@@ -177,15 +246,61 @@ impl DbError {
     pub fn action(&self) -> &String {
         &self.action
     }
+
+    /// operations in rust-oracle during which the error occurred.
+    /// The innermost operation comes first.
+    pub fn context(&self) -> &Vec<String> {
+        &self.context
+    }
+}
+
+fn fmt_context(f: &mut fmt::Formatter, context: &[String]) -> fmt::Result {
+    for ctx in context {
+        write!(f, ", while {}", ctx)?;
+    }
+    Ok(())
+}
+
+impl Error {
+    /// Appends the operation during which the error occurred.
+    /// This affects only errors from Oracle client libraries and ODPI-C.
+    pub(crate) fn add_context<F>(self, f: F) -> Error where F: FnOnce() -> String {
+        match self {
+            Error::OciError(mut err) => {
+                err.context.push(f());
+                Error::OciError(err)
+            },
+            Error::DpiError(mut err) => {
+                err.context.push(f());
+                Error::DpiError(err)
+            },
+            _ => self,
+        }
+    }
+}
+
+/// Adds context to errors in `Result`.
+pub(crate) trait ErrorContext<T> {
+    fn context<F>(self, f: F) -> Result<T> where F: FnOnce() -> String;
+}
+
+impl<T> ErrorContext<T> for Result<T> {
+    fn context<F>(self, f: F) -> Result<T> where F: FnOnce() -> String {
+        self.map_err(|err| err.add_context(f))
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Error::OciError(ref err) =>
-                write!(f, "OCI Error: {}", err.message),
-            Error::DpiError(ref err) =>
-                write!(f, "DPI Error: {}", err.message),
+            Error::OciError(ref err) => {
+                write!(f, "OCI Error: {}", err.message)?;
+                fmt_context(f, &err.context)
+            },
+            Error::DpiError(ref err) => {
+                write!(f, "DPI Error: {}", err.message)?;
+                fmt_context(f, &err.context)
+            },
             Error::NullValue =>
                 write!(f, "NULL value found"),
             Error::ParseError(ref err) =>
@@ -202,12 +317,36 @@ impl fmt::Display for Error {
                 write!(f, "invalid column index (zero-based): {}", idx),
             Error::InvalidColumnName(ref name) =>
                 write!(f, "invalid column name: {}", name),
+            Error::AmbiguousColumnName(ref name) =>
+                write!(f, "ambiguous column name: {}", name),
             Error::InvalidAttributeName(ref name) =>
                 write!(f, "invalid attribute name: {}", name),
+            Error::InvalidIdentifier(ref name) =>
+                write!(f, "invalid identifier: {}", name),
+            Error::InvalidUtf8(ref name, offset) =>
+                if name.is_empty() {
+                    write!(f, "invalid UTF-8 sequence at byte offset {}", offset)
+                } else {
+                    write!(f, "invalid UTF-8 sequence at byte offset {} in column {}", offset, name)
+                },
+            Error::UnknownEnumCode(ref code, name) =>
+                write!(f, "unknown code {} for {}", code, name),
+            Error::LobTooLarge(size, limit) =>
+                write!(f, "LOB size {} exceeds the max LOB fetch size {}", size, limit),
             Error::UninitializedBindValue =>
                 write!(f, "Try to access uninitialized bind value"),
             Error::NoMoreData =>
                 write!(f, "No more data to be fetched"),
+            Error::IoError(ref err) =>
+                write!(f, "I/O Error: {}", err),
+            Error::TransactionInProgress =>
+                write!(f, "transaction in progress"),
+            Error::UnsentBatchRows(num) =>
+                write!(f, "{} rows which failed to be sent remain in the batch", num),
+            Error::UnknownConnection =>
+                write!(f, "unknown connection"),
+            Error::XaError(code) =>
+                write!(f, "XA error: {} ({})", code, code.code()),
             Error::InternalError(ref msg) =>
                 write!(f, "Internal Error: {}", msg),
         }
@@ -218,11 +357,11 @@ impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Error::OciError(ref err) =>
-                write!(f, "OCI Error: (code: {}, offset: {}, message:{}, fn_name: {}, action: {})",
-                       err.code, err.offset, err.message, err.fn_name, err.action),
+                write!(f, "OCI Error: (code: {}, offset: {}, message:{}, fn_name: {}, action: {}, context: {:?})",
+                       err.code, err.offset, err.message, err.fn_name, err.action, err.context),
             Error::DpiError(ref err) =>
-                write!(f, "OCI Error: (code: {}, offset: {}, message:{}, fn_name: {}, action: {})",
-                       err.code, err.offset, err.message, err.fn_name, err.action),
+                write!(f, "OCI Error: (code: {}, offset: {}, message:{}, fn_name: {}, action: {}, context: {:?})",
+                       err.code, err.offset, err.message, err.fn_name, err.action, err.context),
             Error::NullValue =>
                 write!(f, "NULLValue"),
             Error::ParseError(ref err) =>
@@ -239,12 +378,32 @@ impl fmt::Debug for Error {
                 write!(f, "InvalidColumnIndex: {}", idx),
             Error::InvalidColumnName(ref name) =>
                 write!(f, "InvalidColumnName: {}", name),
+            Error::AmbiguousColumnName(ref name) =>
+                write!(f, "AmbiguousColumnName: {}", name),
             Error::InvalidAttributeName(ref name) =>
                 write!(f, "InvalidAttributeName: {}", name),
+            Error::InvalidIdentifier(ref name) =>
+                write!(f, "InvalidIdentifier: {}", name),
+            Error::InvalidUtf8(ref name, offset) =>
+                write!(f, "InvalidUtf8 {{ column: {}, offset: {} }}", name, offset),
+            Error::UnknownEnumCode(ref code, name) =>
+                write!(f, "UnknownEnumCode {{ code: {}, enum: {} }}", code, name),
+            Error::LobTooLarge(size, limit) =>
+                write!(f, "LobTooLarge {{ size: {}, limit: {} }}", size, limit),
             Error::UninitializedBindValue =>
                 write!(f, "UninitializedBindValue"),
             Error::NoMoreData =>
                 write!(f, "NoMoreData"),
+            Error::IoError(ref err) =>
+                write!(f, "IoError: {:?}", err),
+            Error::TransactionInProgress =>
+                write!(f, "TransactionInProgress"),
+            Error::UnsentBatchRows(num) =>
+                write!(f, "UnsentBatchRows: {}", num),
+            Error::UnknownConnection =>
+                write!(f, "UnknownConnection"),
+            Error::XaError(code) =>
+                write!(f, "XaError: {}", code),
             Error::InternalError(_) =>
                 write!(f, "{}", *self),
         }
@@ -264,9 +423,19 @@ impl error::Error for Error {
             Error::InvalidBindName(_) => "index bind name",
             Error::InvalidColumnIndex(_) => "index column index",
             Error::InvalidColumnName(_) => "index column name",
+            Error::AmbiguousColumnName(_) => "ambiguous column name",
             Error::InvalidAttributeName(_) => "index attribute name",
+            Error::InvalidIdentifier(_) => "invalid identifier",
+            Error::InvalidUtf8(_, _) => "invalid UTF-8",
+            Error::UnknownEnumCode(_, _) => "unknown enum code",
+            Error::LobTooLarge(_, _) => "LOB too large",
             Error::UninitializedBindValue => "uninitialided bind value error",
             Error::NoMoreData => "no more data",
+            Error::IoError(_) => "I/O error",
+            Error::TransactionInProgress => "transaction in progress",
+            Error::UnsentBatchRows(_) => "unsent batch rows",
+            Error::UnknownConnection => "unknown connection",
+            Error::XaError(_) => "XA error",
             Error::InternalError(_) => "internal error",
         }
     }
@@ -274,6 +443,7 @@ impl error::Error for Error {
     fn cause(&self) -> Option<&error::Error> {
         match *self {
             Error::ParseError(ref err) => Some(err.as_ref()),
+            Error::IoError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -303,6 +473,21 @@ impl From<try_from::TryFromIntError> for Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IoError(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::IoError(err) => err,
+            err => io::Error::new(io::ErrorKind::Other, err.to_string()),
+        }
+    }
+}
+
 impl From<str::Utf8Error> for Error {
     fn from(err: str::Utf8Error) -> Self {
         Error::ParseError(Box::new(err))
@@ -30,11 +30,27 @@
 // authors and should not be interpreted as representing official policies, either expressed
 // or implied, of the authors.
 
+#[cfg(feature = "bigdecimal")]
+extern crate bigdecimal;
 #[cfg(feature = "chrono")]
 extern crate chrono;
+#[cfg(feature = "chrono-tz")]
+extern crate chrono_tz;
+#[cfg(feature = "geo-types")]
+extern crate geo_types;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 extern crate try_from;
+#[cfg(feature = "uuid")]
+extern crate uuid;
 
 use std::os::raw::c_char;
 use std::ptr;
@@ -47,39 +63,102 @@ use std::slice;
 #[allow(improper_ctypes)]
 mod binding;
 #[macro_use]
+mod macros;
+#[macro_use]
 mod error;
+mod aq;
+mod batch;
+pub mod codegen;
 mod connection;
+mod retry;
 mod statement;
 mod sql_value;
+mod subscription;
 mod types;
 mod util;
+mod xa;
 
+pub use aq::Agent;
+pub use aq::DeliveryMode;
+pub use aq::DeqMode;
+pub use aq::DeqNavigation;
+pub use aq::DeqOptions;
+pub use aq::EnqPayload;
+pub use aq::EnqOptions;
+pub use aq::Message;
+pub use aq::MessageState;
+pub use aq::MsgProps;
+pub use aq::Payload;
+pub use aq::Queue;
+pub use aq::Visibility;
+pub use batch::Batch;
+pub use batch::copy_rows;
 pub use connection::AuthMode;
 pub use connection::StartupMode;
 pub use connection::ShutdownMode;
 pub use connection::Purity;
 pub use connection::Connector;
+pub use connection::CommitOptions;
 pub use connection::Connection;
+pub use connection::IsolationLevel;
+pub use connection::LtxidOutcome;
 pub use error::Error;
 pub use error::ParseOracleTypeError;
 pub use error::DbError;
+pub use retry::RetryPolicy;
+pub use retry::is_transient_error;
 pub use statement::StatementType;
+pub use statement::NumberDefine;
 pub use statement::Statement;
+pub use statement::StatementMetrics;
 pub use statement::ColumnInfo;
 pub use statement::Row;
+pub use statement::RowIter;
+pub use statement::RowValue;
 pub use sql_value::SqlValue;
+pub use sql_value::FloatToInt;
+pub use subscription::Event;
+pub use subscription::EventType;
+pub use subscription::Operation;
+pub use subscription::QueryChange;
+pub use subscription::RowChange;
+pub use subscription::SubscrOptions;
+pub use subscription::Subscription;
+pub use subscription::TableChange;
+pub use types::bfile::Bfile;
+pub use types::bool_convention::BoolConvention;
 pub use types::FromSql;
+pub use types::FromSqlRef;
 pub use types::ToSql;
 pub use types::ToSqlNull;
+pub use types::lob::Blob;
+pub use types::lob::BlobChunks;
+pub use types::lob::Clob;
+pub use types::lob::ClobChunks;
+pub use types::lob::ClobWriter;
+pub use types::lob::Lob;
+#[cfg(feature = "tokio")]
+pub use types::tokio::{AsyncLob, AsyncLobType};
 pub use types::object::Collection;
+pub use types::object::CollectionIter;
+pub use types::object::FromObject;
 pub use types::object::Object;
 pub use types::object::ObjectType;
 pub use types::object::ObjectTypeAttr;
+pub use types::number::OracleNumber;
 pub use types::oracle_type::OracleType;
+pub use types::rowid::RowId;
+#[cfg(feature = "sdo_geometry")]
+pub use types::sdo_geometry::SdoGeometry;
+#[cfg(feature = "sdo_geometry")]
+pub use types::sdo_geometry::SdoPoint;
 pub use types::timestamp::Timestamp;
+pub use types::duration::SignedDuration;
 pub use types::interval_ds::IntervalDS;
 pub use types::interval_ym::IntervalYM;
 pub use types::version::Version;
+pub use xa::Xid;
+pub use xa::XaErrorCode;
 
 use binding::*;
 use types::oracle_type::NativeType;
@@ -0,0 +1,167 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+/// Implements [FromSql][], [ToSql][] and [ToSqlNull][] for a newtype
+/// by delegating them to the inner type.
+///
+/// Domain types wrapping an integer, a string and so on can be bound and
+/// fetched as they are. The inner type must implement the three traits.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[macro_use]
+/// extern crate oracle;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct UserId(i64);
+///
+/// impl_sql_for_newtype!(UserId(i64));
+///
+/// # fn main() {
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select empno from emp where empno = :1",
+///                             &[&UserId(7369)]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// assert_eq!(row.get::<_, UserId>(0).unwrap(), UserId(7369));
+/// # }
+/// ```
+///
+/// [FromSql]: trait.FromSql.html
+/// [ToSql]: trait.ToSql.html
+/// [ToSqlNull]: trait.ToSqlNull.html
+#[macro_export]
+macro_rules! impl_sql_for_newtype {
+    ($type:ident($inner:ty)) => {
+        impl $crate::FromSql for $type {
+            fn from_sql(val: &$crate::SqlValue) -> $crate::Result<$type> {
+                <$inner as $crate::FromSql>::from_sql(val).map($type)
+            }
+        }
+        impl $crate::ToSqlNull for $type {
+            fn oratype_for_null() -> $crate::Result<$crate::OracleType> {
+                <$inner as $crate::ToSqlNull>::oratype_for_null()
+            }
+        }
+        impl $crate::ToSql for $type {
+            fn oratype(&self) -> $crate::Result<$crate::OracleType> {
+                $crate::ToSql::oratype(&self.0)
+            }
+            fn binary_float_oratype(&self) -> $crate::Result<$crate::OracleType> {
+                $crate::ToSql::binary_float_oratype(&self.0)
+            }
+            fn to_sql(&self, val: &mut $crate::SqlValue) -> $crate::Result<()> {
+                $crate::ToSql::to_sql(&self.0, val)
+            }
+        }
+    };
+}
+
+/// Implements [FromSql][], [ToSql][] and [ToSqlNull][] for a fieldless enum
+/// stored as code values in a column.
+///
+/// Each variant is mapped to an explicit code of the type after the colon,
+/// which is usually `String` for VARCHAR2 columns or an integer type for
+/// NUMBER columns. Fetching a code which doesn't match any variant
+/// returns [Error::UnknownEnumCode][].
+///
+/// # Examples
+///
+/// ```no_run
+/// #[macro_use]
+/// extern crate oracle;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Status { Active, Inactive }
+///
+/// impl_sql_for_enum!(Status: String {
+///     Active => "A",
+///     Inactive => "I",
+/// });
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Priority { Low, High }
+///
+/// impl_sql_for_enum!(Priority: i32 {
+///     Low => 1,
+///     High => 9,
+/// });
+///
+/// # fn main() {
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select :1, :2 from dual",
+///                             &[&Status::Active, &Priority::High]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// assert_eq!(row.get::<_, Status>(0).unwrap(), Status::Active);
+/// assert_eq!(row.get::<_, Priority>(1).unwrap(), Priority::High);
+/// # }
+/// ```
+///
+/// [FromSql]: trait.FromSql.html
+/// [ToSql]: trait.ToSql.html
+/// [ToSqlNull]: trait.ToSqlNull.html
+/// [Error::UnknownEnumCode]: enum.Error.html#variant.UnknownEnumCode
+#[macro_export]
+macro_rules! impl_sql_for_enum {
+    ($type:ident: $code_type:ty { $($variant:ident => $code:expr),+ $(,)* }) => {
+        impl $crate::FromSql for $type {
+            fn from_sql(val: &$crate::SqlValue) -> $crate::Result<$type> {
+                let code = <$code_type as $crate::FromSql>::from_sql(val)?;
+                $(
+                    if code == $code {
+                        return Ok($type::$variant);
+                    }
+                )+
+                Err($crate::Error::UnknownEnumCode(code.to_string(), stringify!($type)))
+            }
+        }
+        impl $crate::ToSqlNull for $type {
+            fn oratype_for_null() -> $crate::Result<$crate::OracleType> {
+                <$code_type as $crate::ToSqlNull>::oratype_for_null()
+            }
+        }
+        impl $crate::ToSql for $type {
+            fn oratype(&self) -> $crate::Result<$crate::OracleType> {
+                let code: $code_type = match *self {
+                    $( $type::$variant => $code.into(), )+
+                };
+                $crate::ToSql::oratype(&code)
+            }
+            fn to_sql(&self, val: &mut $crate::SqlValue) -> $crate::Result<()> {
+                let code: $code_type = match *self {
+                    $( $type::$variant => $code.into(), )+
+                };
+                $crate::ToSql::to_sql(&code, val)
+            }
+        }
+    };
+}
@@ -0,0 +1,311 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cell::Cell;
+use std::cmp;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::thread;
+use std::time::Duration;
+
+use Error;
+use Result;
+
+/// Oracle error codes regarded as transient by default
+///
+/// * ORA-00054: resource busy and acquire with NOWAIT specified or timeout expired
+/// * ORA-00060: deadlock detected while waiting for resource
+/// * ORA-04068: existing state of packages has been discarded
+/// * ORA-12514: TNS:listener does not currently know of service requested in connect descriptor
+/// * ORA-12516: TNS:listener could not find available handler with matching protocol stack
+/// * ORA-12520: TNS:listener could not find available handler for requested type of server
+/// * ORA-12528: TNS:listener: all appropriate instances are blocking new connections
+/// * ORA-12541: TNS:no listener
+/// * ORA-30006: resource busy; acquire with WAIT timeout expired
+const TRANSIENT_ERROR_CODES: [i32; 9] = [54, 60, 4068, 12514, 12516, 12520, 12528, 12541, 30006];
+
+/// Returns true when the error is regarded as transient by default.
+///
+/// See [RetryPolicy.retry_if](struct.RetryPolicy.html#method.retry_if)
+/// for the list of transient errors.
+pub fn is_transient_error(err: &Error) -> bool {
+    match *err {
+        Error::OciError(ref dberr) => TRANSIENT_ERROR_CODES.contains(&dberr.code()),
+        _ => false,
+    }
+}
+
+/// Policy to retry operations failed by transient errors
+///
+/// The delay before the n-th retry is `initial_backoff * multiplier^(n-1)`,
+/// which is limited by `max_backoff`. When jitter is enabled, the delay is
+/// randomized between the half of the value and the value.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut policy = oracle::RetryPolicy::new();
+/// policy.max_attempts(5)
+///     .initial_backoff(Duration::from_millis(200))
+///     .max_backoff(Duration::from_secs(5));
+///
+/// conn.execute_with_retry("update emp set sal = sal * 1.1 where empno = :1", &[&7369], &policy).unwrap();
+///
+/// // Connections are retried by `retry`.
+/// let connector = oracle::Connector::new("scott", "tiger", "");
+/// let conn = policy.retry(|| connector.connect()).unwrap();
+/// ```
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    jitter: bool,
+    retry_if: Box<Fn(&Error) -> bool>,
+}
+
+impl RetryPolicy {
+    /// Creates a retry policy with the following default values.
+    ///
+    /// | parameter | default value |
+    /// | --- | --- |
+    /// | max_attempts | 3 |
+    /// | initial_backoff | 100 milliseconds |
+    /// | max_backoff | 10 seconds |
+    /// | multiplier | 2 |
+    /// | jitter | true |
+    /// | retry_if | [is_transient_error](fn.is_transient_error.html) |
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2,
+            jitter: true,
+            retry_if: Box::new(is_transient_error),
+        }
+    }
+
+    /// Sets the maximum number of attempts including the first one.
+    pub fn max_attempts<'a>(&'a mut self, max_attempts: u32) -> &'a mut RetryPolicy {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn initial_backoff<'a>(&'a mut self, backoff: Duration) -> &'a mut RetryPolicy {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the upper limit of delay between retries.
+    pub fn max_backoff<'a>(&'a mut self, backoff: Duration) -> &'a mut RetryPolicy {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Sets the factor by which the delay is multiplied for each retry.
+    pub fn multiplier<'a>(&'a mut self, multiplier: u32) -> &'a mut RetryPolicy {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Enables or disables randomization of delay.
+    pub fn jitter<'a>(&'a mut self, jitter: bool) -> &'a mut RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets a function to classify errors. Operations are retried only when
+    /// it returns true.
+    ///
+    /// The default is [is_transient_error](fn.is_transient_error.html), which
+    /// returns true for the following Oracle errors.
+    ///
+    /// * ORA-00054: resource busy and acquire with NOWAIT specified or timeout expired
+    /// * ORA-00060: deadlock detected while waiting for resource
+    /// * ORA-04068: existing state of packages has been discarded
+    /// * ORA-12514: TNS:listener does not currently know of service requested in connect descriptor
+    /// * ORA-12516: TNS:listener could not find available handler with matching protocol stack
+    /// * ORA-12520: TNS:listener could not find available handler for requested type of server
+    /// * ORA-12528: TNS:listener: all appropriate instances are blocking new connections
+    /// * ORA-12541: TNS:no listener
+    /// * ORA-30006: resource busy; acquire with WAIT timeout expired
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut policy = oracle::RetryPolicy::new();
+    /// // retry also on ORA-01013: user requested cancel of current operation
+    /// policy.retry_if(|err| match *err {
+    ///     oracle::Error::OciError(ref dberr) if dberr.code() == 1013 => true,
+    ///     _ => oracle::is_transient_error(err),
+    /// });
+    /// ```
+    pub fn retry_if<'a, F>(&'a mut self, f: F) -> &'a mut RetryPolicy where F: Fn(&Error) -> bool + 'static {
+        self.retry_if = Box::new(f);
+        self
+    }
+
+    /// Calls `f` until it succeeds, it fails by a non-transient error or
+    /// the number of attempts reaches `max_attempts`. The last result is
+    /// returned.
+    pub fn retry<T, F>(&self, mut f: F) -> Result<T> where F: FnMut() -> Result<T> {
+        let mut attempts = 1;
+        loop {
+            match f() {
+                Err(ref err) if attempts < self.max_attempts && (self.retry_if)(err) => (),
+                res => return res,
+            }
+            thread::sleep(self.backoff(attempts));
+            attempts += 1;
+        }
+    }
+
+    // Returns the delay after `attempts` attempts.
+    fn backoff(&self, attempts: u32) -> Duration {
+        let mut backoff = self.initial_backoff;
+        for _ in 1..attempts {
+            backoff = match backoff.checked_mul(self.multiplier) {
+                Some(d) => cmp::min(d, self.max_backoff),
+                None => self.max_backoff,
+            };
+        }
+        let backoff = cmp::min(backoff, self.max_backoff);
+        if self.jitter {
+            let nanos = backoff.as_secs() * 1_000_000_000 + backoff.subsec_nanos() as u64;
+            let half = nanos / 2;
+            if half == 0 {
+                return backoff;
+            }
+            let nanos = half + next_random() % (nanos - half + 1);
+            Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new()
+    }
+}
+
+thread_local!(static RANDOM_STATE: Cell<u64> = Cell::new(random_seed()));
+
+// Returns a seed taken from the random keys of RandomState, which
+// differ per process and per call.
+fn random_seed() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    match hasher.finish() {
+        0 => 0x9E37_79B9_7F4A_7C15,
+        seed => seed,
+    }
+}
+
+// Returns a pseudo-random number by xorshift64* seeded once per thread.
+fn next_random() -> u64 {
+    RANDOM_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DbError;
+
+    fn ora_error(code: i32) -> Error {
+        Error::OciError(DbError::new(code, 0, format!("ORA-{:05}", code), "".to_string(), "".to_string()))
+    }
+
+    #[test]
+    fn retry() {
+        let mut policy = RetryPolicy::new();
+        policy.initial_backoff(Duration::new(0, 0));
+
+        let mut count = 0;
+        let res = policy.retry(|| { count += 1; if count < 3 { Err(ora_error(60)) } else { Ok(count) } });
+        assert_eq!(res.unwrap(), 3);
+
+        let mut count = 0;
+        let res: Result<()> = policy.retry(|| { count += 1; Err(ora_error(60)) });
+        assert!(res.is_err());
+        assert_eq!(count, 3);
+
+        let mut count = 0;
+        let res: Result<()> = policy.retry(|| { count += 1; Err(ora_error(942)) });
+        assert!(res.is_err());
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn backoff() {
+        let mut policy = RetryPolicy::new();
+        policy.initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500))
+            .jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+
+        policy.jitter(true);
+        for i in 1..5 {
+            let d = policy.backoff(i);
+            assert!(Duration::from_millis(50) <= d && d <= Duration::from_millis(500));
+        }
+        // jitter isn't limited to subsecond values.
+        policy.initial_backoff(Duration::from_secs(10))
+            .max_backoff(Duration::from_secs(10));
+        assert!((0..100).any(|_| policy.backoff(1) > Duration::from_secs(6)));
+    }
+
+    #[test]
+    fn next_random() {
+        let values: Vec<u64> = (0..10).map(|_| super::next_random()).collect();
+        for i in 1..values.len() {
+            assert!(values[i] != values[i - 1]);
+        }
+    }
+}
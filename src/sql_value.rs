@@ -32,36 +32,67 @@
 
 use std::fmt;
 use std::ptr;
+use std::rc::Rc;
 use std::slice;
 use std::str;
 use try_from::TryInto;
 
 use binding::*;
+use Bfile;
+use Blob;
+use Clob;
+use BoolConvention;
 use Connection;
 use Context;
 use Collection;
 use Error;
+use FromObject;
 use FromSql;
 use IntervalDS;
 use IntervalYM;
 use NativeType;
 use Object;
 use ObjectType;
+use OracleNumber;
 use OracleType;
+use OdpiStr;
+use ParseOracleTypeError;
 use Result;
+use RowId;
 use Timestamp;
 use ToSql;
 
+use to_odpi_str;
+use types::lob::write_blob_pieces;
+use types::lob::write_clob_pieces;
 use util::check_number_format;
+use util::parse_float;
+use util::parse_small_integer;
 use util::parse_str_into_raw;
 use util::set_hex_string;
 
+// Converts a float to an integer according to FloatToInt. NaN is always
+// Error::Overflow. Infinity and values out of the range of the integer type
+// are Error::Overflow unless FloatToInt::Saturate is used.
+//
+// The range is checked after truncation or rounding so that the bounds are
+// symmetric: both 127.9 and -128.9 are converted to i8.
+//
+// The upper bound is compared by `<` with `max_value() + 1` because
+// `max_value()` of i64 and u64 isn't representable as f64 and is rounded up
+// to the next power of two. NaN fails both comparisons.
 macro_rules! flt_to_int {
-    ($expr:expr, $src_type:ident, $dest_type:ident) => {
+    ($expr:expr, $src_type:ident, $dest_type:ident, $policy:expr) => {
         {
             let src_val = $expr;
-            if $dest_type::min_value() as $src_type <= src_val && src_val <= $dest_type::max_value() as $src_type {
-                Ok(src_val as $dest_type)
+            let val = match $policy {
+                FloatToInt::Round => src_val.round(),
+                FloatToInt::Checked | FloatToInt::Saturate => src_val.trunc(),
+            };
+            if $dest_type::min_value() as $src_type <= val && val < $dest_type::max_value() as $src_type + 1.0 {
+                Ok(val as $dest_type)
+            } else if $policy == FloatToInt::Saturate && !val.is_nan() {
+                Ok(if val < 0.0 { $dest_type::min_value() } else { $dest_type::max_value() })
             } else {
                 Err(Error::Overflow(src_val.to_string(), stringify!($dest_type)))
             }
@@ -69,22 +100,66 @@ macro_rules! flt_to_int {
     }
 }
 
+/// Policy to convert floating-point numbers to integers
+///
+/// This is used when BINARY_FLOAT and BINARY_DOUBLE values are got as
+/// integers and when `f32` and `f64` values are set to integer variables.
+/// It is set by [Connection.set_float_to_int()][] or
+/// [Statement.set_float_to_int()][].
+///
+/// [Connection.set_float_to_int()]: struct.Connection.html#method.set_float_to_int
+/// [Statement.set_float_to_int()]: struct.Statement.html#method.set_float_to_int
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FloatToInt {
+    /// Truncates fractions toward zero. For example, 255.5 is 255 as `u8`
+    /// and -128.5 is -128 as `i8`. Values out of the range of the integer
+    /// type after truncation are [Error::Overflow][]. This is the default.
+    ///
+    /// [Error::Overflow]: enum.Error.html#variant.Overflow
+    Checked,
+
+    /// Rounds to the nearest integer, half away from zero. Values out of
+    /// the range of the integer type are [Error::Overflow][].
+    ///
+    /// [Error::Overflow]: enum.Error.html#variant.Overflow
+    Round,
+
+    /// Truncates fractions. Values out of the range of the integer type,
+    /// including infinity, become the minimum or maximum value of the type.
+    Saturate,
+}
+
+impl Default for FloatToInt {
+    fn default() -> FloatToInt {
+        FloatToInt::Checked
+    }
+}
+
 macro_rules! define_fn_as_int {
     ($(#[$attr:meta])* : $func_name:ident, $type:ident) => {
         $(#[$attr])*
+        ///
+        /// Fractions of BINARY_FLOAT and BINARY_DOUBLE are truncated or
+        /// rounded according to [FloatToInt](enum.FloatToInt.html).
         pub fn $func_name(&self) -> Result<$type> {
             match self.native_type {
                 NativeType::Int64 =>
                     Ok(self.get_i64_unchecked()?.try_into()?),
                 NativeType::UInt64 =>
                     Ok(self.get_u64_unchecked()?.try_into()?),
-                NativeType::Float =>
-                    flt_to_int!(self.get_f32_unchecked()?, f32, $type),
-                NativeType::Double =>
-                    flt_to_int!(self.get_f64_unchecked()?, f64, $type),
+                NativeType::Float if !self.strict_conversion =>
+                    flt_to_int!(self.get_f32_unchecked()?, f32, $type, self.float_to_int),
+                NativeType::Double if !self.strict_conversion =>
+                    flt_to_int!(self.get_f64_unchecked()?, f64, $type, self.float_to_int),
+                NativeType::Number => {
+                    let bytes = self.get_bytes_ref_unchecked()?;
+                    match parse_small_integer(bytes) {
+                        Some(num) => Ok(num.try_into()?),
+                        None => Ok(str::from_utf8(bytes)?.parse()?),
+                    }
+                },
                 NativeType::Char |
-                NativeType::CLOB |
-                NativeType::Number =>
+                NativeType::CLOB if !self.strict_conversion =>
                     Ok(self.get_string()?.parse()?),
                 _ =>
                     self.invalid_conversion_to_rust_type(stringify!($type))
@@ -118,6 +193,37 @@ macro_rules! define_fn_set_int {
     }
 }
 
+macro_rules! define_fn_set_flt {
+    ($(#[$attr:meta])* : $func_name:ident, $type:ident) => {
+        $(#[$attr])*
+        pub fn $func_name(&mut self, val: &$type) -> Result<()> {
+            match self.native_type {
+                NativeType::Int64 =>
+                    self.set_i64_unchecked(flt_to_int!(*val, $type, i64, self.float_to_int)?),
+                NativeType::UInt64 =>
+                    self.set_u64_unchecked(flt_to_int!(*val, $type, u64, self.float_to_int)?),
+                NativeType::Float =>
+                    self.set_f32_unchecked(*val as f32),
+                NativeType::Double =>
+                    self.set_f64_unchecked(*val as f64),
+                NativeType::Char => {
+                    let s = val.to_string();
+                    self.set_string_unchecked(&s)
+                },
+                NativeType::Number => {
+                    if !val.is_finite() {
+                        return Err(Error::Overflow(val.to_string(), "NUMBER"));
+                    }
+                    let s = val.to_string();
+                    self.set_string_unchecked(&s)
+                },
+                _ =>
+                    self.invalid_conversion_from_rust_type(stringify!($type))
+            }
+        }
+    }
+}
+
 /// A type containing an Oracle value.
 ///
 /// General users cannot use this directly. They access this via [FromSql][] and
@@ -142,6 +248,8 @@ macro_rules! define_fn_set_int {
 ///    implicitly if required. For example string is converted to i64 by
 ///    [parse][] if `as_i64()` is called for `VARCHAR2` columns.
 ///    If the conversion fails, various errors are returned.
+///    Parsing strings and truncating floating-point numbers to integers
+///    are not allowed in strict mode set by [Connection.set_strict_conversion][].
 ///
 /// Setter methods such as `set_i64()` do the followings:
 ///
@@ -164,10 +272,14 @@ macro_rules! define_fn_set_int {
 /// [Statement.bind]: struct.Statement.html#method.bind
 /// [Statement.execute]: struct.Statement.html#method.execute
 /// [Connection.execute]: struct.Connection.html#method.execute
+/// [Connection.set_strict_conversion]: struct.Connection.html#method.set_strict_conversion
 /// [parse]: https://doc.rust-lang.org/std/primitive.str.html#method.parse
 /// [set_null]: struct.SqlValue.html#method.set_null
 pub struct SqlValue {
     ctxt: &'static Context,
+    // connection which created the variable, null for object attributes.
+    // The variable holds a reference to it.
+    conn_handle: *mut dpiConn,
     pub(crate) handle: *mut dpiVar,
     data: *mut dpiData,
     native_type: NativeType,
@@ -176,6 +288,11 @@ pub struct SqlValue {
     pub(crate) buffer_row_index: u32,
     keep_bytes: Vec<u8>,
     keep_dpiobj: *mut dpiObject,
+    pub(crate) bool_convention: Option<Rc<BoolConvention>>,
+    pub(crate) strict_utf8: bool,
+    pub(crate) strict_conversion: bool,
+    pub(crate) float_to_int: FloatToInt,
+    pub(crate) max_lob_fetch_size: Option<u64>,
 }
 
 impl SqlValue {
@@ -184,6 +301,7 @@ impl SqlValue {
     pub(crate) fn new(ctxt: &'static Context) -> SqlValue {
         SqlValue {
             ctxt: ctxt,
+            conn_handle: ptr::null_mut(),
             handle: ptr::null_mut(),
             data: ptr::null_mut(),
             native_type: NativeType::Int64,
@@ -192,6 +310,11 @@ impl SqlValue {
             buffer_row_index: 0,
             keep_bytes: Vec::new(),
             keep_dpiobj: ptr::null_mut(),
+            bool_convention: None,
+            strict_utf8: false,
+            strict_conversion: false,
+            float_to_int: FloatToInt::Checked,
+            max_lob_fetch_size: None,
         }
     }
 
@@ -200,6 +323,7 @@ impl SqlValue {
         let (_, native_type, _, _) = oratype.var_create_param()?;
         Ok(SqlValue {
             ctxt: ctxt,
+            conn_handle: ptr::null_mut(),
             handle: ptr::null_mut(),
             data: data as *mut dpiData,
             native_type: native_type,
@@ -208,10 +332,15 @@ impl SqlValue {
             buffer_row_index: 0,
             keep_bytes: Vec::new(),
             keep_dpiobj: ptr::null_mut(),
+            bool_convention: None,
+            strict_utf8: false,
+            strict_conversion: false,
+            float_to_int: FloatToInt::Checked,
+            max_lob_fetch_size: None,
         })
     }
 
-    fn handle_is_reusable(&self, oratype: &OracleType, array_size: u32) -> Result<bool> {
+    pub(crate) fn handle_is_reusable(&self, oratype: &OracleType, array_size: u32) -> Result<bool> {
         if self.handle.is_null() {
             return Ok(false);
         }
@@ -254,6 +383,7 @@ impl SqlValue {
         chkerr!(conn.ctxt,
                 dpiConn_newVar(conn.handle, oratype_num, native_type_num, array_size, size, size_is_byte,
                                0, object_type_handle, &mut handle, &mut data));
+        self.conn_handle = conn.handle;
         self.handle = handle;
         self.data = data;
         self.native_type = native_type;
@@ -374,16 +504,50 @@ impl SqlValue {
     /// Gets the SQL value as utf8 string. The native_type must be
     /// NativeType::Char or NativeType::Number. Otherwise, this may cause access
     /// violation.
+    ///
+    /// Invalid byte sequences are replaced with U+FFFD unless `strict_utf8`
+    /// is set.
     fn get_string_unchecked(&self) -> Result<String> {
+        let bytes = self.get_bytes_ref_unchecked()?;
+        if self.strict_utf8 {
+            match str::from_utf8(bytes) {
+                Ok(s) => Ok(s.to_string()),
+                Err(err) => Err(Error::InvalidUtf8(String::new(), err.valid_up_to())),
+            }
+        } else {
+            Ok(String::from_utf8_lossy(bytes).into_owned())
+        }
+    }
+
+    /// Gets the SQL value as a slice referring to the buffer in the
+    /// variable. The native_type must be NativeType::Char, NativeType::Number
+    /// or NativeType::Raw. Otherwise, this may cause access violation.
+    fn get_bytes_ref_unchecked(&self) -> Result<&[u8]> {
         self.check_not_null()?;
         unsafe {
             let bytes = dpiData_getBytes(self.data());
             let ptr = (*bytes).ptr as *mut u8;
             let len = (*bytes).length as usize;
-            Ok(String::from_utf8_lossy(slice::from_raw_parts(ptr, len)).into_owned())
+            if len == 0 {
+                Ok(&[])
+            } else {
+                Ok(slice::from_raw_parts(ptr, len))
+            }
         }
     }
 
+    /// Gets the SQL value as String. The native_type must be
+    /// NativeType::Rowid. Otherwise, this may cause access violation.
+    fn get_rowid_as_string_unchecked(&self) -> Result<String> {
+        self.check_not_null()?;
+        let rowid = unsafe { *(*self.data()).value.asRowid.as_ref() };
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        chkerr!(self.ctxt,
+                dpiRowid_getStringValue(rowid, &mut ptr, &mut len));
+        Ok(OdpiStr::new(ptr, len).to_string())
+    }
+
     /// Gets the SQL value as Vec<u8>. The native_type must be
     /// NativeType::Raw. Otherwise, this may cause access violation.
     fn get_raw_unchecked(&self) -> Result<Vec<u8>> {
@@ -451,6 +615,9 @@ impl SqlValue {
         let mut bufsiz = 0;
         unsafe {
             dpiLob_getSize(lob, &mut total_char_size);
+        }
+        self.check_lob_fetch_size(total_char_size)?;
+        unsafe {
             dpiLob_getBufferSize(lob, total_char_size, &mut total_byte_size);
             dpiLob_getBufferSize(lob, READ_CHAR_SIZE, &mut bufsiz);
         }
@@ -492,6 +659,63 @@ impl SqlValue {
         Ok(result)
     }
 
+    fn get_blob_unchecked(&self) -> Result<Vec<u8>> {
+        self.check_not_null()?;
+        const READ_SIZE: u64 = 8192;
+        let lob = unsafe { dpiData_getLOB(self.data()) };
+        let mut total_size = 0;
+        unsafe {
+            dpiLob_getSize(lob, &mut total_size);
+        }
+        self.check_lob_fetch_size(total_size)?;
+        let mut result = vec![0u8; total_size as usize];
+
+        let mut offset = 1;
+        while offset <= total_size {
+            let start = (offset - 1) as usize;
+            let mut read_len = READ_SIZE;
+            if read_len > total_size - offset + 1 {
+                read_len = total_size - offset + 1;
+            }
+            chkerr!(self.ctxt,
+                    dpiLob_readBytes(lob, offset, read_len, result[start..].as_mut_ptr() as *mut i8, &mut read_len));
+            if read_len == 0 {
+                break;
+            }
+            offset += read_len;
+        }
+        result.truncate((offset - 1) as usize);
+        Ok(result)
+    }
+
+    // Checks the size of a LOB read as a whole, which is in characters
+    // for CLOB and NCLOB and in bytes for BLOB.
+    fn check_lob_fetch_size(&self, size: u64) -> Result<()> {
+        match self.max_lob_fetch_size {
+            Some(limit) if size > limit => Err(Error::LobTooLarge(size, limit)),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_clob_unchecked(&self) -> Result<Clob> {
+        self.check_not_null()?;
+        let lob = unsafe { dpiData_getLOB(self.data()) };
+        let oratype = self.oratype.clone().unwrap_or(OracleType::CLOB);
+        Ok(Clob::new(self.ctxt, self.conn_handle, lob, oratype))
+    }
+
+    fn get_blob_locator_unchecked(&self) -> Result<Blob> {
+        self.check_not_null()?;
+        let lob = unsafe { dpiData_getLOB(self.data()) };
+        Ok(Blob::new(self.ctxt, self.conn_handle, lob))
+    }
+
+    fn get_bfile_unchecked(&self) -> Result<Bfile> {
+        self.check_not_null()?;
+        let lob = unsafe { dpiData_getLOB(self.data()) };
+        Ok(Bfile::new(self.ctxt, lob))
+    }
+
     fn get_collection_unchecked(&self, objtype: &ObjectType) -> Result<Collection> {
         self.check_not_null()?;
         let dpiobj = unsafe { dpiData_getObject(self.data()) };
@@ -569,10 +793,10 @@ impl SqlValue {
         self.set_bytes_unchecked(val.as_bytes())
     }
 
-    /// Sets Vec<u8> to the SQL value. The native_type must be
+    /// Sets &[u8] to the SQL value. The native_type must be
     /// NativeType::Raw. Otherwise, this may cause access violation.
-    fn set_raw_unchecked(&mut self, val: &Vec<u8>) -> Result<()> {
-        self.set_bytes_unchecked(val.as_slice())
+    fn set_raw_unchecked(&mut self, val: &[u8]) -> Result<()> {
+        self.set_bytes_unchecked(val)
     }
 
     /// Sets Timestamp to the SQL value. The native_type must be
@@ -602,33 +826,37 @@ impl SqlValue {
     }
 
     fn set_string_to_clob_unchecked(&mut self, val: &str) -> Result<()> {
-        let ptr = val.as_ptr() as *const i8;
-        let len = val.len() as u64;
         let lob = unsafe { dpiData_getLOB(self.data()) };
         chkerr!(self.ctxt,
                 dpiLob_trim(lob, 0));
-        chkerr!(self.ctxt,
-                dpiLob_writeBytes(lob, 1, ptr, len));
+        write_clob_pieces(self.ctxt, lob, 1, val)?;
         unsafe {
             (*self.data()).isNull = 0;
         }
         Ok(())
     }
 
-    fn set_raw_to_blob_unchecked(&mut self, val: &Vec<u8>) -> Result<()> {
-        let ptr = val.as_ptr() as *const i8;
-        let len = val.len() as u64;
+    fn set_raw_to_blob_unchecked(&mut self, val: &[u8]) -> Result<()> {
         let lob = unsafe { dpiData_getLOB(self.data()) };
         chkerr!(self.ctxt,
                 dpiLob_trim(lob, 0));
-        chkerr!(self.ctxt,
-                dpiLob_writeBytes(lob, 1, ptr, len));
+        write_blob_pieces(self.ctxt, lob, 1, val)?;
         unsafe {
             (*self.data()).isNull = 0;
         }
         Ok(())
     }
 
+    fn set_lob_unchecked(&mut self, lob: *mut dpiLob) -> Result<()> {
+        if self.handle.is_null() {
+            unsafe { dpiData_setLOB(self.data(), lob) }
+        } else {
+            chkerr!(self.ctxt,
+                    dpiVar_setFromLob(self.handle, self.buffer_row_index, lob));
+        }
+        Ok(())
+    }
+
     fn set_object_unchecked(&mut self, obj: *mut dpiObject) -> Result<()> {
         if self.handle.is_null() {
             if !self.keep_dpiobj.is_null() {
@@ -672,19 +900,28 @@ impl SqlValue {
 
     /// Gets the SQL value as i64. The Oracle type must be
     /// numeric or string (excluding LOB) types.
+    ///
+    /// Fractions of BINARY_FLOAT and BINARY_DOUBLE are truncated or
+    /// rounded according to [FloatToInt](enum.FloatToInt.html).
     pub fn as_i64(&self) -> Result<i64> {
         match self.native_type {
             NativeType::Int64 =>
                 self.get_i64_unchecked(),
             NativeType::UInt64 =>
                 Ok(self.get_u64_unchecked()?.try_into()?),
-            NativeType::Float =>
-                flt_to_int!(self.get_f32_unchecked()?, f32, i64),
-            NativeType::Double =>
-                flt_to_int!(self.get_f64_unchecked()?, f64, i64),
+            NativeType::Float if !self.strict_conversion =>
+                flt_to_int!(self.get_f32_unchecked()?, f32, i64, self.float_to_int),
+            NativeType::Double if !self.strict_conversion =>
+                flt_to_int!(self.get_f64_unchecked()?, f64, i64, self.float_to_int),
+            NativeType::Number => {
+                let bytes = self.get_bytes_ref_unchecked()?;
+                match parse_small_integer(bytes) {
+                    Some(num) => Ok(num),
+                    None => Ok(str::from_utf8(bytes)?.parse()?),
+                }
+            },
             NativeType::Char |
-            NativeType::CLOB |
-            NativeType::Number =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(self.get_string()?.parse()?),
             _ =>
                 self.invalid_conversion_to_rust_type("i64"),
@@ -706,19 +943,28 @@ impl SqlValue {
 
     /// Gets the SQL value as u64. The Oracle type must be
     /// numeric or string (excluding LOB) types.
+    ///
+    /// Fractions of BINARY_FLOAT and BINARY_DOUBLE are truncated or
+    /// rounded according to [FloatToInt](enum.FloatToInt.html).
     pub fn as_u64(&self) -> Result<u64> {
         match self.native_type {
             NativeType::Int64 =>
                 Ok(self.get_i64_unchecked()?.try_into()?),
             NativeType::UInt64 =>
                 self.get_u64_unchecked(),
-            NativeType::Float =>
-                flt_to_int!(self.get_f32_unchecked()?, f32, u64),
-            NativeType::Double =>
-                flt_to_int!(self.get_f64_unchecked()?, f64, u64),
+            NativeType::Float if !self.strict_conversion =>
+                flt_to_int!(self.get_f32_unchecked()?, f32, u64, self.float_to_int),
+            NativeType::Double if !self.strict_conversion =>
+                flt_to_int!(self.get_f64_unchecked()?, f64, u64, self.float_to_int),
+            NativeType::Number => {
+                let bytes = self.get_bytes_ref_unchecked()?;
+                match parse_small_integer(bytes) {
+                    Some(num) => Ok(num.try_into()?),
+                    None => Ok(str::from_utf8(bytes)?.parse()?),
+                }
+            },
             NativeType::Char |
-            NativeType::CLOB |
-            NativeType::Number =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(self.get_string()?.parse()?),
             _ =>
                 self.invalid_conversion_to_rust_type("u64"),
@@ -727,6 +973,9 @@ impl SqlValue {
 
     /// Gets the SQL value as f32. The Oracle type must be
     /// numeric or string (excluding LOB) types.
+    ///
+    /// Positive and negative infinity of NUMBER, displayed as `~` and `-~`
+    /// by Oracle, are converted to `f32::INFINITY` and `f32::NEG_INFINITY`.
     pub fn as_f32(&self) -> Result<f32> {
         match self.native_type {
             NativeType::Int64 =>
@@ -737,10 +986,16 @@ impl SqlValue {
                 self.get_f32_unchecked(),
             NativeType::Double =>
                 Ok(self.get_f64_unchecked()? as f32),
+            NativeType::Number => {
+                let bytes = self.get_bytes_ref_unchecked()?;
+                match parse_small_integer(bytes) {
+                    Some(num) if -(1 << 24) <= num && num <= (1 << 24) => Ok(num as f32),
+                    _ => parse_float(str::from_utf8(bytes)?),
+                }
+            },
             NativeType::Char |
-            NativeType::CLOB |
-            NativeType::Number =>
-                Ok(self.get_string()?.parse()?),
+            NativeType::CLOB if !self.strict_conversion =>
+                parse_float(&self.get_string()?),
             _ =>
                 self.invalid_conversion_to_rust_type("f32"),
         }
@@ -748,6 +1003,9 @@ impl SqlValue {
 
     /// Gets the SQL value as f64. The Oracle type must be
     /// numeric or string (excluding LOB) types.
+    ///
+    /// Positive and negative infinity of NUMBER, displayed as `~` and `-~`
+    /// by Oracle, are converted to `f64::INFINITY` and `f64::NEG_INFINITY`.
     pub fn as_f64(&self) -> Result<f64> {
         match self.native_type {
             NativeType::Int64 =>
@@ -758,10 +1016,16 @@ impl SqlValue {
                 Ok(self.get_f32_unchecked()? as f64),
             NativeType::Double =>
                 self.get_f64_unchecked(),
+            NativeType::Number => {
+                let bytes = self.get_bytes_ref_unchecked()?;
+                match parse_small_integer(bytes) {
+                    Some(num) if -(1 << 53) <= num && num <= (1 << 53) => Ok(num as f64),
+                    _ => parse_float(str::from_utf8(bytes)?),
+                }
+            },
             NativeType::Char |
-            NativeType::CLOB |
-            NativeType::Number =>
-                Ok(self.get_string()?.parse()?),
+            NativeType::CLOB if !self.strict_conversion =>
+                parse_float(&self.get_string()?),
             _ =>
                 self.invalid_conversion_to_rust_type("f64"),
         }
@@ -783,6 +1047,10 @@ impl SqlValue {
                 self.get_string_unchecked(),
             NativeType::Raw =>
                 self.get_raw_as_hex_string_unchecked(),
+            NativeType::Rowid =>
+                self.get_rowid_as_string_unchecked(),
+            NativeType::Boolean =>
+                Ok(if self.get_bool_unchecked()? { "TRUE" } else { "FALSE" }.to_string()),
             NativeType::Timestamp =>
                 Ok(self.get_timestamp_unchecked()?.to_string()),
             NativeType::IntervalDS =>
@@ -805,18 +1073,56 @@ impl SqlValue {
     }
 
     /// Gets the SQL value as Vec\<u8>. ...
+    ///
+    /// The whole content is read when the Oracle type is `BLOB` or `BFILE`.
     pub fn as_bytes(&self) -> Result<Vec<u8>> {
         match self.native_type {
             NativeType::Raw =>
                 self.get_raw_unchecked(),
+            NativeType::BLOB =>
+                self.get_blob_unchecked(),
+            NativeType::BFILE =>
+                self.get_bfile_unchecked()?.read_all(),
             NativeType::Char |
-            NativeType::CLOB =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(parse_str_into_raw(&self.get_string()?)?),
             _ =>
                 self.invalid_conversion_to_rust_type("raw"),
         }
     }
 
+    /// Gets the SQL value as `&str` without copying. The Oracle type must
+    /// be character data types excluding LOB or NUMBER. Invalid UTF-8 byte
+    /// sequences are always Error::InvalidUtf8 because they cannot be
+    /// replaced without copying.
+    ///
+    /// The returned value refers to the buffer of the variable, which is
+    /// shared by clones and overwritten by the next fetch. This must be
+    /// used only via Row.get(), whose borrow prevents the next fetch.
+    pub(crate) fn as_str_ref(&self) -> Result<&str> {
+        match self.native_type {
+            NativeType::Char |
+            NativeType::Number =>
+                str::from_utf8(self.get_bytes_ref_unchecked()?)
+                    .map_err(|err| Error::InvalidUtf8(String::new(), err.valid_up_to())),
+            _ =>
+                self.invalid_conversion_to_rust_type("&str"),
+        }
+    }
+
+    /// Gets the SQL value as `&[u8]` without copying. The Oracle type must
+    /// be RAW or LONG RAW.
+    ///
+    /// This must be used only via Row.get() as as_str_ref().
+    pub(crate) fn as_bytes_ref(&self) -> Result<&[u8]> {
+        match self.native_type {
+            NativeType::Raw =>
+                self.get_bytes_ref_unchecked(),
+            _ =>
+                self.invalid_conversion_to_rust_type("&[u8]"),
+        }
+    }
+
     /// Gets the SQL value as Timestamp. The Oracle type must be
     /// `DATE`, `TIMESTAMP`, or `TIMESTAMP WITH TIME ZONE`.
     pub fn as_timestamp(&self) -> Result<Timestamp> {
@@ -824,13 +1130,64 @@ impl SqlValue {
             NativeType::Timestamp =>
                 self.get_timestamp_unchecked(),
             NativeType::Char |
-            NativeType::CLOB =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(self.get_string()?.parse()?),
             _ =>
                 self.invalid_conversion_to_rust_type("Timestamp"),
         }
     }
 
+    /// Gets the SQL value as OracleNumber. The Oracle type must be
+    /// numeric or character data types.
+    pub fn as_oracle_number(&self) -> Result<OracleNumber> {
+        match self.native_type {
+            NativeType::Int64 =>
+                Ok(OracleNumber::from(self.get_i64_unchecked()?)),
+            NativeType::UInt64 =>
+                Ok(OracleNumber::from(self.get_u64_unchecked()?)),
+            NativeType::Float =>
+                Ok(self.get_f32_unchecked()?.to_string().parse()?),
+            NativeType::Double =>
+                Ok(self.get_f64_unchecked()?.to_string().parse()?),
+            NativeType::Number =>
+                Ok(str::from_utf8(self.get_bytes_ref_unchecked()?)?.parse()?),
+            NativeType::Char |
+            NativeType::CLOB if !self.strict_conversion =>
+                Ok(self.get_string()?.parse()?),
+            _ =>
+                self.invalid_conversion_to_rust_type("OracleNumber"),
+        }
+    }
+
+    /// Gets the SQL value as i64 when it is an integer fetched without
+    /// the text parser, that is, a NUMBER of at most 18 digits or a value
+    /// whose native type is i64. Otherwise, this returns `Ok(None)` and
+    /// callers fall back to [as_oracle_number()](#method.as_oracle_number).
+    #[cfg(any(feature = "bigdecimal", feature = "rust_decimal"))]
+    pub(crate) fn as_small_integer(&self) -> Result<Option<i64>> {
+        match self.native_type {
+            NativeType::Int64 =>
+                Ok(Some(self.get_i64_unchecked()?)),
+            NativeType::Number =>
+                Ok(parse_small_integer(self.get_bytes_ref_unchecked()?)),
+            _ =>
+                Ok(None),
+        }
+    }
+
+    /// Gets the SQL value as RowId. The Oracle type must be
+    /// `ROWID`, `UROWID` or character data types.
+    pub fn as_rowid(&self) -> Result<RowId> {
+        match self.native_type {
+            NativeType::Rowid =>
+                Ok(self.get_rowid_as_string_unchecked()?.parse()?),
+            NativeType::Char if !self.strict_conversion =>
+                Ok(self.get_string_unchecked()?.parse()?),
+            _ =>
+                self.invalid_conversion_to_rust_type("RowId"),
+        }
+    }
+
     /// Gets the SQL value as IntervalDS. The Oracle type must be
     /// `INTERVAL DAY TO SECOND`.
     pub fn as_interval_ds(&self) -> Result<IntervalDS> {
@@ -838,7 +1195,7 @@ impl SqlValue {
             NativeType::IntervalDS =>
                 self.get_interval_ds_unchecked(),
             NativeType::Char |
-            NativeType::CLOB =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(self.get_string()?.parse()?),
             _ =>
                 self.invalid_conversion_to_rust_type("IntervalDS"),
@@ -852,13 +1209,45 @@ impl SqlValue {
             NativeType::IntervalYM =>
                 self.get_interval_ym_unchecked(),
             NativeType::Char |
-            NativeType::CLOB =>
+            NativeType::CLOB if !self.strict_conversion =>
                 Ok(self.get_string()?.parse()?),
             _ =>
                 self.invalid_conversion_to_rust_type("IntervalYM"),
         }
     }
 
+    /// Gets the SQL value as Clob to read and write it as a stream.
+    /// The Oracle type must be `CLOB` or `NCLOB`.
+    pub fn as_clob(&self) -> Result<Clob> {
+        match self.native_type {
+            NativeType::CLOB =>
+                self.get_clob_unchecked(),
+            _ =>
+                self.invalid_conversion_to_rust_type("Clob"),
+        }
+    }
+
+    /// Gets the SQL value as Blob to read and write it as a stream.
+    /// The Oracle type must be `BLOB`.
+    pub fn as_blob(&self) -> Result<Blob> {
+        match self.native_type {
+            NativeType::BLOB =>
+                self.get_blob_locator_unchecked(),
+            _ =>
+                self.invalid_conversion_to_rust_type("Blob"),
+        }
+    }
+
+    /// Gets the SQL value as Bfile. The Oracle type must be `BFILE`.
+    pub fn as_bfile(&self) -> Result<Bfile> {
+        match self.native_type {
+            NativeType::BFILE =>
+                self.get_bfile_unchecked(),
+            _ =>
+                self.invalid_conversion_to_rust_type("Bfile"),
+        }
+    }
+
     pub fn as_collection(&self) -> Result<Collection> {
         match self.native_type {
             NativeType::Object(ref objtype) =>
@@ -885,12 +1274,53 @@ impl SqlValue {
         }
     }
 
+    /// Gets the SQL value as a rust value converted from an object by
+    /// [FromObject][]. The Oracle type must be an object type.
+    ///
+    /// [FromObject]: trait.FromObject.html
+    pub fn as_object_of<T>(&self) -> Result<T> where T: FromObject {
+        T::from_object(&self.as_object()?)
+    }
+
     /// Gets the SQL value as bool. The Oracle type must be
-    /// `BOOLEAN`(PL/SQL only).
+    /// `BOOLEAN`, or numeric or character data types (excluding LOB)
+    /// when they are allowed by [Connection.set_bool_convention][].
+    ///
+    /// [Connection.set_bool_convention]: struct.Connection.html#method.set_bool_convention
     pub fn as_bool(&self) -> Result<bool> {
         match self.native_type {
             NativeType::Boolean =>
                 self.get_bool_unchecked(),
+            NativeType::Int64 |
+            NativeType::UInt64 |
+            NativeType::Number if self.bool_convention.as_ref().map_or(false, |conv| conv.number_from_bool(true).is_some()) => {
+                let num = self.as_i64()?;
+                match self.bool_convention.as_ref().and_then(|conv| conv.bool_from_number(num)) {
+                    Some(val) => Ok(val),
+                    None => Err(ParseOracleTypeError::new("bool").into()),
+                }
+            },
+            NativeType::Float |
+            NativeType::Double if self.bool_convention.as_ref().map_or(false, |conv| conv.number_from_bool(true).is_some()) => {
+                // Fractions are errors instead of being truncated by FloatToInt.
+                let num = self.as_f64()?;
+                let val = if num.fract() == 0.0 {
+                    self.bool_convention.as_ref().and_then(|conv| conv.bool_from_number(num as i64))
+                } else {
+                    None
+                };
+                match val {
+                    Some(val) => Ok(val),
+                    None => Err(ParseOracleTypeError::new("bool").into()),
+                }
+            },
+            NativeType::Char if self.bool_convention.as_ref().map_or(false, |conv| conv.str_from_bool(true).is_some()) => {
+                let s = self.get_string_unchecked()?;
+                match self.bool_convention.as_ref().and_then(|conv| conv.bool_from_str(&s)) {
+                    Some(val) => Ok(val),
+                    None => Err(ParseOracleTypeError::new("bool").into()),
+                }
+            },
             _ =>
                 self.invalid_conversion_to_rust_type("bool"),
         }
@@ -932,13 +1362,19 @@ impl SqlValue {
         /// Sets u64 to the SQL value. The Oracle type must be
         /// numeric or string (excluding LOB) types.
         : set_u64, u64);
-    define_fn_set_int!(
+    define_fn_set_flt!(
         /// Sets f32 to the SQL value. The Oracle type must be
         /// numeric or string (excluding LOB) types.
+        ///
+        /// NaN and infinity are set to BINARY_FLOAT and BINARY_DOUBLE as they are.
+        /// They are `Error::Overflow` for NUMBER and integer native types.
         : set_f32, f32);
-    define_fn_set_int!(
+    define_fn_set_flt!(
         /// Sets f64 to the SQL value. The Oracle type must be
         /// numeric or string (excluding LOB) types.
+        ///
+        /// NaN and infinity are set to BINARY_FLOAT and BINARY_DOUBLE as they are.
+        /// They are `Error::Overflow` for NUMBER and integer native types.
         : set_f64, f64);
 
     /// Sets &str to the SQL value. ...
@@ -975,15 +1411,15 @@ impl SqlValue {
         }
     }
 
-    /// Sets Vec\<u8> to the SQL value. ...
-    pub fn set_bytes(&mut self, val: &Vec<u8>) -> Result<()> {
+    /// Sets &[u8] to the SQL value. ...
+    pub fn set_bytes(&mut self, val: &[u8]) -> Result<()> {
         match self.native_type {
             NativeType::Raw =>
                 self.set_raw_unchecked(val),
             NativeType::BLOB => 
                 self.set_raw_to_blob_unchecked(val),
             _ =>
-                self.invalid_conversion_from_rust_type("Vec<u8>"),
+                self.invalid_conversion_from_rust_type("&[u8]"),
         }
     }
 
@@ -998,6 +1434,28 @@ impl SqlValue {
         }
     }
 
+    /// Sets OracleNumber to the SQL value. The Oracle type must be
+    /// numeric or character data types.
+    pub fn set_oracle_number(&mut self, val: &OracleNumber) -> Result<()> {
+        match self.native_type {
+            NativeType::Number =>
+                self.set_string_unchecked(&val.to_exp_string()),
+            _ =>
+                self.set_string(&val.to_string()),
+        }
+    }
+
+    /// Sets RowId to the SQL value. The Oracle type must be
+    /// character data types.
+    pub fn set_rowid(&mut self, val: &RowId) -> Result<()> {
+        match self.native_type {
+            NativeType::Char =>
+                self.set_string_unchecked(val.as_str()),
+            _ =>
+                self.invalid_conversion_from_rust_type("RowId"),
+        }
+    }
+
     /// Sets IntervalDS to the SQL value. The Oracle type must be
     /// `INTERVAL DAY TO SECOND`.
     pub fn set_interval_ds(&mut self, val: &IntervalDS) -> Result<()> {
@@ -1020,6 +1478,49 @@ impl SqlValue {
         }
     }
 
+    /// Sets Clob to the SQL value. The Oracle type must be `CLOB` or `NCLOB`.
+    /// The locator itself is bound. The content isn't copied to the client.
+    pub fn set_clob(&mut self, val: &Clob) -> Result<()> {
+        match self.native_type {
+            NativeType::CLOB =>
+                self.set_lob_unchecked(val.handle),
+            _ =>
+                self.invalid_conversion_from_rust_type("Clob"),
+        }
+    }
+
+    /// Sets Blob to the SQL value. The Oracle type must be `BLOB`.
+    /// The locator itself is bound. The content isn't copied to the client.
+    pub fn set_blob(&mut self, val: &Blob) -> Result<()> {
+        match self.native_type {
+            NativeType::BLOB =>
+                self.set_lob_unchecked(val.handle),
+            _ =>
+                self.invalid_conversion_from_rust_type("Blob"),
+        }
+    }
+
+    /// Sets Bfile to the SQL value. The Oracle type must be `BFILE`.
+    /// The directory alias and the file name are copied.
+    pub fn set_bfile(&mut self, val: &Bfile) -> Result<()> {
+        match self.native_type {
+            NativeType::BFILE => {
+                let (dir, name) = val.directory_and_file_name()?;
+                let dir = to_odpi_str(&dir);
+                let name = to_odpi_str(&name);
+                let lob = unsafe { dpiData_getLOB(self.data()) };
+                chkerr!(self.ctxt,
+                        dpiLob_setDirectoryAndFileName(lob, dir.ptr, dir.len, name.ptr, name.len));
+                unsafe {
+                    (*self.data()).isNull = 0;
+                }
+                Ok(())
+            },
+            _ =>
+                self.invalid_conversion_from_rust_type("Bfile"),
+        }
+    }
+
     /// Sets Object to the Sql Value
     pub fn set_object(&mut self, val: &Object) -> Result<()> {
         match self.native_type {
@@ -1041,17 +1542,48 @@ impl SqlValue {
     }
 
     /// Sets boolean to the SQL value. The Oracle type must be
-    /// `BOOLEAN`(PL/SQL only).
+    /// `BOOLEAN`, or numeric or character data types (excluding LOB)
+    /// when they are allowed by [Connection.set_bool_convention][].
+    ///
+    /// [Connection.set_bool_convention]: struct.Connection.html#method.set_bool_convention
     pub fn set_bool(&mut self, val: &bool) -> Result<()> {
+        let num = self.bool_convention.as_ref().and_then(|conv| conv.number_from_bool(*val));
+        let s = self.bool_convention.as_ref().and_then(|conv| conv.str_from_bool(*val).map(|s| s.to_string()));
         match self.native_type {
             NativeType::Boolean =>
                 self.set_bool_unchecked(*val),
+            NativeType::Int64 |
+            NativeType::UInt64 |
+            NativeType::Float |
+            NativeType::Double |
+            NativeType::Number if num.is_some() =>
+                self.set_i64(&num.unwrap()),
+            NativeType::Char if s.is_some() =>
+                self.set_string_unchecked(&s.unwrap()),
             _ =>
                 self.invalid_conversion_from_rust_type("bool"),
         }
     }
 }
 
+impl ToSql for SqlValue {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(self.oracle_type()?.clone())
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        if self.handle.is_null() || val.handle.is_null() || self.native_type != val.native_type {
+            return match self.oratype {
+                Some(ref oratype) => val.invalid_conversion_from_rust_type(&format!("SqlValue({})", oratype)),
+                None => Err(Error::UninitializedBindValue),
+            };
+        }
+        chkerr!(self.ctxt,
+                dpiVar_copyData(val.handle, val.buffer_row_index, self.handle, self.buffer_row_index));
+        Ok(())
+    }
+}
+
 impl fmt::Display for SqlValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.oratype {
@@ -1068,6 +1600,7 @@ impl Clone for SqlValue {
         }
         SqlValue {
             ctxt: self.ctxt,
+            conn_handle: self.conn_handle,
             handle: self.handle,
             data: self.data,
             native_type: self.native_type.clone(),
@@ -1076,6 +1609,11 @@ impl Clone for SqlValue {
             buffer_row_index: self.buffer_row_index,
             keep_bytes: Vec::new(),
             keep_dpiobj: ptr::null_mut(),
+            bool_convention: self.bool_convention.clone(),
+            strict_utf8: self.strict_utf8,
+            strict_conversion: self.strict_conversion,
+            float_to_int: self.float_to_int,
+            max_lob_fetch_size: self.max_lob_fetch_size,
         }
     }
 }
@@ -30,15 +30,29 @@
 // authors and should not be interpreted as representing official policies, either expressed
 // or implied, of the authors.
 
+use std::collections::HashMap;
 use std::ptr;
 use std::fmt;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 use std::ascii::AsciiExt;
 
 use binding::*;
 
+use BoolConvention;
 use Connection;
 use Error;
+use FloatToInt;
 use FromSql;
+use FromSqlRef;
+use ObjectType;
 use OracleType;
 use Result;
 use SqlValue;
@@ -46,6 +60,8 @@ use ToSql;
 
 use OdpiStr;
 use to_odpi_str;
+use error::ErrorContext;
+use util;
 
 //
 // StatementType
@@ -106,6 +122,131 @@ impl fmt::Display for StatementType {
     }
 }
 
+//
+// NumberDefine
+//
+
+/// Strategy to define NUMBER columns of queries
+///
+/// The Oracle type of a fetched NUMBER column determines the precision of
+/// rust values got from it and the cost of conversion. This is set by
+/// [Connection.set_number_define()][] or [Statement.set_number_define()][].
+///
+/// [Connection.set_number_define()]: struct.Connection.html#method.set_number_define
+/// [Statement.set_number_define()]: struct.Statement.html#method.set_number_define
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NumberDefine {
+    /// NUMBER(p, 0) whose p is less than 18 is defined as 64-bit integer.
+    /// Others are defined as text without loss of precision. This is the default.
+    Auto,
+
+    /// All NUMBER columns are defined as text without loss of precision.
+    /// Use this to get [OracleNumber][] or decimal types from integer
+    /// columns without going through i64.
+    ///
+    /// [OracleNumber]: struct.OracleNumber.html
+    Text,
+
+    /// NUMBER(p, 0) whose p is less than 18 is defined as 64-bit integer
+    /// and others as 64-bit floating point number. This is the fastest but
+    /// digits which don't fit in f64 are lost.
+    Native,
+}
+
+impl NumberDefine {
+    fn oracle_type(&self, oratype: &OracleType) -> Option<OracleType> {
+        match (*self, oratype) {
+            // When the column type is number whose prec is less than 18
+            // and the scale is zero, define it as int64.
+            (NumberDefine::Auto, &OracleType::Number(prec, 0)) |
+            (NumberDefine::Native, &OracleType::Number(prec, 0))
+                if 0 < prec && prec < DPI_MAX_INT64_PRECISION as u8 =>
+                Some(OracleType::Int64),
+            (NumberDefine::Native, &OracleType::Number(_, _)) =>
+                Some(OracleType::BinaryDouble),
+            _ =>
+                None,
+        }
+    }
+}
+
+impl Default for NumberDefine {
+    fn default() -> NumberDefine {
+        NumberDefine::Auto
+    }
+}
+
+//
+// StatementMetrics
+//
+
+/// Execution metrics of a statement
+///
+/// See [Statement.metrics()](struct.Statement.html#method.metrics) and
+/// [Connection.set_metrics_callback()](struct.Connection.html#method.set_metrics_callback).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementMetrics {
+    prepare_time: Duration,
+    execute_time: Duration,
+    last_execute_time: Duration,
+    fetch_time: Duration,
+    execute_count: u64,
+    fetched_rows: u64,
+    round_trips: u64,
+}
+
+impl StatementMetrics {
+    fn new() -> StatementMetrics {
+        StatementMetrics {
+            prepare_time: Duration::new(0, 0),
+            execute_time: Duration::new(0, 0),
+            last_execute_time: Duration::new(0, 0),
+            fetch_time: Duration::new(0, 0),
+            execute_count: 0,
+            fetched_rows: 0,
+            round_trips: 0,
+        }
+    }
+
+    /// Elapsed time to prepare the statement
+    pub fn prepare_time(&self) -> Duration {
+        self.prepare_time
+    }
+
+    /// Total elapsed time to execute the statement
+    pub fn execute_time(&self) -> Duration {
+        self.execute_time
+    }
+
+    /// Elapsed time of the last execution
+    pub fn last_execute_time(&self) -> Duration {
+        self.last_execute_time
+    }
+
+    /// Total elapsed time to fetch rows
+    pub fn fetch_time(&self) -> Duration {
+        self.fetch_time
+    }
+
+    /// Number of executions
+    pub fn execute_count(&self) -> u64 {
+        self.execute_count
+    }
+
+    /// Number of fetched rows
+    pub fn fetched_rows(&self) -> u64 {
+        self.fetched_rows
+    }
+
+    /// Estimated number of round-trips to the server. This is the number of
+    /// executions plus the number of fetches which needed to get rows from
+    /// the server. Round-trips such as LOB access and object type lookup are
+    /// not included.
+    pub fn round_trips(&self) -> u64 {
+        self.round_trips
+    }
+}
+
 //
 // Statement
 //
@@ -113,25 +254,69 @@ impl fmt::Display for StatementType {
 pub struct Statement<'conn> {
     conn: &'conn Connection,
     handle: *mut dpiStmt,
+    sql: String,
+    metrics: StatementMetrics,
     row: Row,
     fetch_array_size: u32,
+    // fetch array size applied at the next execution
+    next_fetch_array_size: u32,
     statement_type: dpiStatementType,
     is_returning: bool,
     bind_count: usize,
     bind_names: Vec<String>,
     bind_values: Vec<SqlValue>,
+    bool_convention: Option<Rc<BoolConvention>>,
+    number_define: NumberDefine,
+    fetch_lob_inline: bool,
+    binary_float_bind: bool,
+    strict_utf8: bool,
+    strict_conversion: bool,
+    float_to_int: FloatToInt,
+    max_lob_fetch_size: Option<u64>,
+    // true when the next fetch may need a round-trip
+    fetch_pending: bool,
+    pub(crate) internal: bool,
+    watchdog_armed: bool,
 }
 
 impl<'conn> Statement<'conn> {
 
     pub(crate) fn new(conn: &'conn Connection, scrollable: bool, sql: &str, tag: &str) -> Result<Statement<'conn>> {
+        Statement::new_internal(conn, scrollable, sql, tag)
+            .context(|| format!("preparing \"{}\"", sql))
+    }
+
+    fn new_internal(conn: &'conn Connection, scrollable: bool, sql: &str, tag: &str) -> Result<Statement<'conn>> {
+        let start_time = Instant::now();
         let scrollable = if scrollable { 1 } else { 0 };
-        let sql = to_odpi_str(sql);
+        let odpi_sql = to_odpi_str(sql);
         let tag = to_odpi_str(tag);
         let mut handle: *mut dpiStmt = ptr::null_mut();
         chkerr!(conn.ctxt,
-                dpiConn_prepareStmt(conn.handle, scrollable, sql.ptr, sql.len,
+                dpiConn_prepareStmt(conn.handle, scrollable, odpi_sql.ptr, odpi_sql.len,
                                     tag.ptr, tag.len, &mut handle));
+        Statement::from_handle(conn, handle, sql, start_time)
+    }
+
+    // Creates a statement registered to a subscription. Queries executed
+    // by the statement are registered for continuous query notification.
+    pub(crate) fn new_for_subscription(conn: &'conn Connection, subscr: *mut dpiSubscr, sql: &str) -> Result<Statement<'conn>> {
+        Statement::new_for_subscription_internal(conn, subscr, sql)
+            .context(|| format!("preparing \"{}\"", sql))
+    }
+
+    fn new_for_subscription_internal(conn: &'conn Connection, subscr: *mut dpiSubscr, sql: &str) -> Result<Statement<'conn>> {
+        let start_time = Instant::now();
+        let odpi_sql = to_odpi_str(sql);
+        let mut handle: *mut dpiStmt = ptr::null_mut();
+        chkerr!(conn.ctxt,
+                dpiSubscr_prepareStmt(subscr, odpi_sql.ptr, odpi_sql.len, &mut handle));
+        Statement::from_handle(conn, handle, sql, start_time)
+    }
+
+    // The SQL text is kept for sql(), sql_id() and metrics after the
+    // statement is prepared. It isn't copied when preparing it fails.
+    fn from_handle(conn: &'conn Connection, handle: *mut dpiStmt, sql: &str, start_time: Instant) -> Result<Statement<'conn>> {
         let mut info: dpiStmtInfo = Default::default();
         chkerr!(conn.ctxt,
                 dpiStmt_getInfo(handle, &mut info),
@@ -154,16 +339,39 @@ impl<'conn> Statement<'conn> {
                 bind_names.push(OdpiStr::new(names[i], lengths[i]).to_string());
             }
         };
+        let mut metrics = StatementMetrics::new();
+        metrics.prepare_time = start_time.elapsed();
+        let bool_convention = conn.bool_convention_rc();
+        let mut bind_value = SqlValue::new(conn.ctxt);
+        bind_value.bool_convention = bool_convention.clone();
+        bind_value.strict_utf8 = conn.strict_utf8();
+        bind_value.strict_conversion = conn.strict_conversion();
+        bind_value.float_to_int = conn.float_to_int();
+        bind_value.max_lob_fetch_size = conn.max_lob_fetch_size();
         Ok(Statement {
             conn: conn,
             handle: handle,
+            sql: sql.to_string(),
+            metrics: metrics,
             row: Row { column_info: Vec::new(), column_values: Vec::new(), },
-            fetch_array_size: 0,
+            fetch_array_size: DPI_DEFAULT_FETCH_ARRAY_SIZE,
+            next_fetch_array_size: DPI_DEFAULT_FETCH_ARRAY_SIZE,
             statement_type: info.statementType,
             is_returning: info.isReturning != 0,
             bind_count: bind_count,
             bind_names: bind_names,
-            bind_values: vec![SqlValue::new(conn.ctxt); bind_count],
+            bind_values: vec![bind_value; bind_count],
+            bool_convention: bool_convention,
+            number_define: conn.number_define(),
+            fetch_lob_inline: false,
+            binary_float_bind: conn.binary_float_bind(),
+            strict_utf8: conn.strict_utf8(),
+            strict_conversion: conn.strict_conversion(),
+            float_to_int: conn.float_to_int(),
+            max_lob_fetch_size: conn.max_lob_fetch_size(),
+            fetch_pending: false,
+            internal: false,
+            watchdog_armed: false,
         })
     }
 
@@ -205,7 +413,17 @@ impl<'conn> Statement<'conn> {
     /// ```
     pub fn bind<I>(&mut self, bindidx: I, value: &ToSql) -> Result<()> where I: BindIndex {
         let pos = bindidx.idx(&self)?;
-        if self.bind_values[pos].init_handle(self.conn, &value.oratype()?, 1)? {
+        self.bind_internal(&bindidx, pos, value)
+            .context(|| format!("binding parameter {} of \"{}\"", pos + 1, self.sql))
+    }
+
+    fn bind_internal<I>(&mut self, bindidx: &I, pos: usize, value: &ToSql) -> Result<()> where I: BindIndex {
+        let oratype = if self.binary_float_bind {
+            value.binary_float_oratype()?
+        } else {
+            value.oratype()?
+        };
+        if self.bind_values[pos].init_handle(self.conn, &oratype, 1)? {
             chkerr!(self.conn.ctxt,
                     bindidx.bind(self.handle, self.bind_values[pos].handle));
         }
@@ -248,7 +466,55 @@ impl<'conn> Statement<'conn> {
         for i in 0..params.len() {
             self.bind(i + 1, params[i])?;
         }
-        self.execute_internal()
+        self.execute_and_measure()
+    }
+
+    /// Binds values by position and executes the statement with timeout.
+    ///
+    /// When the execution doesn't finish within `timeout`, it is canceled
+    /// by [Connection.break_execution][] called from another thread and
+    /// this returns an error `ORA-01013: user requested cancel of current
+    /// operation`. The timeout applies only to this call.
+    ///
+    /// The bundled ODPI-C has no call timeout (`dpiConn_setCallTimeout()`
+    /// of ODPI-C 3.0 or later). A thread waiting for the timeout is used
+    /// instead. It is started by the first call in a connection and lives
+    /// as long as the connection. The thread doesn't break the connection
+    /// after the execution returns. However, when the timeout expires
+    /// just as the execution finishes, the break may reach the server
+    /// after the execution. This pings the server in the case to consume
+    /// the break, though the next call in the connection may still fail
+    /// with `ORA-01013` in rare cases.
+    ///
+    /// [Connection.break_execution]: struct.Connection.html#method.break_execution
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("begin dbms_lock.sleep(10); end;").unwrap();
+    /// match stmt.execute_with_timeout(&[], Duration::from_secs(1)) {
+    ///     Err(oracle::Error::OciError(ref dberr)) if dberr.code() == 1013 =>
+    ///         println!("timed out"),
+    ///     _ => (),
+    /// }
+    /// ```
+    pub fn execute_with_timeout(&mut self, params: &[&ToSql], timeout: Duration) -> Result<()> {
+        for i in 0..params.len() {
+            self.bind(i + 1, params[i])?;
+        }
+        self.conn.start_watchdog(timeout);
+        self.watchdog_armed = true;
+        let res = self.execute_and_measure();
+        self.watchdog_armed = false;
+        let broken = self.conn.stop_watchdog();
+        if broken && res.is_ok() {
+            // The break was sent after the execution finished.
+            let _ = self.conn.ping();
+        }
+        res
     }
 
     /// Binds values by name and executes the statement.
@@ -256,38 +522,97 @@ impl<'conn> Statement<'conn> {
         for i in 0..params.len() {
             self.bind(params[i].0, params[i].1)?;
         }
-        self.execute_internal()
+        self.execute_and_measure()
+    }
+
+    fn execute_and_measure(&mut self) -> Result<()> {
+        let start_time = Instant::now();
+        let res = self.execute_internal()
+            .context(|| format!("executing \"{}\"", self.sql));
+        let elapsed = start_time.elapsed();
+        self.metrics.last_execute_time = elapsed;
+        self.metrics.execute_time += elapsed;
+        self.metrics.execute_count += 1;
+        self.metrics.round_trips += 1;
+        res
     }
 
     fn execute_internal(&mut self) -> Result<()> {
+        if self.next_fetch_array_size != self.fetch_array_size {
+            chkerr!(self.conn.ctxt,
+                    dpiStmt_setFetchArraySize(self.handle, self.next_fetch_array_size));
+            // Columns must be defined again with the new array size.
+            self.row.column_values = Vec::new();
+        }
+        // Statements executed internally, such as SET TRANSACTION and
+        // SAVEPOINT, must not end the transaction in autocommit mode.
+        let exec_mode = if self.internal {
+            DPI_MODE_EXEC_DEFAULT
+        } else {
+            self.conn.exec_mode()
+        };
         let mut num_query_columns = 0;
-        chkerr!(self.conn.ctxt,
-                dpiStmt_execute(self.handle, DPI_MODE_EXEC_DEFAULT, &mut num_query_columns));
+        let rc = unsafe { dpiStmt_execute(self.handle, exec_mode, &mut num_query_columns) };
+        if self.watchdog_armed {
+            self.conn.disarm_watchdog();
+        }
+        if rc != DPI_SUCCESS as i32 {
+            return Err(::error::error_from_context(self.conn.ctxt));
+        }
         chkerr!(self.conn.ctxt,
                 dpiStmt_getFetchArraySize(self.handle, &mut self.fetch_array_size));
+        self.fetch_pending = true;
         if self.statement_type == DPI_STMT_TYPE_SELECT {
             let num_cols = num_query_columns as usize;
 
-            self.row.column_info = Vec::with_capacity(num_cols);
-            self.row.column_values = vec![SqlValue::new(self.conn.ctxt); num_cols];
+            // When the statement is re-executed, ODPI-C keeps the variables
+            // defined in the previous execution. Reuse them along with
+            // column information unless the types or sizes of columns
+            // changed, for example by binding values of other types.
+            // Query information is compared without creating ColumnInfo
+            // to avoid describing object types again.
+            if num_cols > 0 && self.row.column_values.len() == num_cols {
+                let mut same = true;
+                for i in 0..num_cols {
+                    if !self.row.column_info[i].is_same_query_info(self, i)? {
+                        same = false;
+                        break;
+                    }
+                }
+                if same {
+                    return Ok(());
+                }
+            }
+
+            let mut column_info = Vec::with_capacity(num_cols);
+            for i in 0..num_cols {
+                column_info.push(ColumnInfo::new(self, i)?);
+            }
+            set_column_aliases(&mut column_info);
+
+            self.row.column_info = column_info;
+            let mut column_value = SqlValue::new(self.conn.ctxt);
+            column_value.bool_convention = self.bool_convention.clone();
+            column_value.strict_utf8 = self.strict_utf8;
+            column_value.strict_conversion = self.strict_conversion;
+            column_value.float_to_int = self.float_to_int;
+            column_value.max_lob_fetch_size = self.max_lob_fetch_size;
+            self.row.column_values = vec![column_value; num_cols];
 
             for i in 0..num_cols {
-                // set column info
-                let ci = ColumnInfo::new(self, i)?;
-                self.row.column_info.push(ci);
                 // setup column value
                 let mut val = unsafe { self.row.column_values.get_unchecked_mut(i) };
                 let oratype = self.row.column_info[i].oracle_type();
-                let oratype_i64 = OracleType::Int64;
-                let oratype = match *oratype {
-                    // When the column type is number whose prec is less than 18
-                    // and the scale is zero, define it as int64.
-                    OracleType::Number(prec, 0) if 0 < prec && prec < DPI_MAX_INT64_PRECISION as u8 =>
-                        &oratype_i64,
+                let define_type = match *oratype {
+                    OracleType::CLOB | OracleType::NCLOB if self.fetch_lob_inline =>
+                        Some(OracleType::Long),
+                    OracleType::BLOB if self.fetch_lob_inline =>
+                        Some(OracleType::LongRaw),
                     _ =>
-                        oratype,
+                        self.number_define.oracle_type(oratype),
                 };
-                val.init_handle(self.conn, oratype, DPI_DEFAULT_FETCH_ARRAY_SIZE)?;
+                let oratype = define_type.as_ref().unwrap_or(oratype);
+                val.init_handle(self.conn, oratype, self.fetch_array_size)?;
                 chkerr!(self.conn.ctxt,
                         dpiStmt_define(self.handle, (i + 1) as u32, val.handle));
             }
@@ -344,8 +669,15 @@ impl<'conn> Statement<'conn> {
 
     /// Returns the column names.
     /// This returns an empty vector for non-query statements.
+    ///
+    /// When more than one column has same name, the second one is
+    /// suffixed by `_1`, the third one by `_2` and so on, such as
+    /// `NAME`, `NAME_1` and `NAME_2`. The suffixed names are available
+    /// as column indexes of [Row.get][].
+    ///
+    /// [Row.get]: struct.Row.html#method.get
     pub fn column_names(&self) -> Vec<&str> {
-        self.row.column_info.iter().map(|info| info.name().as_str()).collect()
+        self.row.column_info.iter().map(|info| info.alias.as_str()).collect()
     }
 
     /// Returns column information.
@@ -353,14 +685,191 @@ impl<'conn> Statement<'conn> {
         &self.row.column_info
     }
 
+    /// Gets column information of a query without executing it.
+    ///
+    /// The query is executed in describe-only mode. Rows are not fetched
+    /// and bind values are not required. This returns an empty vector for
+    /// non-query statements.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("select * from emp where empno = :1").unwrap();
+    ///
+    /// for info in stmt.column_info_only().unwrap() {
+    ///     println!("{}", info);
+    /// }
+    /// ```
+    pub fn column_info_only(&mut self) -> Result<&Vec<ColumnInfo>> {
+        let mut num_query_columns = 0;
+        if self.statement_type == DPI_STMT_TYPE_SELECT {
+            chkerr!(self.conn.ctxt,
+                    dpiStmt_execute(self.handle, DPI_MODE_EXEC_DESCRIBE_ONLY, &mut num_query_columns));
+        }
+        let num_cols = num_query_columns as usize;
+        let mut column_info = Vec::with_capacity(num_cols);
+        for i in 0..num_cols {
+            column_info.push(ColumnInfo::new(self, i)?);
+        }
+        set_column_aliases(&mut column_info);
+        self.row.column_info = column_info;
+        // Columns are defined by the next execution.
+        self.row.column_values = Vec::new();
+        Ok(&self.row.column_info)
+    }
+
+    /// Returns the number of rows fetched from the server by one round-trip.
+    /// The default value is 100.
+    ///
+    /// This is the value set by [set_fetch_array_size](#method.set_fetch_array_size)
+    /// even when it doesn't take effect yet.
+    pub fn fetch_array_size(&self) -> u32 {
+        self.next_fetch_array_size
+    }
+
+    /// Sets the number of rows fetched from the server by one round-trip.
+    ///
+    /// Larger values reduce round-trips of queries returning many rows
+    /// at the cost of memory. Zero means the default value. This takes
+    /// effect at the next execution. Rows of the current execution are
+    /// fetched with the previous size.
+    ///
+    /// This is also the number of rows prefetched by the first round-trip
+    /// because the bundled ODPI-C doesn't set the number of prefetch rows
+    /// separately.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("select * from emp").unwrap();
+    /// stmt.set_fetch_array_size(1000);
+    /// stmt.execute(&[]).unwrap();
+    /// ```
+    pub fn set_fetch_array_size(&mut self, size: u32) {
+        self.next_fetch_array_size = if size == 0 { DPI_DEFAULT_FETCH_ARRAY_SIZE } else { size };
+    }
+
+    /// Returns the strategy to define NUMBER columns.
+    pub fn number_define(&self) -> NumberDefine {
+        self.number_define
+    }
+
+    /// Sets the strategy to define NUMBER columns. The default value is
+    /// the one set to the connection when the statement was prepared.
+    /// This takes effect at the next execution.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oracle::{Connection, NumberDefine, OracleNumber};
+    ///
+    /// let conn = Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("select empno from emp").unwrap();
+    /// stmt.set_number_define(NumberDefine::Text);
+    /// stmt.execute(&[]).unwrap();
+    /// let empno: OracleNumber = stmt.fetch().unwrap().get(0).unwrap();
+    /// ```
+    pub fn set_number_define(&mut self, number_define: NumberDefine) {
+        if self.number_define != number_define {
+            self.number_define = number_define;
+            // Columns must be defined again with the new strategy.
+            self.row.column_values = Vec::new();
+        }
+    }
+
+    /// Returns true when LOB columns are fetched inline.
+    pub fn fetch_lob_inline(&self) -> bool {
+        self.fetch_lob_inline
+    }
+
+    /// Fetches CLOB and NCLOB columns as `LONG` and BLOB columns as `LONG RAW`
+    /// when `enable` is true. The content then arrives with the row instead
+    /// of being read by an additional round trip per LOB, which is much faster
+    /// for LOBs up to a few megabytes. The values are got as String and
+    /// Vec\<u8> but not as [Clob][] or [Blob][]. The default value is false.
+    /// This takes effect at the next execution.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("select id, doc from documents").unwrap();
+    /// stmt.set_fetch_lob_inline(true);
+    /// stmt.execute(&[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     let (id, doc) = row.get_as::<(i32, String)>().unwrap();
+    ///     println!("{}: {}", id, doc);
+    /// }
+    /// ```
+    ///
+    /// [Clob]: struct.Clob.html
+    /// [Blob]: struct.Blob.html
+    pub fn set_fetch_lob_inline(&mut self, enable: bool) {
+        if self.fetch_lob_inline != enable {
+            self.fetch_lob_inline = enable;
+            // Columns must be defined again with the new types.
+            self.row.column_values = Vec::new();
+        }
+    }
+
+    /// Returns true when strict conversion is enabled.
+    pub fn strict_conversion(&self) -> bool {
+        self.strict_conversion
+    }
+
+    /// Enables or disables strict conversion of fetched rows and bind values.
+    /// The default value is the one set to the connection when the statement
+    /// was prepared. See [Connection.set_strict_conversion()][] for details.
+    ///
+    /// [Connection.set_strict_conversion()]: struct.Connection.html#method.set_strict_conversion
+    pub fn set_strict_conversion(&mut self, enable: bool) {
+        self.strict_conversion = enable;
+        for val in self.row.column_values.iter_mut().chain(self.bind_values.iter_mut()) {
+            val.strict_conversion = enable;
+        }
+    }
+
+    /// Returns the policy to convert floating-point numbers to integers.
+    pub fn float_to_int(&self) -> FloatToInt {
+        self.float_to_int
+    }
+
+    /// Sets the policy to convert floating-point numbers to integers in
+    /// fetched rows and bind values. The default value is the one set to
+    /// the connection when the statement was prepared. See [FloatToInt][]
+    /// for details.
+    ///
+    /// [FloatToInt]: enum.FloatToInt.html
+    pub fn set_float_to_int(&mut self, policy: FloatToInt) {
+        self.float_to_int = policy;
+        for val in self.row.column_values.iter_mut().chain(self.bind_values.iter_mut()) {
+            val.float_to_int = policy;
+        }
+    }
+
     /// Fetchs one row from the statement. This returns `Err(Error::NoMoreData)`
     /// when all rows are fetched.
     pub fn fetch(&mut self) -> Result<&Row> {
         let mut found = 0;
         let mut buffer_row_index = 0;
-        chkerr!(self.conn.ctxt,
-                dpiStmt_fetch(self.handle, &mut found, &mut buffer_row_index));
+        // Rows are fetched from the server only when the fetch buffer is
+        // exhausted. Other calls take rows in the buffer and aren't timed.
+        if self.fetch_pending {
+            let start_time = Instant::now();
+            let res = self.fetch_internal(&mut found, &mut buffer_row_index);
+            self.metrics.fetch_time += start_time.elapsed();
+            res
+        } else {
+            self.fetch_internal(&mut found, &mut buffer_row_index)
+        }.context(|| format!("fetching rows of \"{}\"", self.sql))?;
         if found != 0 {
+            self.metrics.fetched_rows += 1;
+            if buffer_row_index == 0 {
+                self.metrics.round_trips += 1;
+            }
+            self.fetch_pending = buffer_row_index + 1 >= self.fetch_array_size;
             for val in self.row.column_values.iter_mut() {
                 val.buffer_row_index = buffer_row_index;
             }
@@ -370,6 +879,42 @@ impl<'conn> Statement<'conn> {
         }
     }
 
+    /// Fetches all remaining rows as a vector of the specified type.
+    ///
+    /// Rows are fetched [fetch_array_size][] rows per round trip. Set a
+    /// larger size by [set_fetch_array_size][] before execution to reduce
+    /// round trips for large result sets.
+    ///
+    /// [fetch_array_size]: #method.fetch_array_size
+    /// [set_fetch_array_size]: #method.set_fetch_array_size
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select empno, ename from emp", &[]).unwrap();
+    /// let rows = stmt.fetch_all::<(i32, String)>().unwrap();
+    /// for (empno, ename) in rows {
+    ///     println!("{},{}", empno, ename);
+    /// }
+    /// ```
+    pub fn fetch_all<T>(&mut self) -> Result<Vec<T>> where T: RowValue {
+        let mut rows = Vec::with_capacity(self.fetch_array_size as usize);
+        loop {
+            match self.fetch() {
+                Ok(row) => rows.push(row.get_as()?),
+                Err(Error::NoMoreData) => return Ok(rows),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn fetch_internal(&self, found: &mut i32, buffer_row_index: &mut u32) -> Result<()> {
+        chkerr!(self.conn.ctxt,
+                dpiStmt_fetch(self.handle, found, buffer_row_index));
+        Ok(())
+    }
+
     /// Returns statement type
     pub fn statement_type(&self) -> StatementType {
         match self.statement_type {
@@ -387,6 +932,79 @@ impl<'conn> Statement<'conn> {
         }
     }
 
+    /// Gets and removes lines buffered by `DBMS_OUTPUT.PUT_LINE` in the
+    /// connection. This is same with [Connection.dbms_output_lines][].
+    ///
+    /// [Connection.dbms_output_lines]: struct.Connection.html#method.dbms_output_lines
+    pub fn dbms_output_lines(&self) -> Result<Vec<String>> {
+        self.conn.dbms_output_lines()
+    }
+
+    /// Returns the query id of a query registered to a subscription
+    /// for query-level notifications. This is available after the statement
+    /// is executed.
+    ///
+    /// Use [Subscription.register_query][], which returns the query id.
+    ///
+    /// [Subscription.register_query]: struct.Subscription.html#method.register_query
+    pub fn subscr_query_id(&self) -> Result<u64> {
+        let mut query_id = 0;
+        chkerr!(self.conn.ctxt,
+                dpiStmt_getSubscrQueryId(self.handle, &mut query_id));
+        Ok(query_id)
+    }
+
+    /// Returns the SQL text of the statement.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Returns the SQL_ID of the statement, which is used to identify the
+    /// statement in `V$SQL`, `V$ACTIVE_SESSION_HISTORY`, AWR reports and so on.
+    ///
+    /// The value is calculated from the SQL text on the client side in the
+    /// same way as the server does, without a round-trip. It isn't the
+    /// value assigned by the server and may differ from it in these cases.
+    ///
+    /// * The SQL text contains non-ASCII characters and the database
+    ///   character set isn't AL32UTF8. The server calculates it from the
+    ///   text encoded in the database character set whereas this method
+    ///   uses the UTF-8 text.
+    /// * The server rewrites the SQL text, for example, by
+    ///   `CURSOR_SHARING=FORCE`.
+    ///
+    /// Query `PREV_SQL_ID` of `V$SESSION` just after execution when the
+    /// server value is required.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let stmt = conn.execute("select * from dual", &[]).unwrap();
+    /// assert_eq!(stmt.sql_id(), "a5ks9fhw2v9s1");
+    /// ```
+    pub fn sql_id(&self) -> String {
+        util::sql_id(&self.sql)
+    }
+
+    /// Returns execution metrics of the statement.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select * from emp", &[]).unwrap();
+    /// while let Ok(_) = stmt.fetch() {
+    ///     // ...
+    /// }
+    /// let metrics = stmt.metrics();
+    /// println!("executed in {:?}, fetched {} rows in {:?}",
+    ///          metrics.execute_time(), metrics.fetched_rows(), metrics.fetch_time());
+    /// ```
+    pub fn metrics(&self) -> &StatementMetrics {
+        &self.metrics
+    }
+
     /// Returns true when the SQL statement has a `RETURNING INTO` clause.
     pub fn is_returning(&self) -> bool {
         self.is_returning
@@ -395,10 +1013,134 @@ impl<'conn> Statement<'conn> {
 
 impl<'conn> Drop for Statement<'conn> {
     fn drop(&mut self) {
+        if !self.internal {
+            // A panic in the callback must not escape from drop, which
+            // may be called while unwinding.
+            let conn = self.conn;
+            let sql = &self.sql;
+            let metrics = &self.metrics;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| conn.call_metrics_callback(sql, metrics)));
+        }
         let _ = unsafe { dpiStmt_release(self.handle) };
     }
 }
 
+//
+// Watchdog
+//
+
+// dpiConn handle passed to a watchdog thread.
+struct ConnHandle(*mut dpiConn);
+
+// SAFETY: dpiConn_breakExecution() is the only function called with the
+// handle in the watchdog thread, and OCIBreak() may be called from another
+// thread while the connection is in use. The thread calls it only between
+// start() and stop(), which are called while Statement.execute_with_timeout
+// borrows the connection. The thread is joined before the connection is
+// released.
+unsafe impl Send for ConnHandle {}
+
+enum WatchdogMsg {
+    Start(Duration),
+    Stop,
+}
+
+// A thread which breaks execution in a connection when the execution
+// doesn't finish within the timeout. One thread is started per connection
+// by the first execute_with_timeout and reused by later calls.
+pub(crate) struct Watchdog {
+    sender: Option<mpsc::Sender<WatchdogMsg>>,
+    // receives whether the execution was broken when stopped
+    stopped: mpsc::Receiver<bool>,
+    // true until the execution returns
+    armed: Arc<Mutex<bool>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub(crate) fn new(conn: &Connection) -> Watchdog {
+        let (sender, receiver) = mpsc::channel();
+        let (stopped_sender, stopped) = mpsc::channel();
+        let handle = ConnHandle(conn.handle);
+        let armed = Arc::new(Mutex::new(false));
+        let thread_armed = armed.clone();
+        let thread = thread::spawn(move || {
+            while let Ok(WatchdogMsg::Start(timeout)) = receiver.recv() {
+                let broken = match receiver.recv_timeout(timeout) {
+                    Ok(_) => false,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        // The lock is held while breaking so that the
+                        // execution isn't regarded as finished in the meantime.
+                        let broken = match thread_armed.lock() {
+                            Ok(armed) if *armed => {
+                                unsafe {
+                                    dpiConn_breakExecution(handle.0);
+                                }
+                                true
+                            },
+                            _ => false,
+                        };
+                        // wait for Stop
+                        if receiver.recv().is_err() {
+                            return;
+                        }
+                        broken
+                    },
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                };
+                if stopped_sender.send(broken).is_err() {
+                    return;
+                }
+            }
+        });
+        Watchdog {
+            sender: Some(sender),
+            stopped: stopped,
+            armed: armed,
+            thread: Some(thread),
+        }
+    }
+
+    pub(crate) fn start(&self, timeout: Duration) {
+        if let Ok(mut armed) = self.armed.lock() {
+            *armed = true;
+        }
+        if let Some(ref sender) = self.sender {
+            let _ = sender.send(WatchdogMsg::Start(timeout));
+        }
+    }
+
+    // Prevents the thread from breaking the connection. This is called
+    // just after the execution returns.
+    pub(crate) fn disarm(&self) {
+        if let Ok(mut armed) = self.armed.lock() {
+            *armed = false;
+        }
+    }
+
+    // Waits for the thread to stop watching so that the connection is
+    // never broken after this returns. This returns true when the
+    // connection was broken.
+    pub(crate) fn stop(&self) -> bool {
+        self.disarm();
+        match self.sender {
+            Some(ref sender) if sender.send(WatchdogMsg::Stop).is_ok() =>
+                self.stopped.recv().unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // The thread exits when the sender is dropped.
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
 //
 // ColumnInfo
 //
@@ -436,11 +1178,17 @@ impl<'conn> Drop for Statement<'conn> {
 ///  COMM                                    NUMBER(7,2)
 ///  DEPTNO                                  NUMBER(2)
 /// ```
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct ColumnInfo {
     name: String,
     oracle_type: OracleType,
     nullable: bool,
+    precision: i16,
+    scale: i8,
+    size_in_bytes: u32,
+    size_in_chars: u32,
+    // unique name in the statement returned by Statement.column_names
+    alias: String,
 }
 
 impl ColumnInfo {
@@ -448,13 +1196,82 @@ impl ColumnInfo {
         let mut info = Default::default();
         chkerr!(stmt.conn.ctxt,
                 dpiStmt_getQueryInfo(stmt.handle, (idx + 1) as u32, &mut info));
+        let name = OdpiStr::new(info.name, info.nameLength).to_string();
         Ok(ColumnInfo {
-            name: OdpiStr::new(info.name, info.nameLength).to_string(),
+            alias: name.clone(),
+            name: name,
             oracle_type: OracleType::from_type_info(stmt.conn.ctxt, &info.typeInfo)?,
             nullable: info.nullOk != 0,
+            precision: info.typeInfo.precision,
+            scale: info.typeInfo.scale,
+            size_in_bytes: info.typeInfo.dbSizeInBytes,
+            size_in_chars: info.typeInfo.sizeInChars,
         })
     }
 
+    // Checks whether the query information of the idx-th column is same
+    // with this. Object types are compared by names.
+    fn is_same_query_info(&self, stmt: &Statement, idx: usize) -> Result<bool> {
+        let mut info = Default::default();
+        chkerr!(stmt.conn.ctxt,
+                dpiStmt_getQueryInfo(stmt.handle, (idx + 1) as u32, &mut info));
+        if OdpiStr::new(info.name, info.nameLength).to_string() != self.name
+            || (info.nullOk != 0) != self.nullable
+            || info.typeInfo.precision != self.precision
+            || info.typeInfo.scale != self.scale
+            || info.typeInfo.dbSizeInBytes != self.size_in_bytes
+            || info.typeInfo.sizeInChars != self.size_in_chars {
+            return Ok(false);
+        }
+        if info.typeInfo.oracleTypeNum == DPI_ORACLE_TYPE_OBJECT {
+            let objtype = match self.oracle_type {
+                OracleType::Object(ref objtype) => objtype,
+                _ => return Ok(false),
+            };
+            let mut objinfo = Default::default();
+            chkerr!(stmt.conn.ctxt,
+                    dpiObjectType_getInfo(info.typeInfo.objectType, &mut objinfo));
+            Ok(OdpiStr::new(objinfo.schema, objinfo.schemaLength).to_string() == *objtype.schema()
+               && OdpiStr::new(objinfo.name, objinfo.nameLength).to_string() == *objtype.name())
+        } else {
+            Ok(OracleType::from_type_info(stmt.conn.ctxt, &info.typeInfo)? == self.oracle_type)
+        }
+    }
+
+    /// Creates column information which isn't associated with any statement.
+    ///
+    /// This doesn't need Oracle client libraries. It is intended for tools
+    /// handling metadata, such as code generators, which don't always connect
+    /// to a database.
+    ///
+    /// Precision, scale and sizes are taken from `oracle_type`. The size in
+    /// bytes of `NCHAR` and `NVARCHAR2` is zero because it depends on the
+    /// national character set of the database.
+    pub fn new_offline(name: &str, oracle_type: OracleType, nullable: bool) -> ColumnInfo {
+        let (precision, scale, size_in_bytes, size_in_chars) = match oracle_type {
+            OracleType::Varchar2(size) |
+            OracleType::Char(size) => (0, 0, size, size),
+            OracleType::NVarchar2(size) |
+            OracleType::NChar(size) => (0, 0, 0, size),
+            OracleType::Raw(size) => (0, 0, size, 0),
+            OracleType::Number(prec, scale) => (prec as i16, scale, 0, 0),
+            OracleType::Float(prec) => (prec as i16, -127, 0, 0),
+            OracleType::IntervalDS(lfprec, _) |
+            OracleType::IntervalYM(lfprec) => (lfprec as i16, 0, 0, 0),
+            _ => (0, 0, 0, 0),
+        };
+        ColumnInfo {
+            name: name.to_string(),
+            oracle_type: oracle_type,
+            nullable: nullable,
+            precision: precision,
+            scale: scale,
+            size_in_bytes: size_in_bytes,
+            size_in_chars: size_in_chars,
+            alias: name.to_string(),
+        }
+    }
+
     /// Gets column name
     pub fn name(&self) -> &String {
         &self.name
@@ -470,6 +1287,56 @@ impl ColumnInfo {
     pub fn nullable(&self) -> bool {
         self.nullable
     }
+
+    /// Gets the precision of `NUMBER`, `FLOAT` and the leading field
+    /// precision of `INTERVAL` types. Zero for other types and `NUMBER`
+    /// without precision.
+    pub fn precision(&self) -> i16 {
+        self.precision
+    }
+
+    /// Gets the scale of `NUMBER`. This is -127 for `FLOAT` and
+    /// zero for other types.
+    pub fn scale(&self) -> i8 {
+        self.scale
+    }
+
+    /// Gets the column size in bytes of character and `RAW` types.
+    /// Zero for other types.
+    pub fn size_in_bytes(&self) -> u32 {
+        self.size_in_bytes
+    }
+
+    /// Gets the column size in characters of character types.
+    /// Zero for other types.
+    ///
+    /// This differs from [size_in_bytes()][] when the character set is
+    /// multi-byte. For example, `VARCHAR2(10 CHAR)` in AL32UTF8 is 10
+    /// characters and 40 bytes.
+    ///
+    /// [size_in_bytes()]: #method.size_in_bytes
+    pub fn size_in_chars(&self) -> u32 {
+        self.size_in_chars
+    }
+
+    /// Gets whether the column is in the national character set, i.e.
+    /// `NCHAR`, `NVARCHAR2` or `NCLOB`.
+    pub fn is_national(&self) -> bool {
+        match self.oracle_type {
+            OracleType::NChar(_) |
+            OracleType::NVarchar2(_) |
+            OracleType::NCLOB => true,
+            _ => false,
+        }
+    }
+
+    /// Gets the object type when the column is an object or a collection.
+    pub fn object_type(&self) -> Option<&ObjectType> {
+        match self.oracle_type {
+            OracleType::Object(ref objtype) => Some(objtype),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ColumnInfo {
@@ -492,14 +1359,217 @@ pub struct Row {
 }
 
 impl Row {
-    pub fn get<I, T>(&self, colidx: I) -> Result<T> where I: ColumnIndex, T: FromSql {
+    /// Gets the column value.
+    ///
+    /// `T` is a type implementing [FromSql][] or `&str` and `&[u8]`, which
+    /// refer to the fetch buffer without copying. See [FromSqlRef][].
+    ///
+    /// `colidx` is a zero-based column index or a column name compared
+    /// case-insensitively. A name matching more than one column fails with
+    /// [Error::AmbiguousColumnName][]. Note that the first matching column
+    /// was returned in this case in earlier versions. Use suffixed names
+    /// returned by [Statement.column_names][] to get such columns.
+    ///
+    /// [FromSql]: trait.FromSql.html
+    /// [FromSqlRef]: trait.FromSqlRef.html
+    /// [Error::AmbiguousColumnName]: enum.Error.html#variant.AmbiguousColumnName
+    /// [Statement.column_names]: struct.Statement.html#method.column_names
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select ename from emp", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     let ename: &str = row.get(0).unwrap();
+    ///     println!("{}", ename);
+    /// }
+    /// ```
+    pub fn get<'a, I, T>(&'a self, colidx: I) -> Result<T> where I: ColumnIndex, T: FromSqlRef<'a> {
         let pos = colidx.idx(&self.column_info)?;
-        self.column_values[pos].get()
+        T::from_sql_ref(self, pos).map_err(|err| match err {
+            Error::InvalidUtf8(_, offset) =>
+                Error::InvalidUtf8(self.column_info[pos].name().clone(), offset),
+            _ => err,
+        })
+    }
+
+    /// Gets the column value as `Some(value)`, or `None` when it is null.
+    /// This is same with `row.get::<I, Option<T>>(colidx)`.
+    pub fn get_opt<I, T>(&self, colidx: I) -> Result<Option<T>> where I: ColumnIndex, T: FromSql {
+        self.get(colidx)
+    }
+
+    /// Gets the column value, or `default` when it is null.
+    pub fn get_or<I, T>(&self, colidx: I, default: T) -> Result<T> where I: ColumnIndex, T: FromSql {
+        Ok(self.get_opt(colidx)?.unwrap_or(default))
     }
 
     pub fn columns(&self) -> &Vec<SqlValue> {
         &self.column_values
     }
+
+    /// Gets column values as specified type.
+    ///
+    /// Tuples of types implementing [FromSql][] are available. The first
+    /// element of the tuple is converted from the first column, the second
+    /// element from the second column and so on.
+    ///
+    /// [FromSql]: trait.FromSql.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select empno, ename, comm from emp", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     let (empno, ename, comm) = row.get_as::<(i32, String, Option<f64>)>().unwrap();
+    ///     println!("{},{},{:?}", empno, ename, comm);
+    /// }
+    /// ```
+    pub fn get_as<T>(&self) -> Result<T> where T: RowValue {
+        T::get(self)
+    }
+
+    /// Converts the row to a map whose keys are column names and values
+    /// are column values converted to strings. Null values become `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select * from emp", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     println!("{:?}", row.to_map().unwrap());
+    /// }
+    /// ```
+    pub fn to_map(&self) -> Result<HashMap<String, Option<String>>> {
+        self.to_map_as()
+    }
+
+    /// Converts the row to a map whose keys are column names and values
+    /// are column values converted to the specified type. Null values
+    /// become `None`.
+    ///
+    /// Use [iter()][] to make other types of collections such as `BTreeMap`.
+    ///
+    /// [iter()]: #method.iter
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select sal, comm from emp", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     let map = row.to_map_as::<f64>().unwrap();
+    ///     println!("sal: {:?}, comm: {:?}", map["SAL"], map["COMM"]);
+    /// }
+    /// ```
+    pub fn to_map_as<T>(&self) -> Result<HashMap<String, Option<T>>> where T: FromSql {
+        let mut map = HashMap::with_capacity(self.column_values.len());
+        for (name, val) in self.iter() {
+            map.insert(name.to_string(), val.get()?);
+        }
+        Ok(map)
+    }
+
+    /// Returns an iterator over pairs of column names and values.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select * from emp", &[]).unwrap();
+    /// while let Ok(row) = stmt.fetch() {
+    ///     for (name, val) in row.iter() {
+    ///         println!("{} = {}", name, val);
+    ///     }
+    /// }
+    /// ```
+    pub fn iter(&self) -> RowIter {
+        RowIter {
+            column_info: self.column_info.iter(),
+            column_values: self.column_values.iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Row {
+    type Item = (&'a str, &'a SqlValue);
+    type IntoIter = RowIter<'a>;
+
+    fn into_iter(self) -> RowIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over pairs of column names and values in a row.
+///
+/// This is created by [Row.iter()](struct.Row.html#method.iter).
+pub struct RowIter<'a> {
+    column_info: ::std::slice::Iter<'a, ColumnInfo>,
+    column_values: ::std::slice::Iter<'a, SqlValue>,
+}
+
+impl<'a> Iterator for RowIter<'a> {
+    type Item = (&'a str, &'a SqlValue);
+
+    fn next(&mut self) -> Option<(&'a str, &'a SqlValue)> {
+        match (self.column_info.next(), self.column_values.next()) {
+            (Some(info), Some(val)) => Some((info.alias.as_str(), val)),
+            _ => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.column_values.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for RowIter<'a> {}
+
+//
+// RowValue
+//
+
+/// A trait to get a row as specified type
+///
+/// This is used by [Row.get_as()](struct.Row.html#method.get_as).
+/// Tuples up to 12 elements whose types implement [FromSql][] implement
+/// this trait.
+///
+/// [FromSql]: trait.FromSql.html
+pub trait RowValue: Sized {
+    fn get(row: &Row) -> Result<Self>;
+}
+
+macro_rules! impl_row_value_for_tuple {
+    ($(
+        [$($idx:tt : $T:ident),+]
+    )+) => {
+        $(
+            impl<$($T: FromSql,)+> RowValue for ($($T,)+) {
+                fn get(row: &Row) -> Result<($($T,)+)> {
+                    Ok(($(row.get::<usize, $T>($idx)?,)+))
+                }
+            }
+        )+
+    }
+}
+
+impl_row_value_for_tuple!{
+    [0:T0]
+    [0:T0, 1:T1]
+    [0:T0, 1:T1, 2:T2]
+    [0:T0, 1:T1, 2:T2, 3:T3]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6, 7:T7]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6, 7:T7, 8:T8]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6, 7:T7, 8:T8, 9:T9]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6, 7:T7, 8:T8, 9:T9, 10:T10]
+    [0:T0, 1:T1, 2:T2, 3:T3, 4:T4, 5:T5, 6:T6, 7:T7, 8:T8, 9:T9, 10:T10, 11:T11]
 }
 
 //
@@ -560,11 +1630,42 @@ impl ColumnIndex for usize {
 
 impl<'a> ColumnIndex for &'a str {
     fn idx(&self, column_info: &Vec<ColumnInfo>) -> Result<usize> {
+        let mut found = None;
         for (idx, info) in column_info.iter().enumerate() {
             if info.name().as_str().eq_ignore_ascii_case(*self) {
-                return Ok(idx);
+                if found.is_some() {
+                    return Err(Error::AmbiguousColumnName((*self).to_string()));
+                }
+                found = Some(idx);
             }
         }
-        Err(Error::InvalidColumnName((*self).to_string()))
+        if let Some(idx) = found {
+            return Ok(idx);
+        }
+        // Look up suffixed names of duplicated columns.
+        column_info.iter()
+            .position(|info| info.alias.eq_ignore_ascii_case(*self))
+            .ok_or_else(|| Error::InvalidColumnName((*self).to_string()))
+    }
+}
+
+// Makes unique column names by suffixing `_1`, `_2` and so on to
+// duplicated names. Suffixed names never conflict with other columns.
+// This is called once per description of columns.
+fn set_column_aliases(column_info: &mut [ColumnInfo]) {
+    let mut names: Vec<String> = Vec::with_capacity(column_info.len());
+    for info in column_info.iter() {
+        let name = info.name();
+        let mut alias = name.clone();
+        let mut n = 0;
+        while names.iter().any(|s| s.eq_ignore_ascii_case(&alias))
+            || (n > 0 && column_info.iter().any(|ci| ci.name().eq_ignore_ascii_case(&alias))) {
+            n += 1;
+            alias = format!("{}_{}", name, n);
+        }
+        names.push(alias);
+    }
+    for (info, alias) in column_info.iter_mut().zip(names) {
+        info.alias = alias;
     }
 }
@@ -0,0 +1,690 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+//! Continuous query notification (CQN)
+
+use std::os::raw::c_void;
+use std::panic;
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
+
+use binding::*;
+
+use Connection;
+use Error;
+use Result;
+use RowId;
+use Statement;
+use ToSql;
+
+use OdpiStr;
+use error::error_from_dpi_error;
+use to_odpi_str;
+
+//
+// Operation
+//
+
+/// Database operation which caused a change
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Insert
+    Insert,
+    /// Update
+    Update,
+    /// Delete
+    Delete,
+    /// Alter table
+    Alter,
+    /// Drop table
+    Drop,
+    /// Unknown operation
+    Unknown,
+}
+
+const OPERATIONS: [(Operation, dpiOpCode); 6] = [
+    (Operation::Insert, DPI_OPCODE_INSERT),
+    (Operation::Update, DPI_OPCODE_UPDATE),
+    (Operation::Delete, DPI_OPCODE_DELETE),
+    (Operation::Alter, DPI_OPCODE_ALTER),
+    (Operation::Drop, DPI_OPCODE_DROP),
+    (Operation::Unknown, DPI_OPCODE_UNKNOWN),
+];
+
+impl Operation {
+    fn to_dpi_value(&self) -> dpiOpCode {
+        OPERATIONS.iter().find(|&&(op, _)| op == *self).unwrap().1
+    }
+
+    fn from_dpi_value(code: dpiOpCode) -> Vec<Operation> {
+        OPERATIONS.iter()
+            .filter(|&&(_, dpi_code)| code.0 & dpi_code.0 != 0)
+            .map(|&(op, _)| op)
+            .collect()
+    }
+}
+
+//
+// EventType
+//
+
+/// Type of an event notified to subscriptions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    /// No event
+    None,
+    /// The database started up.
+    Startup,
+    /// The database shut down.
+    Shutdown,
+    /// An instance of Oracle Real Application Clusters shut down.
+    ShutdownAny,
+    /// The database was dropped.
+    DropDb,
+    /// The subscription was deregistered.
+    Dereg,
+    /// Objects of registered queries changed.
+    ObjChange,
+    /// Results of registered queries changed.
+    QueryChange,
+}
+
+impl EventType {
+    fn from_dpi_value(val: dpiEventType) -> EventType {
+        match val {
+            DPI_EVENT_STARTUP => EventType::Startup,
+            DPI_EVENT_SHUTDOWN => EventType::Shutdown,
+            DPI_EVENT_SHUTDOWN_ANY => EventType::ShutdownAny,
+            DPI_EVENT_DROP_DB => EventType::DropDb,
+            DPI_EVENT_DEREG => EventType::Dereg,
+            DPI_EVENT_OBJCHANGE => EventType::ObjChange,
+            DPI_EVENT_QUERYCHANGE => EventType::QueryChange,
+            _ => EventType::None,
+        }
+    }
+}
+
+//
+// RowChange
+//
+
+/// Change of a row
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowChange {
+    operation: Operation,
+    rowid: RowId,
+}
+
+impl RowChange {
+    /// Returns the operation which changed the row.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Returns the rowid of the changed row.
+    pub fn rowid(&self) -> &RowId {
+        &self.rowid
+    }
+
+    fn from_dpi(row: &dpiSubscrMessageRow) -> RowChange {
+        RowChange {
+            operation: Operation::from_dpi_value(row.operation).pop().unwrap_or(Operation::Unknown),
+            rowid: RowId::new(OdpiStr::new(row.rowid, row.rowidLength).to_string()),
+        }
+    }
+}
+
+//
+// TableChange
+//
+
+/// Change of a table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableChange {
+    name: String,
+    operations: Vec<Operation>,
+    all_rows: bool,
+    rows: Vec<RowChange>,
+}
+
+impl TableChange {
+    /// Returns the table name including the schema name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns operations which changed the table.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns `true` when changed rows are unknown. Oracle doesn't tell
+    /// changed rows when [rowids][] isn't set or too many rows changed.
+    /// Invalidate all cached data of the table in this case.
+    ///
+    /// [rowids]: struct.SubscrOptions.html#method.rowids
+    pub fn all_rows(&self) -> bool {
+        self.all_rows
+    }
+
+    /// Returns changed rows.
+    pub fn rows(&self) -> &[RowChange] {
+        &self.rows
+    }
+
+    fn from_dpi(table: &dpiSubscrMessageTable) -> TableChange {
+        let rows = to_slice(table.rows, table.numRows);
+        TableChange {
+            name: OdpiStr::new(table.name, table.nameLength).to_string(),
+            operations: Operation::from_dpi_value(table.operation),
+            all_rows: table.operation.0 & DPI_OPCODE_ALL_ROWS.0 != 0,
+            rows: rows.iter().map(RowChange::from_dpi).collect(),
+        }
+    }
+}
+
+//
+// QueryChange
+//
+
+/// Change of the result of a registered query
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryChange {
+    id: u64,
+    operations: Vec<Operation>,
+    tables: Vec<TableChange>,
+}
+
+impl QueryChange {
+    /// Returns the query id, which is returned by
+    /// [Subscription.register_query](struct.Subscription.html#method.register_query)
+    /// when the query is registered.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns operations which changed the query result.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns changed tables referred by the query.
+    pub fn tables(&self) -> &[TableChange] {
+        &self.tables
+    }
+
+    fn from_dpi(query: &dpiSubscrMessageQuery) -> QueryChange {
+        let tables = to_slice(query.tables, query.numTables);
+        QueryChange {
+            id: query.id,
+            operations: Operation::from_dpi_value(query.operation),
+            tables: tables.iter().map(TableChange::from_dpi).collect(),
+        }
+    }
+}
+
+//
+// Event
+//
+
+/// Event notified to subscriptions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    event_type: EventType,
+    db_name: String,
+    tables: Vec<TableChange>,
+    queries: Vec<QueryChange>,
+    tx_id: Vec<u8>,
+}
+
+impl Event {
+    /// Returns the event type.
+    pub fn event_type(&self) -> EventType {
+        self.event_type
+    }
+
+    /// Returns the name of the database which sent the event.
+    pub fn db_name(&self) -> &str {
+        &self.db_name
+    }
+
+    /// Returns changed tables. This is available when the event type
+    /// is [EventType::ObjChange][].
+    ///
+    /// [EventType::ObjChange]: enum.EventType.html#variant.ObjChange
+    pub fn tables(&self) -> &[TableChange] {
+        &self.tables
+    }
+
+    /// Returns changed queries. This is available when the event type
+    /// is [EventType::QueryChange][].
+    ///
+    /// [EventType::QueryChange]: enum.EventType.html#variant.QueryChange
+    pub fn queries(&self) -> &[QueryChange] {
+        &self.queries
+    }
+
+    /// Returns the changed query whose query id is `id`, if any.
+    /// This is used to dispatch events to handlers registered per query.
+    pub fn query(&self, id: u64) -> Option<&QueryChange> {
+        self.queries.iter().find(|query| query.id == id)
+    }
+
+    /// Returns the identifier of the transaction which made the change.
+    pub fn tx_id(&self) -> &[u8] {
+        &self.tx_id
+    }
+
+    fn from_dpi(msg: &dpiSubscrMessage) -> Event {
+        let tables = to_slice(msg.tables, msg.numTables);
+        let queries = to_slice(msg.queries, msg.numQueries);
+        Event {
+            event_type: EventType::from_dpi_value(msg.eventType),
+            db_name: OdpiStr::new(msg.dbName, msg.dbNameLength).to_string(),
+            tables: tables.iter().map(TableChange::from_dpi).collect(),
+            queries: queries.iter().map(QueryChange::from_dpi).collect(),
+            tx_id: to_slice(msg.txId as *const u8, msg.txIdLength).to_vec(),
+        }
+    }
+}
+
+fn to_slice<'a, T>(ptr: *const T, len: u32) -> &'a [T] {
+    if ptr.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(ptr, len as usize) }
+    }
+}
+
+//
+// SubscrOptions
+//
+
+/// Options to create a subscription
+///
+/// # Examples
+///
+/// ```no_run
+/// # use oracle::*;
+/// let mut opts = SubscrOptions::new();
+/// opts.rowids(true)
+///     .operations(&[Operation::Insert, Operation::Update, Operation::Delete])
+///     .timeout(3600);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubscrOptions {
+    name: Option<String>,
+    port_number: u32,
+    timeout: u32,
+    rowids: bool,
+    best_effort: bool,
+    query: bool,
+    reliable: bool,
+    dereg_nfy: bool,
+    operations: Vec<Operation>,
+}
+
+impl SubscrOptions {
+    /// Creates options with default values.
+    pub fn new() -> SubscrOptions {
+        SubscrOptions {
+            name: None,
+            port_number: 0,
+            timeout: 0,
+            rowids: false,
+            best_effort: false,
+            query: false,
+            reliable: false,
+            dereg_nfy: false,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Sets the name of the subscription.
+    pub fn name<'a>(&'a mut self, name: &str) -> &'a mut SubscrOptions {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the port number on which the client listens for notifications.
+    /// The default is 0, which means that a port is chosen by Oracle.
+    ///
+    /// The database connects to the client on the port, so it must be
+    /// open in firewalls between them. Client-initiated connections, which
+    /// don't need it, and grouping of notifications aren't supported
+    /// because the bundled ODPI-C has no `clientInitiated` and
+    /// `groupingClass` in `dpiSubscrCreateParams`.
+    pub fn port_number<'a>(&'a mut self, port_number: u32) -> &'a mut SubscrOptions {
+        self.port_number = port_number;
+        self
+    }
+
+    /// Sets the number of seconds after which the subscription is
+    /// deregistered automatically. The default is 0, which means never.
+    pub fn timeout<'a>(&'a mut self, secs: u32) -> &'a mut SubscrOptions {
+        self.timeout = secs;
+        self
+    }
+
+    /// Sets whether rowids of changed rows are notified. When this is
+    /// `true`, events have [RowChange][]s of changed rows unless too many
+    /// rows are changed.
+    ///
+    /// [RowChange]: struct.RowChange.html
+    pub fn rowids<'a>(&'a mut self, rowids: bool) -> &'a mut SubscrOptions {
+        self.rowids = rowids;
+        self
+    }
+
+    /// Sets whether query results are checked in best-effort mode.
+    /// Notifications may be sent when query results don't change
+    /// in exchange for less overhead on the server.
+    pub fn best_effort<'a>(&'a mut self, best_effort: bool) -> &'a mut SubscrOptions {
+        self.best_effort = best_effort;
+        self
+    }
+
+    /// Sets whether changes are notified per query instead of per object.
+    /// When this is `true`, notified events are [EventType::QueryChange][].
+    ///
+    /// [EventType::QueryChange]: enum.EventType.html#variant.QueryChange
+    pub fn query<'a>(&'a mut self, query: bool) -> &'a mut SubscrOptions {
+        self.query = query;
+        self
+    }
+
+    /// Sets whether notifications are persisted in the database so that
+    /// they survive instance failures.
+    pub fn reliable<'a>(&'a mut self, reliable: bool) -> &'a mut SubscrOptions {
+        self.reliable = reliable;
+        self
+    }
+
+    /// Sets whether the subscription is deregistered after the first
+    /// notification.
+    pub fn dereg_nfy<'a>(&'a mut self, dereg_nfy: bool) -> &'a mut SubscrOptions {
+        self.dereg_nfy = dereg_nfy;
+        self
+    }
+
+    /// Sets operations to be notified. All operations are notified when
+    /// this isn't set.
+    pub fn operations<'a>(&'a mut self, operations: &[Operation]) -> &'a mut SubscrOptions {
+        self.operations = operations.to_vec();
+        self
+    }
+
+    fn qos(&self) -> dpiSubscrQOS {
+        let mut qos = dpiSubscrQOS(0);
+        if self.reliable {
+            qos |= DPI_SUBSCR_QOS_RELIABLE;
+        }
+        if self.dereg_nfy {
+            qos |= DPI_SUBSCR_QOS_DEREG_NFY;
+        }
+        if self.rowids {
+            qos |= DPI_SUBSCR_QOS_ROWIDS;
+        }
+        if self.query {
+            qos |= DPI_SUBSCR_QOS_QUERY;
+        }
+        if self.best_effort {
+            qos |= DPI_SUBSCR_QOS_BEST_EFFORT;
+        }
+        qos
+    }
+
+    fn dpi_operations(&self) -> dpiOpCode {
+        let mut code = DPI_OPCODE_ALL_OPS;
+        for op in &self.operations {
+            code.0 |= op.to_dpi_value().0;
+        }
+        code
+    }
+}
+
+//
+// Subscription
+//
+
+// Functions called by ODPI-C. This must live until the subscription is closed.
+struct Callbacks {
+    event: Mutex<Box<FnMut(&Event) + Send>>,
+    error: Mutex<Option<Box<FnMut(&Error) + Send>>>,
+}
+
+unsafe extern "C" fn subscr_callback(context: *mut c_void, message: *mut dpiSubscrMessage) {
+    let callbacks = &*(context as *const Callbacks);
+    let message = &*message;
+    // Panics must not unwind into ODPI-C.
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        if message.errorInfo.is_null() {
+            let event = Event::from_dpi(message);
+            if let Ok(mut f) = callbacks.event.lock() {
+                f(&event);
+            }
+        } else {
+            let err = error_from_dpi_error(&*message.errorInfo);
+            if let Ok(mut f) = callbacks.error.lock() {
+                if let Some(ref mut f) = *f {
+                    f(&err);
+                }
+            }
+        }
+    }));
+}
+
+/// Subscription for continuous query notification (CQN)
+///
+/// This is created by [Connection.subscribe][]. The connection must be
+/// created with [Connector.events][] set to `true`. Queries registered
+/// by [register_query](#method.register_query) are monitored and
+/// the callback is called in a thread created by the Oracle client when
+/// objects referred by them change.
+///
+/// The subscription is deregistered by [unsubscribe](#method.unsubscribe)
+/// or when it is dropped. Errors on deregistration are ignored in the
+/// latter case.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use oracle::*;
+/// let conn = Connector::new("scott", "tiger", "").events(true).connect().unwrap();
+/// let mut opts = SubscrOptions::new();
+/// opts.rowids(true);
+/// let subscr = conn.subscribe(&opts, |event| {
+///     for table in event.tables() {
+///         if table.all_rows() {
+///             println!("flush all cached rows of {}", table.name());
+///         } else {
+///             for row in table.rows() {
+///                 println!("{:?} {} of {}", row.operation(), row.rowid(), table.name());
+///             }
+///         }
+///     }
+/// }).unwrap();
+/// subscr.set_error_callback(|err| println!("notification error: {}", err));
+/// subscr.register_query("select * from emp", &[]).unwrap();
+/// println!("registered as {}", subscr.id());
+/// // ...
+/// subscr.unsubscribe().unwrap();
+/// ```
+///
+/// [Connection.subscribe]: struct.Connection.html#method.subscribe
+/// [Connector.events]: struct.Connector.html#method.events
+pub struct Subscription<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiSubscr,
+    id: u64,
+    callbacks: Box<Callbacks>,
+}
+
+impl<'conn> Subscription<'conn> {
+    pub(crate) fn new<F>(conn: &'conn Connection, options: &SubscrOptions, callback: F) -> Result<Subscription<'conn>> where F: FnMut(&Event) + Send + 'static {
+        let callbacks = Box::new(Callbacks {
+            event: Mutex::new(Box::new(callback)),
+            error: Mutex::new(None),
+        });
+        let mut params = conn.ctxt.subscr_create_params;
+        let name = to_odpi_str(options.name.as_ref().map(|s| s.as_str()).unwrap_or(""));
+        params.subscrNamespace = DPI_SUBSCR_NAMESPACE_DBCHANGE;
+        params.protocol = DPI_SUBSCR_PROTO_CALLBACK;
+        params.qos = options.qos();
+        params.operations = options.dpi_operations();
+        params.portNumber = options.port_number;
+        params.timeout = options.timeout;
+        params.name = name.ptr;
+        params.nameLength = name.len;
+        params.callback = Some(subscr_callback);
+        params.callbackContext = &*callbacks as *const Callbacks as *mut c_void;
+        let mut handle = ptr::null_mut();
+        let mut id = 0;
+        chkerr!(conn.ctxt,
+                dpiConn_newSubscription(conn.handle, &mut params, &mut handle, &mut id));
+        Ok(Subscription {
+            conn: conn,
+            handle: handle,
+            id: id,
+            callbacks: callbacks,
+        })
+    }
+
+    /// Returns the registration id, which is `REGID` of the
+    /// `USER_CHANGE_NOTIFICATION_REGS` data dictionary view.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Registers a query. Changes of objects referred by the query
+    /// are notified after this.
+    ///
+    /// This returns the query id assigned to the query, which is
+    /// [QueryChange.id][] of events when [SubscrOptions.query][] is `true`.
+    /// It is zero otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use oracle::*;
+    /// let conn = Connector::new("scott", "tiger", "").events(true).connect().unwrap();
+    /// let mut opts = SubscrOptions::new();
+    /// opts.query(true);
+    /// let (tx, rx) = std::sync::mpsc::channel();
+    /// let subscr = conn.subscribe(&opts, move |event| {
+    ///     let _ = tx.send(event.clone());
+    /// }).unwrap();
+    /// let emp_query = subscr.register_query("select * from emp where deptno = 10", &[]).unwrap();
+    /// let dept_query = subscr.register_query("select * from dept", &[]).unwrap();
+    /// let event = rx.recv().unwrap();
+    /// if event.query(emp_query).is_some() {
+    ///     println!("employees in department 10 changed");
+    /// }
+    /// if event.query(dept_query).is_some() {
+    ///     println!("departments changed");
+    /// }
+    /// ```
+    ///
+    /// [QueryChange.id]: struct.QueryChange.html#method.id
+    /// [SubscrOptions.query]: struct.SubscrOptions.html#method.query
+    pub fn register_query(&self, sql: &str, params: &[&ToSql]) -> Result<u64> {
+        let mut stmt = Statement::new_for_subscription(self.conn, self.handle, sql)?;
+        stmt.execute(params)?;
+        stmt.subscr_query_id()
+    }
+
+    /// Sets a function called with errors notified instead of events.
+    pub fn set_error_callback<F>(&self, f: F) where F: FnMut(&Error) + Send + 'static {
+        if let Ok(mut callback) = self.callbacks.error.lock() {
+            *callback = Some(Box::new(f));
+        }
+    }
+
+    /// Removes the function set by [set_error_callback](#method.set_error_callback).
+    pub fn unset_error_callback(&self) {
+        if let Ok(mut callback) = self.callbacks.error.lock() {
+            *callback = None;
+        }
+    }
+
+    /// Deregisters the subscription. No callback is called after this
+    /// returns.
+    pub fn unsubscribe(mut self) -> Result<()> {
+        let handle = self.handle;
+        self.handle = ptr::null_mut();
+        chkerr!(self.conn.ctxt,
+                dpiSubscr_close(handle),
+                unsafe { dpiSubscr_release(handle); });
+        unsafe { dpiSubscr_release(handle); }
+        Ok(())
+    }
+}
+
+impl<'conn> Drop for Subscription<'conn> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe {
+                dpiSubscr_close(self.handle);
+                dpiSubscr_release(self.handle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations() {
+        assert_eq!(Operation::from_dpi_value(DPI_OPCODE_ALL_OPS), vec![]);
+        assert_eq!(Operation::from_dpi_value(DPI_OPCODE_INSERT), vec![Operation::Insert]);
+        assert_eq!(Operation::from_dpi_value(DPI_OPCODE_ALL_ROWS | DPI_OPCODE_UPDATE | DPI_OPCODE_DELETE),
+                   vec![Operation::Update, Operation::Delete]);
+        for op in &[Operation::Insert, Operation::Update, Operation::Delete,
+                    Operation::Alter, Operation::Drop, Operation::Unknown] {
+            assert_eq!(Operation::from_dpi_value(op.to_dpi_value()), vec![*op]);
+        }
+    }
+
+    #[test]
+    fn subscr_options() {
+        let mut opts = SubscrOptions::new();
+        assert_eq!(opts.qos(), dpiSubscrQOS(0));
+        assert_eq!(opts.dpi_operations(), DPI_OPCODE_ALL_OPS);
+        opts.rowids(true).best_effort(true)
+            .operations(&[Operation::Insert, Operation::Delete]);
+        assert_eq!(opts.qos(), DPI_SUBSCR_QOS_ROWIDS | DPI_SUBSCR_QOS_BEST_EFFORT);
+        assert_eq!(opts.dpi_operations(), DPI_OPCODE_INSERT | DPI_OPCODE_DELETE);
+    }
+}
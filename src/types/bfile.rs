@@ -0,0 +1,214 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::fmt;
+
+use binding::*;
+use Context;
+use FromSql;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSql;
+
+use new_odpi_str;
+use to_odpi_str;
+
+/// BFILE locator pointing to a file outside of the database
+///
+/// A BFILE refers to a file by a directory alias and a file name.
+/// The file must be opened by [open](#method.open) before its content
+/// is read by [read_bytes](#method.read_bytes). [read_all](#method.read_all)
+/// opens and closes the file by itself when it isn't opened yet.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oracle::Bfile;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select bfilename('DATA_DIR', 'readme.txt') from dual", &[]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// let bfile: Bfile = row.get(0).unwrap();
+/// let (dir, name) = bfile.directory_and_file_name().unwrap();
+/// if bfile.file_exists().unwrap() {
+///     let content = bfile.read_all().unwrap();
+///     println!("{}/{}: {} bytes", dir, name, content.len());
+/// }
+/// ```
+pub struct Bfile {
+    ctxt: &'static Context,
+    pub(crate) handle: *mut dpiLob,
+}
+
+impl Bfile {
+    pub(crate) fn new(ctxt: &'static Context, handle: *mut dpiLob) -> Bfile {
+        unsafe { dpiLob_addRef(handle) };
+        Bfile {
+            ctxt: ctxt,
+            handle: handle,
+        }
+    }
+
+    /// Gets the directory alias and the file name.
+    pub fn directory_and_file_name(&self) -> Result<(String, String)> {
+        let mut dir = new_odpi_str();
+        let mut name = new_odpi_str();
+        chkerr!(self.ctxt,
+                dpiLob_getDirectoryAndFileName(self.handle, &mut dir.ptr, &mut dir.len,
+                                               &mut name.ptr, &mut name.len));
+        Ok((dir.to_string(), name.to_string()))
+    }
+
+    /// Changes the directory alias and the file name the locator points to.
+    pub fn set_directory_and_file_name(&mut self, dir: &str, name: &str) -> Result<()> {
+        let dir = to_odpi_str(dir);
+        let name = to_odpi_str(name);
+        chkerr!(self.ctxt,
+                dpiLob_setDirectoryAndFileName(self.handle, dir.ptr, dir.len, name.ptr, name.len));
+        Ok(())
+    }
+
+    /// Returns `true` when the file exists on the database server.
+    pub fn file_exists(&self) -> Result<bool> {
+        let mut exists = 0;
+        chkerr!(self.ctxt,
+                dpiLob_getFileExists(self.handle, &mut exists));
+        Ok(exists != 0)
+    }
+
+    /// Opens the file to read its content.
+    pub fn open(&mut self) -> Result<()> {
+        chkerr!(self.ctxt,
+                dpiLob_openResource(self.handle));
+        Ok(())
+    }
+
+    /// Closes the file opened by [open](#method.open).
+    pub fn close(&mut self) -> Result<()> {
+        chkerr!(self.ctxt,
+                dpiLob_closeResource(self.handle));
+        Ok(())
+    }
+
+    /// Returns `true` when the file is opened.
+    pub fn is_open(&self) -> Result<bool> {
+        let mut is_open = 0;
+        chkerr!(self.ctxt,
+                dpiLob_getIsResourceOpen(self.handle, &mut is_open));
+        Ok(is_open != 0)
+    }
+
+    /// Gets the size of the file in bytes.
+    pub fn size(&self) -> Result<u64> {
+        let mut size = 0;
+        chkerr!(self.ctxt,
+                dpiLob_getSize(self.handle, &mut size));
+        Ok(size)
+    }
+
+    /// Reads at most `amount` bytes starting at `offset`. The offset
+    /// of the first byte is 1 as in `DBMS_LOB`. The file must be opened.
+    pub fn read_bytes(&self, offset: u64, amount: u64) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; amount as usize];
+        let mut read_len = amount;
+        chkerr!(self.ctxt,
+                dpiLob_readBytes(self.handle, offset, amount, buf.as_mut_ptr() as *mut i8, &mut read_len));
+        buf.truncate(read_len as usize);
+        Ok(buf)
+    }
+
+    /// Reads the whole content of the file. The file is opened
+    /// and closed in this method when it isn't opened yet.
+    pub fn read_all(&self) -> Result<Vec<u8>> {
+        const READ_SIZE: u64 = 8192;
+        let opened_here = !self.is_open()?;
+        if opened_here {
+            chkerr!(self.ctxt,
+                    dpiLob_openResource(self.handle));
+        }
+        let result = (|| {
+            let total_size = self.size()?;
+            let mut result = Vec::with_capacity(total_size as usize);
+            let mut offset = 1;
+            while offset <= total_size {
+                let buf = self.read_bytes(offset, READ_SIZE)?;
+                if buf.is_empty() {
+                    break;
+                }
+                offset += buf.len() as u64;
+                result.extend_from_slice(&buf);
+            }
+            Ok(result)
+        })();
+        if opened_here {
+            unsafe { dpiLob_closeResource(self.handle) };
+        }
+        result
+    }
+}
+
+impl Clone for Bfile {
+    fn clone(&self) -> Bfile {
+        Bfile::new(self.ctxt, self.handle)
+    }
+}
+
+impl Drop for Bfile {
+    fn drop(&mut self) {
+        let _ = unsafe { dpiLob_release(self.handle) };
+    }
+}
+
+impl fmt::Debug for Bfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.directory_and_file_name() {
+            Ok((dir, name)) => write!(f, "Bfile {{ directory: {:?}, file_name: {:?} }}", dir, name),
+            Err(_) => write!(f, "Bfile {{ handle: {:?} }}", self.handle),
+        }
+    }
+}
+
+impl FromSql for Bfile {
+    fn from_sql(val: &SqlValue) -> Result<Bfile> {
+        val.as_bfile()
+    }
+}
+
+impl ToSql for Bfile {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::BFILE)
+    }
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_bfile(self)
+    }
+}
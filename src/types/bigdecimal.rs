@@ -0,0 +1,94 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use bigdecimal::BigDecimal;
+
+use Error;
+use FromSql;
+use OracleNumber;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSqlNull;
+use ToSql;
+
+//
+// bigdecimal::BigDecimal
+//
+
+/// Converts NUMBER to BigDecimal without loss of precision.
+///
+/// The scale of the result is the column scale when the column is declared
+/// as `NUMBER(p, s)` with positive `s`, e.g. `1.5` in `NUMBER(10, 2)`
+/// becomes `1.50`. Otherwise, the scale is the number of fractional digits.
+impl FromSql for BigDecimal {
+    fn from_sql(val: &SqlValue) -> Result<BigDecimal> {
+        let num = match val.as_small_integer()? {
+            Some(num) => BigDecimal::from(num),
+            None => {
+                let s = val.as_oracle_number()?.to_string();
+                s.parse().map_err(|err| Error::ParseError(Box::new(err)))?
+            },
+        };
+        // as_bigint_and_exponent() is used instead of fractional_digit_count(),
+        // which isn't in early 0.4 releases allowed by Cargo.toml.
+        let (_, num_scale) = num.as_bigint_and_exponent();
+        match *val.oracle_type()? {
+            OracleType::Number(_, scale) if scale > 0 && num_scale < scale as i64 =>
+                Ok(num.with_scale(scale as i64)),
+            _ =>
+                Ok(num),
+        }
+    }
+}
+
+impl ToSqlNull for BigDecimal {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::Number(0, 0))
+    }
+}
+
+/// Converts BigDecimal to NUMBER.
+///
+/// Numbers with more than 40 significant digits are rounded to 40 digits,
+/// which is the maximum precision of Oracle NUMBER.
+impl ToSql for BigDecimal {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::Number(0, 0))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        let (int_val, scale) = self.as_bigint_and_exponent();
+        let num: OracleNumber = format!("{}E{}", int_val, -scale).parse()?;
+        val.set_oracle_number(&num)
+    }
+}
@@ -0,0 +1,161 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+/// Conversion between `bool` and NUMBER or character columns
+///
+/// Only BOOLEAN is converted to and from `bool` by default. Legacy schemas
+/// often use `NUMBER(1)` or `CHAR(1)` columns as flags instead. Set a
+/// convention to [Connection.set_bool_convention][] to convert them by
+/// `row.get::<bool>()` and by binding `bool` with an explicit Oracle type.
+///
+/// [Connection.set_bool_convention]: struct.Connection.html#method.set_bool_convention
+///
+/// # Examples
+///
+/// ```no_run
+/// use oracle::{BoolConvention, Connection, OracleType};
+///
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+///
+/// // 1 and 0 in numeric columns and 'Y' and 'N' in character columns
+/// let mut conv = BoolConvention::new();
+/// conv.numbers(true).chars("Y", "N");
+/// conn.set_bool_convention(conv);
+///
+/// let mut stmt = conn.execute("select 1, 'N' from dual", &[]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// assert_eq!(row.get::<_, bool>(0).unwrap(), true);
+/// assert_eq!(row.get::<_, bool>(1).unwrap(), false);
+///
+/// // Bind true as 'Y'.
+/// conn.execute("update emp set active = :1 where empno = :2",
+///              &[&(&true, &OracleType::Char(1)), &7369]).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoolConvention {
+    numbers: bool,
+    chars: Option<(String, String)>,
+}
+
+impl BoolConvention {
+    /// Creates the default convention, which converts only BOOLEAN.
+    pub fn new() -> BoolConvention {
+        BoolConvention {
+            numbers: false,
+            chars: None,
+        }
+    }
+
+    /// Converts numbers 1 and 0 to `true` and `false` and vice versa when
+    /// `enable` is true. Other numbers are errors.
+    pub fn numbers<'a>(&'a mut self, enable: bool) -> &'a mut BoolConvention {
+        self.numbers = enable;
+        self
+    }
+
+    /// Converts `true_value` and `false_value` in character columns to
+    /// `true` and `false` and vice versa. Other strings are errors.
+    /// Trailing spaces of fetched values are ignored.
+    pub fn chars<'a>(&'a mut self, true_value: &str, false_value: &str) -> &'a mut BoolConvention {
+        self.chars = Some((true_value.to_string(), false_value.to_string()));
+        self
+    }
+
+    pub(crate) fn bool_from_number(&self, num: i64) -> Option<bool> {
+        match num {
+            1 if self.numbers => Some(true),
+            0 if self.numbers => Some(false),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn bool_from_str(&self, s: &str) -> Option<bool> {
+        match self.chars {
+            Some((ref t, ref f)) => {
+                let s = s.trim_end_matches(' ');
+                if s == t {
+                    Some(true)
+                } else if s == f {
+                    Some(false)
+                } else {
+                    None
+                }
+            },
+            None => None,
+        }
+    }
+
+    pub(crate) fn number_from_bool(&self, val: bool) -> Option<i64> {
+        if self.numbers {
+            Some(if val { 1 } else { 0 })
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn str_from_bool(&self, val: bool) -> Option<&str> {
+        match self.chars {
+            Some((ref t, ref f)) => Some(if val { t } else { f }),
+            None => None,
+        }
+    }
+}
+
+impl Default for BoolConvention {
+    fn default() -> BoolConvention {
+        BoolConvention::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert() {
+        let conv = BoolConvention::new();
+        assert_eq!(conv.bool_from_number(1), None);
+        assert_eq!(conv.bool_from_str("Y"), None);
+        assert_eq!(conv.number_from_bool(true), None);
+
+        let mut conv = BoolConvention::new();
+        conv.numbers(true).chars("Y", "N");
+        assert_eq!(conv.bool_from_number(1), Some(true));
+        assert_eq!(conv.bool_from_number(0), Some(false));
+        assert_eq!(conv.bool_from_number(2), None);
+        assert_eq!(conv.bool_from_str("Y"), Some(true));
+        assert_eq!(conv.bool_from_str("N  "), Some(false));
+        assert_eq!(conv.bool_from_str("y"), None);
+        assert_eq!(conv.number_from_bool(false), Some(0));
+        assert_eq!(conv.str_from_bool(true), Some("Y"));
+    }
+}
@@ -35,15 +35,19 @@ use chrono::prelude::*;
 use Error;
 use FromSql;
 use IntervalDS;
+use IntervalYM;
 use OracleType;
+use ParseOracleTypeError;
 use Result;
 use SqlValue;
 use Timestamp;
 use ToSqlNull;
 use ToSql;
 use chrono::Duration;
+use chrono::Months;
 use chrono::naive::NaiveDate;
 use chrono::naive::NaiveDateTime;
+use chrono::naive::NaiveTime;
 
 //
 // chrono::DateTime<Utc>
@@ -57,24 +61,41 @@ fn datetime_from_sql<Tz>(tz: &Tz, ts: &Timestamp) -> Result<DateTime<Tz>> where
     Ok(tz.ymd(ts.year(), ts.month(), ts.day()).and_hms_nano(ts.hour(), ts.minute(), ts.second(), ts.nanosecond()))
 }
 
+fn fixed_offset(ts: &Timestamp) -> Result<FixedOffset> {
+    FixedOffset::east_opt(ts.tz_offset())
+        .ok_or_else(|| ParseOracleTypeError::new("FixedOffset").into())
+}
+
+// TIMESTAMP WITH LOCAL TIME ZONE is a point in time fetched in the session
+// time zone. Convert it by the offset so that the result doesn't depend on
+// the session time zone. The offset of TIMESTAMP WITH TIME ZONE is ignored
+// for compatibility.
+fn datetime_in_tz_from_sql<Tz>(tz: &Tz, val: &SqlValue) -> Result<DateTime<Tz>> where Tz: TimeZone {
+    let ts = val.as_timestamp()?;
+    match *val.oracle_type()? {
+        OracleType::TimestampLTZ(_) =>
+            Ok(datetime_from_sql(&fixed_offset(&ts)?, &ts)?.with_timezone(tz)),
+        _ =>
+            datetime_from_sql(tz, &ts),
+    }
+}
+
 impl FromSql for DateTime<Utc> {
     fn from_sql(val: &SqlValue) -> Result<DateTime<Utc>> {
-        let ts = val.as_timestamp()?;
-        datetime_from_sql(&Utc, &ts)
+        datetime_in_tz_from_sql(&Utc, val)
     }
 }
 
 impl FromSql for DateTime<Local> {
     fn from_sql(val: &SqlValue) -> Result<DateTime<Local>> {
-        let ts = val.as_timestamp()?;
-        datetime_from_sql(&Local, &ts)
+        datetime_in_tz_from_sql(&Local, val)
     }
 }
 
 impl FromSql for DateTime<FixedOffset> {
     fn from_sql(val: &SqlValue) -> Result<DateTime<FixedOffset>> {
         let ts = val.as_timestamp()?;
-        datetime_from_sql(&FixedOffset::east(ts.tz_offset()), &ts)
+        datetime_from_sql(&fixed_offset(&ts)?, &ts)
     }
 }
 
@@ -108,24 +129,33 @@ fn date_from_sql<Tz>(tz: &Tz, ts: &Timestamp) -> Result<Date<Tz>> where Tz: Time
     Ok(tz.ymd(ts.year(), ts.month(), ts.day()))
 }
 
+// The date of TIMESTAMP WITH LOCAL TIME ZONE is taken after conversion
+// to `tz` for the same reason as datetime_in_tz_from_sql().
+fn date_in_tz_from_sql<Tz>(tz: &Tz, val: &SqlValue) -> Result<Date<Tz>> where Tz: TimeZone {
+    match *val.oracle_type()? {
+        OracleType::TimestampLTZ(_) =>
+            Ok(datetime_in_tz_from_sql(tz, val)?.date()),
+        _ =>
+            date_from_sql(tz, &val.as_timestamp()?),
+    }
+}
+
 impl FromSql for Date<Utc> {
     fn from_sql(val: &SqlValue) -> Result<Date<Utc>> {
-        let ts = val.as_timestamp()?;
-        date_from_sql(&Utc, &ts)
+        date_in_tz_from_sql(&Utc, val)
     }
 }
 
 impl FromSql for Date<Local> {
     fn from_sql(val: &SqlValue) -> Result<Date<Local>> {
-        let ts = val.as_timestamp()?;
-        date_from_sql(&Local, &ts)
+        date_in_tz_from_sql(&Local, val)
     }
 }
 
 impl FromSql for Date<FixedOffset> {
     fn from_sql(val: &SqlValue) -> Result<Date<FixedOffset>> {
         let ts = val.as_timestamp()?;
-        date_from_sql(&FixedOffset::east(ts.tz_offset()), &ts)
+        date_from_sql(&fixed_offset(&ts)?, &ts)
     }
 }
 
@@ -252,3 +282,76 @@ impl ToSql for Duration {
         val.set_interval_ds(&it)
     }
 }
+
+//
+// chrono::naive::NaiveTime
+//
+
+impl FromSql for NaiveTime {
+    fn from_sql(val: &SqlValue) -> Result<NaiveTime> {
+        let it = val.as_interval_ds()?;
+        if it.days() != 0 || it.hours() < 0 || it.minutes() < 0 || it.seconds() < 0 || it.nanoseconds() < 0 {
+            return Err(Error::Overflow(it.to_string(), "NaiveTime"));
+        }
+        NaiveTime::from_hms_nano_opt(it.hours() as u32, it.minutes() as u32, it.seconds() as u32, it.nanoseconds() as u32)
+            .ok_or(Error::Overflow(it.to_string(), "NaiveTime"))
+    }
+}
+
+impl ToSqlNull for NaiveTime {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+}
+
+impl ToSql for NaiveTime {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        // A leap second is represented as nanosecond >= 1_000_000_000 in chrono.
+        if self.nanosecond() >= 1_000_000_000 {
+            return Err(Error::Overflow(self.to_string(), "INTERVAL DAY TO SECOND"));
+        }
+        let it = IntervalDS::new(0, self.hour() as i32, self.minute() as i32, self.second() as i32, self.nanosecond() as i32);
+        val.set_interval_ds(&it)
+    }
+}
+
+//
+// chrono::Months
+//
+
+impl FromSql for Months {
+    fn from_sql(val: &SqlValue) -> Result<Months> {
+        let it = val.as_interval_ym()?;
+        let months = it.years() as i64 * 12 + it.months() as i64;
+        if months < 0 {
+            return Err(Error::Overflow(it.to_string(), "Months"));
+        }
+        Ok(Months::new(months as u32))
+    }
+}
+
+impl ToSqlNull for Months {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::IntervalYM(9))
+    }
+}
+
+impl ToSql for Months {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::IntervalYM(9))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        let months = self.as_u32();
+        let years = months / 12;
+        if years >= 1000000000 {
+            return Err(Error::Overflow(format!("{} months", months), "INTERVAL YEAR TO MONTH"));
+        }
+        let it = IntervalYM::new(years as i32, (months % 12) as i32);
+        val.set_interval_ym(&it)
+    }
+}
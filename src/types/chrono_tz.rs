@@ -0,0 +1,147 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+//! Conversions between `TIMESTAMP WITH TIME ZONE` and `chrono_tz`.
+//!
+//! ODPI-C passes time zones of `TIMESTAMP WITH TIME ZONE` values as offsets
+//! from UTC. Region names such as `Europe/Paris` are dropped by it. Convert
+//! the value to a string with `TZR` in SQL to keep the region name:
+//!
+//! ```sql
+//! select to_char(ts_col, 'YYYY-MM-DD HH24:MI:SS.FF9 TZR') from ...
+//! select extract(timezone_region from ts_col) from ...
+//! ```
+//!
+//! The former is fetched as `DateTime<Tz>` in the region and the latter as `Tz`.
+//! `TIMESTAMP WITH TIME ZONE` values fetched directly are converted to
+//! `DateTime<Tz>` in UTC. `DateTime<Tz>` is bound with its offset from UTC
+//! as other `DateTime` types.
+
+use chrono::prelude::*;
+use chrono::LocalResult;
+use chrono_tz::Tz;
+
+use FromSql;
+use ParseOracleTypeError;
+use Result;
+use SqlValue;
+use Timestamp;
+
+//
+// chrono_tz::Tz
+//
+
+impl FromSql for Tz {
+    fn from_sql(val: &SqlValue) -> Result<Tz> {
+        let s = val.as_string()?;
+        s.trim().parse().map_err(|_| ParseOracleTypeError::new("Tz").into())
+    }
+}
+
+//
+// chrono::DateTime<chrono_tz::Tz>
+//
+
+fn datetime_from_str(s: &str) -> Result<DateTime<Tz>> {
+    let s = s.trim();
+    if let Some(pos) = s.rfind(' ') {
+        if let Ok(tz) = s[pos + 1..].parse::<Tz>() {
+            let ts: Timestamp = s[..pos].parse()?;
+            let naive = NaiveDate::from_ymd_opt(ts.year(), ts.month(), ts.day())
+                .and_then(|d| d.and_hms_nano_opt(ts.hour(), ts.minute(), ts.second(), ts.nanosecond()))
+                .ok_or_else(|| ParseOracleTypeError::new("DateTime<Tz>"))?;
+            return match tz.from_local_datetime(&naive) {
+                LocalResult::Single(dt) => Ok(dt),
+                // Oracle uses the standard time for ambiguous local times
+                // without TZD.
+                LocalResult::Ambiguous(dt1, dt2) =>
+                    if dt1.offset().fix().local_minus_utc() < dt2.offset().fix().local_minus_utc() {
+                        Ok(dt1)
+                    } else {
+                        Ok(dt2)
+                    },
+                LocalResult::None => Err(ParseOracleTypeError::new("DateTime<Tz>").into()),
+            };
+        }
+    }
+    let ts: Timestamp = s.parse()?;
+    datetime_from_timestamp(&ts)
+}
+
+fn datetime_from_timestamp(ts: &Timestamp) -> Result<DateTime<Tz>> {
+    let offset = FixedOffset::east_opt(ts.tz_offset())
+        .ok_or_else(|| ParseOracleTypeError::new("DateTime<Tz>"))?;
+    let naive = NaiveDate::from_ymd_opt(ts.year(), ts.month(), ts.day())
+        .and_then(|d| d.and_hms_nano_opt(ts.hour(), ts.minute(), ts.second(), ts.nanosecond()))
+        .ok_or_else(|| ParseOracleTypeError::new("DateTime<Tz>"))?;
+    match offset.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Ok(dt.with_timezone(&Tz::UTC)),
+        _ => Err(ParseOracleTypeError::new("DateTime<Tz>").into()),
+    }
+}
+
+/// Converts a string in the form of `YYYY-MM-DD HH24:MI:SS.FF9 TZR` to
+/// `DateTime<Tz>` in the region. Other values are converted to
+/// `DateTime<Tz>` in UTC.
+impl FromSql for DateTime<Tz> {
+    fn from_sql(val: &SqlValue) -> Result<DateTime<Tz>> {
+        match val.as_timestamp() {
+            Ok(ts) => datetime_from_timestamp(&ts),
+            Err(_) => datetime_from_str(&val.as_string()?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use chrono_tz::Europe::Paris;
+
+    #[test]
+    fn from_str() {
+        let dt = datetime_from_str("2018-03-04 05:06:07.123456789 Europe/Paris").unwrap();
+        assert_eq!(dt.timezone(), Paris);
+        assert_eq!(dt, Paris.with_ymd_and_hms(2018, 3, 4, 5, 6, 7).unwrap() + Duration::nanoseconds(123456789));
+
+        // ambiguous local time
+        let dt = datetime_from_str("2018-10-28 02:30:00 Europe/Paris").unwrap();
+        assert_eq!(dt.offset().fix().local_minus_utc(), 3600);
+
+        // nonexistent local time
+        assert!(datetime_from_str("2018-03-25 02:30:00 Europe/Paris").is_err());
+
+        let dt = datetime_from_str("2018-03-04 05:06:07 +09:00").unwrap();
+        assert_eq!(dt.timezone(), Tz::UTC);
+        assert_eq!(dt, Tz::UTC.with_ymd_and_hms(2018, 3, 3, 20, 6, 7).unwrap());
+    }
+}
@@ -0,0 +1,233 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::fmt;
+use std::time::Duration;
+
+use Error;
+use FromSql;
+use IntervalDS;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSqlNull;
+use ToSql;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const MAX_DAYS: u64 = 999999999;
+
+/// A signed version of `std::time::Duration`
+///
+/// `std::time::Duration` cannot be negative. Use this to fetch and bind
+/// negative `INTERVAL DAY TO SECOND` values without `chrono` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use oracle::SignedDuration;
+///
+/// let d = SignedDuration::negative(Duration::new(90061, 500000000));
+/// let it = d.to_interval_ds().unwrap();
+/// assert_eq!(it.to_string(), "-000000001 01:01:01.500000000");
+/// assert_eq!(SignedDuration::from_interval_ds(&it), d);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignedDuration {
+    negative: bool,
+    duration: Duration,
+}
+
+impl SignedDuration {
+    /// Creates a positive duration.
+    pub fn positive(duration: Duration) -> SignedDuration {
+        SignedDuration {
+            negative: false,
+            duration: duration,
+        }
+    }
+
+    /// Creates a negative duration. This is positive when `duration` is zero.
+    pub fn negative(duration: Duration) -> SignedDuration {
+        SignedDuration {
+            negative: duration != Duration::new(0, 0),
+            duration: duration,
+        }
+    }
+
+    /// Returns `true` when the duration is negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the absolute value of the duration.
+    pub fn abs(&self) -> Duration {
+        self.duration
+    }
+
+    /// Creates a duration from an interval.
+    pub fn from_interval_ds(it: &IntervalDS) -> SignedDuration {
+        let secs = it.days() as i64 * SECS_PER_DAY as i64 + it.hours() as i64 * 3600
+            + it.minutes() as i64 * 60 + it.seconds() as i64;
+        let nsecs = it.nanoseconds() as i64;
+        // All fields of IntervalDS have same sign.
+        if secs < 0 || nsecs < 0 {
+            SignedDuration::negative(Duration::new((-secs) as u64, (-nsecs) as u32))
+        } else {
+            SignedDuration::positive(Duration::new(secs as u64, nsecs as u32))
+        }
+    }
+
+    /// Converts the duration to an interval. This returns `Error::Overflow`
+    /// when it is 1,000,000,000 days or longer.
+    pub fn to_interval_ds(&self) -> Result<IntervalDS> {
+        let secs = self.duration.as_secs();
+        let days = secs / SECS_PER_DAY;
+        if days > MAX_DAYS {
+            return Err(Error::Overflow(self.to_string(), "INTERVAL DAY TO SECOND"));
+        }
+        let secs = secs % SECS_PER_DAY;
+        let sign = if self.negative { -1 } else { 1 };
+        Ok(IntervalDS::new(sign * days as i32,
+                           sign * (secs / 3600) as i32,
+                           sign * (secs % 3600 / 60) as i32,
+                           sign * (secs % 60) as i32,
+                           sign * self.duration.subsec_nanos() as i32))
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(duration: Duration) -> SignedDuration {
+        SignedDuration::positive(duration)
+    }
+}
+
+impl fmt::Display for SignedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            write!(f, "-{:?}", self.duration)
+        } else {
+            write!(f, "{:?}", self.duration)
+        }
+    }
+}
+
+//
+// oracle::SignedDuration
+//
+
+impl FromSql for SignedDuration {
+    fn from_sql(val: &SqlValue) -> Result<SignedDuration> {
+        Ok(SignedDuration::from_interval_ds(&val.as_interval_ds()?))
+    }
+}
+
+impl ToSqlNull for SignedDuration {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+}
+
+impl ToSql for SignedDuration {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_interval_ds(&self.to_interval_ds()?)
+    }
+}
+
+//
+// std::time::Duration
+//
+
+/// Converts INTERVAL DAY TO SECOND to Duration. This returns
+/// `Error::Overflow` for negative intervals. Use [SignedDuration][] instead
+/// to fetch them.
+///
+/// [SignedDuration]: struct.SignedDuration.html
+impl FromSql for Duration {
+    fn from_sql(val: &SqlValue) -> Result<Duration> {
+        let it = val.as_interval_ds()?;
+        let d = SignedDuration::from_interval_ds(&it);
+        if d.is_negative() {
+            return Err(Error::Overflow(it.to_string(), "Duration"));
+        }
+        Ok(d.abs())
+    }
+}
+
+impl ToSqlNull for Duration {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+}
+
+impl ToSql for Duration {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::IntervalDS(9, 9))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_interval_ds(&SignedDuration::positive(*self).to_interval_ds()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_interval_ds() {
+        let d = SignedDuration::positive(Duration::new(90061, 123456789));
+        assert_eq!(d.to_interval_ds().unwrap(), IntervalDS::new(1, 1, 1, 1, 123456789));
+        let d = SignedDuration::negative(Duration::new(90061, 123456789));
+        assert_eq!(d.to_interval_ds().unwrap(), IntervalDS::new(-1, -1, -1, -1, -123456789));
+        let d = SignedDuration::negative(Duration::new(0, 0));
+        assert!(!d.is_negative());
+        let d = SignedDuration::positive(Duration::new((MAX_DAYS + 1) * SECS_PER_DAY - 1, 999999999));
+        assert_eq!(d.to_interval_ds().unwrap(), IntervalDS::new(999999999, 23, 59, 59, 999999999));
+        let d = SignedDuration::positive(Duration::new((MAX_DAYS + 1) * SECS_PER_DAY, 0));
+        assert!(d.to_interval_ds().is_err());
+    }
+
+    #[test]
+    fn from_interval_ds() {
+        let it = IntervalDS::new(1, 1, 1, 1, 123456789);
+        assert_eq!(SignedDuration::from_interval_ds(&it), SignedDuration::positive(Duration::new(90061, 123456789)));
+        let it = IntervalDS::new(0, 0, 0, 0, -1);
+        assert_eq!(SignedDuration::from_interval_ds(&it), SignedDuration::negative(Duration::new(0, 1)));
+        let it = IntervalDS::new(-999999999, -23, -59, -59, -999999999);
+        assert_eq!(SignedDuration::from_interval_ds(&it).to_interval_ds().unwrap(), it);
+    }
+}
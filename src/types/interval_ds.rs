@@ -43,10 +43,11 @@ use ParseOracleTypeError;
 ///
 /// [INTVL_DS]: https://docs.oracle.com/database/122/NLSPG/datetime-data-types-and-time-zone-support.htm#GUID-FD8C41B7-8CDC-4D02-8E6B-5250416BC17D
 ///
-/// This struct doesn't have arithmetic methods and they won't be added to avoid
-/// reinventing the wheel. If you need methods such as adding an interval to a
-/// timestamp, enable `chrono` feature and use [chrono::Duration][] instead.
+/// This struct doesn't have arithmetic methods other than adding to and
+/// subtracting from [Timestamp][]. If you need more, enable `chrono` feature
+/// and use [chrono::Duration][] instead.
 ///
+/// [Timestamp]: struct.Timestamp.html
 /// [chrono::Duration]: https://docs.rs/chrono/0.4/chrono/struct.Duration.html
 ///
 /// # Examples
@@ -79,6 +80,11 @@ use ParseOracleTypeError;
 /// // The precisions are determined by number of decimal digits in the string.
 /// assert_eq!(intvl4.lfprec(), 1);
 /// assert_eq!(intvl4.fsprec(), 2);
+///
+/// // Convert from and to ISO 8601 duration.
+/// let intvl5: IntervalDS = "P1DT2H3M4.5S".parse().unwrap();
+/// assert_eq!(intvl5, intvl1);
+/// assert_eq!(intvl2.to_iso8601(), "-P1DT2H3M4.5S");
 /// ```
 ///
 /// Fetch and bind interval values.
@@ -209,6 +215,135 @@ impl IntervalDS {
     pub fn fsprec(&self) -> u8 {
         self.fsprec
     }
+
+    /// Creates an interval from an [ISO 8601 duration][] such as `P1DT2H3M4.5S`.
+    ///
+    /// Weeks, days, hours, minutes and seconds are allowed. Years and months
+    /// aren't because their lengths vary. Values larger than the next unit,
+    /// such as `PT36H`, are normalized. A leading minus sign makes a negative
+    /// interval. `str.parse()` also accepts this format.
+    ///
+    /// [ISO 8601 duration]: https://en.wikipedia.org/wiki/ISO_8601#Durations
+    pub fn from_iso8601(s: &str) -> Result<IntervalDS, ParseOracleTypeError> {
+        let err = || ParseOracleTypeError::new("IntervalDS");
+        let mut s = Scanner::new(s);
+        let minus = match s.char() {
+            Some('+') => {
+                s.next();
+                false
+            },
+            Some('-') => {
+                s.next();
+                true
+            },
+            _ => false,
+        };
+        if let Some('P') = s.char() {
+            s.next();
+        } else {
+            return Err(err());
+        }
+        let mut secs = 0u64;
+        let mut nsecs = 0;
+        let mut fsprec = 0;
+        let mut last_unit = 0;
+        let mut in_time = false;
+        loop {
+            match s.char() {
+                None if last_unit != 0 && (!in_time || last_unit > 2) => break,
+                Some('T') if !in_time => {
+                    s.next();
+                    in_time = true;
+                    continue;
+                },
+                _ => (),
+            }
+            let num = s.read_digits().ok_or(err())?;
+            if let Some('.') = s.char() {
+                s.next();
+                if !in_time {
+                    return Err(err());
+                }
+                let frac = s.read_digits();
+                let ndigit = s.ndigits();
+                if ndigit > 9 {
+                    return Err(err());
+                }
+                // less than 10^9, which fits in u32
+                nsecs = frac.ok_or(err())? as u32 * 10u32.pow(9 - ndigit);
+                fsprec = ndigit;
+                if s.char() != Some('S') {
+                    return Err(err());
+                }
+            }
+            let (unit, unit_secs) = match (in_time, s.char()) {
+                (false, Some('W')) => (1, 7 * 86400),
+                (false, Some('D')) => (2, 86400),
+                (true, Some('H')) => (3, 3600),
+                (true, Some('M')) => (4, 60),
+                (true, Some('S')) => (5, 1),
+                _ => return Err(err()),
+            };
+            if unit <= last_unit {
+                return Err(err());
+            }
+            last_unit = unit;
+            s.next();
+            secs = num.checked_mul(unit_secs).and_then(|n| secs.checked_add(n)).ok_or(err())?;
+        }
+        let days = secs / 86400;
+        if days > 999999999 {
+            return Err(err());
+        }
+        let sign = if minus { -1 } else { 1 };
+        Ok(IntervalDS {
+            days: sign * days as i32,
+            hours: sign * (secs % 86400 / 3600) as i32,
+            minutes: sign * (secs % 3600 / 60) as i32,
+            seconds: sign * (secs % 60) as i32,
+            nanoseconds: sign * nsecs as i32,
+            lfprec: days.to_string().len() as u8,
+            fsprec: fsprec as u8,
+        })
+    }
+
+    /// Returns the interval as an [ISO 8601 duration][] such as `P1DT2H3M4.5S`.
+    ///
+    /// Zero components are omitted. The zero interval is `PT0S`.
+    ///
+    /// [ISO 8601 duration]: https://en.wikipedia.org/wiki/ISO_8601#Durations
+    pub fn to_iso8601(&self) -> String {
+        let mut s = String::new();
+        if self.days < 0 || self.hours < 0 || self.minutes < 0 || self.seconds < 0 || self.nanoseconds < 0 {
+            s.push('-');
+        }
+        s.push('P');
+        if self.days != 0 {
+            s.push_str(&format!("{}D", self.days.abs()));
+        }
+        if self.hours != 0 || self.minutes != 0 || self.seconds != 0 || self.nanoseconds != 0 {
+            s.push('T');
+            if self.hours != 0 {
+                s.push_str(&format!("{}H", self.hours.abs()));
+            }
+            if self.minutes != 0 {
+                s.push_str(&format!("{}M", self.minutes.abs()));
+            }
+            if self.seconds != 0 || self.nanoseconds != 0 {
+                s.push_str(&self.seconds.abs().to_string());
+                if self.nanoseconds != 0 {
+                    let frac = format!("{:09}", self.nanoseconds.abs());
+                    s.push('.');
+                    s.push_str(frac.trim_end_matches('0'));
+                }
+                s.push('S');
+            }
+        }
+        if s.ends_with('P') {
+            s.push_str("T0S");
+        }
+        s
+    }
 }
 
 impl cmp::PartialEq for IntervalDS {
@@ -261,6 +396,9 @@ impl str::FromStr for IntervalDS {
     type Err = ParseOracleTypeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start_matches(|c| c == '+' || c == '-').starts_with('P') {
+            return IntervalDS::from_iso8601(s);
+        }
         let err = || ParseOracleTypeError::new("IntervalDS");
         let mut s = Scanner::new(s);
         let minus = match s.char() {
@@ -442,4 +580,33 @@ mod tests {
         it.fsprec = 9; it.nanoseconds = -123456789;
         assert_eq!("-1 02:03:04.123456789".parse(), Ok(it));
     }
+
+    #[test]
+    fn iso8601() {
+        let it = IntervalDS::new(1, 2, 3, 4, 500000000);
+        assert_eq!(it.to_iso8601(), "P1DT2H3M4.5S");
+        assert_eq!(IntervalDS::from_iso8601("P1DT2H3M4.5S"), Ok(it));
+        assert_eq!("P1DT2H3M4.5S".parse(), Ok(it));
+        assert_eq!("PT26H3M4.500S".parse(), Ok(it));
+        let it = IntervalDS::new(-8, 0, 0, 0, -1);
+        assert_eq!(it.to_iso8601(), "-P8DT0.000000001S");
+        assert_eq!("-P1W1DT0.000000001S".parse(), Ok(it));
+        assert_eq!(IntervalDS::new(0, 0, 0, 0, 0).to_iso8601(), "PT0S");
+        assert_eq!(IntervalDS::new(0, 0, 5, 0, 0).to_iso8601(), "PT5M");
+        assert_eq!("PT0S".parse(), Ok(IntervalDS::new(0, 0, 0, 0, 0)));
+
+        let err = Err(ParseOracleTypeError::new("IntervalDS"));
+        assert_eq!(IntervalDS::from_iso8601("P"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT"), err);
+        assert_eq!(IntervalDS::from_iso8601("P1DT"), err);
+        assert_eq!(IntervalDS::from_iso8601("P1Y"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT1M2H"), err);
+        assert_eq!(IntervalDS::from_iso8601("P1H"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT1.5M"), err);
+        assert_eq!(IntervalDS::from_iso8601("P1000000000D"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT1.99999999999S"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT99999999999999999999S"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT1.S"), err);
+        assert_eq!(IntervalDS::from_iso8601("PT1.999999999S"), Ok(IntervalDS::new(0, 0, 0, 1, 999999999)));
+    }
 }
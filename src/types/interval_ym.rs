@@ -76,6 +76,11 @@ use ParseOracleTypeError;
 ///
 /// // The precision is determined by number of decimal digits in the string.
 /// assert_eq!(intvl4.precision(), 3);
+///
+/// // Convert from and to ISO 8601 duration.
+/// let intvl5: IntervalYM = "P2Y3M".parse().unwrap();
+/// assert_eq!(intvl5, intvl1);
+/// assert_eq!(intvl2.to_iso8601(), "-P2Y3M");
 /// ```
 ///
 /// Fetch and bind interval values.
@@ -168,6 +173,83 @@ impl IntervalYM {
     pub fn precision(&self) -> u8 {
         self.precision
     }
+
+    /// Creates an interval from an [ISO 8601 duration][] such as `P1Y2M`.
+    ///
+    /// Only years and months are allowed. Months larger than 11, such as
+    /// `P18M`, are normalized. A leading minus sign makes a negative interval.
+    /// `str.parse()` also accepts this format.
+    ///
+    /// [ISO 8601 duration]: https://en.wikipedia.org/wiki/ISO_8601#Durations
+    pub fn from_iso8601(s: &str) -> Result<IntervalYM, ParseOracleTypeError> {
+        let err = || ParseOracleTypeError::new("IntervalYM");
+        let mut s = Scanner::new(s);
+        let minus = match s.char() {
+            Some('+') => {
+                s.next();
+                false
+            },
+            Some('-') => {
+                s.next();
+                true
+            },
+            _ => false,
+        };
+        if let Some('P') = s.char() {
+            s.next();
+        } else {
+            return Err(err());
+        }
+        let mut months = 0u64;
+        let mut last_unit = 0;
+        loop {
+            if s.char().is_none() && last_unit != 0 {
+                break;
+            }
+            let num = s.read_digits().ok_or(err())?;
+            let (unit, unit_months) = match s.char() {
+                Some('Y') => (1, 12),
+                Some('M') => (2, 1),
+                _ => return Err(err()),
+            };
+            if unit <= last_unit {
+                return Err(err());
+            }
+            last_unit = unit;
+            s.next();
+            months = num.checked_mul(unit_months).and_then(|n| months.checked_add(n)).ok_or(err())?;
+        }
+        let years = months / 12;
+        if years > 999999999 {
+            return Err(err());
+        }
+        let sign = if minus { -1 } else { 1 };
+        Ok(IntervalYM {
+            years: sign * years as i32,
+            months: sign * (months % 12) as i32,
+            precision: years.to_string().len() as u8,
+        })
+    }
+
+    /// Returns the interval as an [ISO 8601 duration][] such as `P1Y2M`.
+    ///
+    /// Zero components are omitted. The zero interval is `P0M`.
+    ///
+    /// [ISO 8601 duration]: https://en.wikipedia.org/wiki/ISO_8601#Durations
+    pub fn to_iso8601(&self) -> String {
+        let mut s = String::new();
+        if self.years < 0 || self.months < 0 {
+            s.push('-');
+        }
+        s.push('P');
+        if self.years != 0 {
+            s.push_str(&format!("{}Y", self.years.abs()));
+        }
+        if self.months != 0 || self.years == 0 {
+            s.push_str(&format!("{}M", self.months.abs()));
+        }
+        s
+    }
 }
 
 impl cmp::PartialEq for IntervalYM {
@@ -203,6 +285,9 @@ impl str::FromStr for IntervalYM {
     type Err = ParseOracleTypeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start_matches(|c| c == '+' || c == '-').starts_with('P') {
+            return IntervalYM::from_iso8601(s);
+        }
         let err = || ParseOracleTypeError::new("IntervalYM");
         let mut s = Scanner::new(s);
         let minus = match s.char() {
@@ -312,4 +397,25 @@ mod tests {
         let it = IntervalYM::new(-1, -2);
         assert_eq!("-000000001-02".parse(), Ok(it));
     }
+
+    #[test]
+    fn iso8601() {
+        let it = IntervalYM::new(1, 2);
+        assert_eq!(it.to_iso8601(), "P1Y2M");
+        assert_eq!(IntervalYM::from_iso8601("P1Y2M"), Ok(it));
+        assert_eq!("P1Y2M".parse(), Ok(it));
+        assert_eq!("P14M".parse(), Ok(it));
+        assert_eq!(IntervalYM::new(-1, 0).to_iso8601(), "-P1Y");
+        assert_eq!(IntervalYM::new(0, -3).to_iso8601(), "-P3M");
+        assert_eq!(IntervalYM::new(0, 0).to_iso8601(), "P0M");
+        assert_eq!("-P3M".parse(), Ok(IntervalYM::new(0, -3)));
+
+        let err = Err(ParseOracleTypeError::new("IntervalYM"));
+        assert_eq!(IntervalYM::from_iso8601("P"), err);
+        assert_eq!(IntervalYM::from_iso8601("P1D"), err);
+        assert_eq!(IntervalYM::from_iso8601("P1M1Y"), err);
+        assert_eq!(IntervalYM::from_iso8601("PT1M"), err);
+        assert_eq!(IntervalYM::from_iso8601("P99999999999999999999Y"), err);
+        assert_eq!(IntervalYM::from_iso8601("P1537228672809129301M"), err);
+    }
 }
@@ -0,0 +1,1010 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::str;
+
+use binding::*;
+use Connection;
+use Context;
+use Error;
+use FromSql;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSql;
+
+// The number of bytes a character in a CLOB takes at most in UTF-8.
+// The length of a CLOB is counted in UCS-2 code units, so a character
+// outside of the basic multilingual plane is counted as two.
+const MAX_BYTES_PER_CHAR: usize = 4;
+
+fn ucs2_len(s: &str) -> u64 {
+    s.chars().map(|c| c.len_utf16() as u64).sum()
+}
+
+fn seek_position(pos: u64, size: u64, seek_from: io::SeekFrom) -> io::Result<u64> {
+    let newpos = match seek_from {
+        io::SeekFrom::Start(offset) => return Ok(offset),
+        io::SeekFrom::Current(offset) => pos as i64 + offset,
+        io::SeekFrom::End(offset) => size as i64 + offset,
+    };
+    if newpos < 0 {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative position"))
+    } else {
+        Ok(newpos as u64)
+    }
+}
+
+// The maximum size in bytes written by one call of dpiLob_writeBytes.
+const MAX_WRITE_SIZE: usize = 1024 * 1024;
+
+// Returns the number of bytes written at a time, which is a multiple
+// of the chunk size so that each write fills whole chunks.
+fn write_size(chunk_size: u32) -> usize {
+    let chunk_size = cmp::max(chunk_size as usize, 1);
+    cmp::max(MAX_WRITE_SIZE / chunk_size, 1) * chunk_size
+}
+
+pub(crate) fn lob_size(ctxt: &'static Context, handle: *mut dpiLob) -> Result<u64> {
+    let mut size = 0;
+    chkerr!(ctxt,
+            dpiLob_getSize(handle, &mut size));
+    Ok(size)
+}
+
+pub(crate) fn lob_chunk_size(ctxt: &'static Context, handle: *mut dpiLob) -> Result<u32> {
+    let mut size = 0;
+    chkerr!(ctxt,
+            dpiLob_getChunkSize(handle, &mut size));
+    Ok(size)
+}
+
+pub(crate) fn lob_buffer_size(ctxt: &'static Context, handle: *mut dpiLob, size_in_chars: u64) -> Result<u64> {
+    let mut size = 0;
+    chkerr!(ctxt,
+            dpiLob_getBufferSize(handle, size_in_chars, &mut size));
+    Ok(size)
+}
+
+pub(crate) fn lob_open_resource(ctxt: &'static Context, handle: *mut dpiLob) -> Result<()> {
+    chkerr!(ctxt,
+            dpiLob_openResource(handle));
+    Ok(())
+}
+
+pub(crate) fn lob_close_resource(ctxt: &'static Context, handle: *mut dpiLob) -> Result<()> {
+    chkerr!(ctxt,
+            dpiLob_closeResource(handle));
+    Ok(())
+}
+
+// Checks whether a LOB is temporary in the connection which the LOB
+// belongs to.
+fn lob_is_temporary<L>(ctxt: &'static Context, conn_handle: *mut dpiConn, lob: &L) -> Result<bool> where L: ToSql {
+    if conn_handle.is_null() {
+        return Err(Error::UnknownConnection);
+    }
+    let conn = Connection::from_lob_conn(ctxt, conn_handle);
+    let mut stmt = conn.prepare_internal("begin :1 := dbms_lob.istemporary(:2); end;")?;
+    stmt.execute(&[&OracleType::Int64, lob])?;
+    let is_temp: i64 = stmt.bind_value(1)?;
+    Ok(is_temp != 0)
+}
+
+pub(crate) fn lob_is_resource_open(ctxt: &'static Context, handle: *mut dpiLob) -> Result<bool> {
+    let mut is_open = 0;
+    chkerr!(ctxt,
+            dpiLob_getIsResourceOpen(handle, &mut is_open));
+    Ok(is_open != 0)
+}
+
+// Writes `data` to a BLOB at one-based `offset` piece by piece.
+pub(crate) fn write_blob_pieces(ctxt: &'static Context, handle: *mut dpiLob, offset: u64, data: &[u8]) -> Result<()> {
+    let piece_size = write_size(lob_chunk_size(ctxt, handle)?);
+    let mut offset = offset;
+    for piece in data.chunks(piece_size) {
+        chkerr!(ctxt,
+                dpiLob_writeBytes(handle, offset, piece.as_ptr() as *const i8, piece.len() as u64));
+        offset += piece.len() as u64;
+    }
+    Ok(())
+}
+
+// Writes `data` to a CLOB at one-based character `offset` piece by piece.
+// Each piece ends at a character boundary.
+pub(crate) fn write_clob_pieces(ctxt: &'static Context, handle: *mut dpiLob, offset: u64, data: &str) -> Result<()> {
+    let piece_size = write_size(lob_chunk_size(ctxt, handle)?);
+    let mut offset = offset;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let mut len = cmp::min(piece_size, rest.len());
+        while !rest.is_char_boundary(len) {
+            len -= 1;
+        }
+        if len == 0 {
+            len = rest.chars().next().map(|c| c.len_utf8()).unwrap_or(rest.len());
+        }
+        let (piece, next) = rest.split_at(len);
+        chkerr!(ctxt,
+                dpiLob_writeBytes(handle, offset, piece.as_ptr() as *const i8, piece.len() as u64));
+        offset += ucs2_len(piece);
+        rest = next;
+    }
+    Ok(())
+}
+
+/// Operations common to [Clob][] and [Blob][]
+///
+/// Offsets are zero-based as positions of [Seek][]. They are counted
+/// in characters for `Clob` and in bytes for `Blob`. Data written to
+/// `Clob` must be valid UTF-8.
+///
+/// Writes are split into pieces whose sizes are multiples of the chunk
+/// size of the LOB to fill whole chunks at a time.
+///
+/// [Clob]: struct.Clob.html
+/// [Blob]: struct.Blob.html
+/// [Seek]: https://doc.rust-lang.org/std/io/trait.Seek.html
+pub trait Lob: ToSql {
+    /// Gets the size of the LOB, in characters for `Clob` and in bytes for `Blob`.
+    fn size(&self) -> Result<u64>;
+
+    /// Gets the size of the LOB in bytes. This is exact for `Blob`. For
+    /// `Clob` this is the upper bound of the UTF-8 length computed from
+    /// the number of characters because the exact length is known only
+    /// after reading the whole content.
+    fn byte_size(&self) -> Result<u64>;
+
+    /// Gets the Oracle type of the LOB, `CLOB`, `NCLOB` or `BLOB`.
+    fn oracle_type(&self) -> &OracleType;
+
+    /// Returns true when the LOB is a temporary LOB, which isn't stored
+    /// in a table. This queries the server by `DBMS_LOB.ISTEMPORARY` in the
+    /// connection which the LOB belongs to.
+    ///
+    /// This returns [Error::UnknownConnection][] for LOBs got from object
+    /// attributes.
+    ///
+    /// [Error::UnknownConnection]: enum.Error.html#variant.UnknownConnection
+    fn is_temporary(&self) -> Result<bool>;
+
+    /// Gets the chunk size in bytes of the LOB. Writes are efficient
+    /// when they are made in multiples of this size.
+    fn chunk_size(&self) -> Result<u32>;
+
+    /// Writes `data` at `offset`, overwriting existing data.
+    fn write_at(&mut self, offset: u64, data: &[u8]) -> Result<()>;
+
+    /// Opens the LOB so that indexes and triggers on it are updated only
+    /// once at [close_resource](#tymethod.close_resource) instead of on
+    /// every write. This improves performance of many small writes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oracle::{Clob, Lob, OracleType};
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("insert into documents values (1, empty_clob()) returning doc into :1").unwrap();
+    /// stmt.execute(&[&OracleType::CLOB]).unwrap();
+    /// let mut clob: Clob = stmt.bind_value(1).unwrap();
+    /// clob.open_resource().unwrap();
+    /// for i in 0..1000 {
+    ///     clob.append_bytes(format!("line {}\n", i).as_bytes()).unwrap();
+    /// }
+    /// clob.close_resource().unwrap();
+    /// conn.commit().unwrap();
+    /// ```
+    fn open_resource(&mut self) -> Result<()>;
+
+    /// Closes the LOB opened by [open_resource](#tymethod.open_resource).
+    fn close_resource(&mut self) -> Result<()>;
+
+    /// Returns true when the LOB is opened by [open_resource](#tymethod.open_resource).
+    fn is_resource_open(&self) -> Result<bool>;
+
+    /// Appends `data` to the end of the LOB.
+    fn append_bytes(&mut self, data: &[u8]) -> Result<()> {
+        let size = self.size()?;
+        self.write_at(size, data)
+    }
+}
+
+/// BLOB locator read and written as a stream
+///
+/// This implements [Read][], [Write][] and [Seek][] so that large
+/// BLOBs are processed without loading the whole content into memory.
+/// Positions are zero-based byte offsets.
+///
+/// It can be bound to a statement as it is. The locator is passed to the
+/// server without copying the content through the client.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use std::fs::File;
+/// use oracle::Blob;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select image from photos where id = 1", &[]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// let mut blob: Blob = row.get(0).unwrap();
+/// let mut file = File::create("photo.jpg").unwrap();
+/// io::copy(&mut blob, &mut file).unwrap();
+/// ```
+///
+/// [Read]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [Write]: https://doc.rust-lang.org/std/io/trait.Write.html
+/// [Seek]: https://doc.rust-lang.org/std/io/trait.Seek.html
+pub struct Blob {
+    ctxt: &'static Context,
+    // connection which the LOB belongs to, null when it is unknown
+    conn_handle: *mut dpiConn,
+    pub(crate) handle: *mut dpiLob,
+    pos: u64,
+    size: Option<u64>,
+}
+
+impl Blob {
+    pub(crate) fn new(ctxt: &'static Context, conn_handle: *mut dpiConn, handle: *mut dpiLob) -> Blob {
+        unsafe { dpiLob_addRef(handle) };
+        if !conn_handle.is_null() {
+            unsafe { dpiConn_addRef(conn_handle) };
+        }
+        Blob {
+            ctxt: ctxt,
+            conn_handle: conn_handle,
+            handle: handle,
+            pos: 0,
+            size: None,
+        }
+    }
+
+    /// Returns an iterator over the content in pieces of at most `size` bytes
+    /// from the beginning regardless of the current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: u64) -> BlobChunks {
+        assert!(size != 0, "chunk size must be non-zero");
+        BlobChunks {
+            blob: self,
+            offset: 1,
+            chunk_size: size,
+            size: None,
+            done: false,
+        }
+    }
+
+    /// Replaces the content with all data read from `reader` and returns
+    /// the number of bytes written. Data are written in multiples of the
+    /// chunk size. The position is moved to the end.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use oracle::{Blob, OracleType};
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("insert into photos values (1, empty_blob()) returning image into :1").unwrap();
+    /// stmt.execute(&[&OracleType::BLOB]).unwrap();
+    /// let mut blob: Blob = stmt.bind_value(1).unwrap();
+    /// blob.write_from_reader(File::open("photo.jpg").unwrap()).unwrap();
+    /// conn.commit().unwrap();
+    /// ```
+    pub fn write_from_reader<R>(&mut self, mut reader: R) -> Result<u64> where R: io::Read {
+        let mut buf = vec![0u8; write_size(self.chunk_size()?)];
+        chkerr!(self.ctxt,
+                dpiLob_trim(self.handle, 0));
+        self.size = None;
+        let mut offset = 0;
+        loop {
+            let mut len = 0;
+            while len < buf.len() {
+                match reader.read(&mut buf[len..]) {
+                    Ok(0) => break,
+                    Ok(n) => len += n,
+                    Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+                    Err(err) => return Err(Error::IoError(err)),
+                }
+            }
+            if len == 0 {
+                break;
+            }
+            chkerr!(self.ctxt,
+                    dpiLob_writeBytes(self.handle, offset + 1, buf.as_ptr() as *const i8, len as u64));
+            offset += len as u64;
+        }
+        self.pos = offset;
+        Ok(offset)
+    }
+
+    /// Writes the whole content to `writer` from the beginning regardless
+    /// of the current position and returns the number of bytes written.
+    /// Data are read in multiples of the chunk size.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use oracle::Blob;
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select image from photos where id = 1", &[]).unwrap();
+    /// let blob: Blob = stmt.fetch().unwrap().get(0).unwrap();
+    /// blob.read_to_writer(File::create("photo.jpg").unwrap()).unwrap();
+    /// ```
+    pub fn read_to_writer<W>(&self, mut writer: W) -> Result<u64> where W: io::Write {
+        let read_size = write_size(self.chunk_size()?) as u64;
+        let mut total = 0;
+        for chunk in self.chunks(read_size) {
+            let chunk = chunk?;
+            writer.write_all(&chunk)?;
+            total += chunk.len() as u64;
+        }
+        writer.flush()?;
+        Ok(total)
+    }
+
+    fn cached_size(&mut self) -> Result<u64> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => {
+                let size = self.size()?;
+                self.size = Some(size);
+                Ok(size)
+            },
+        }
+    }
+
+    fn read_internal(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.cached_size()?;
+        if self.pos >= size || buf.is_empty() {
+            return Ok(0);
+        }
+        let amount = cmp::min(buf.len() as u64, size - self.pos);
+        let mut read_len = amount;
+        chkerr!(self.ctxt,
+                dpiLob_readBytes(self.handle, self.pos + 1, amount, buf.as_mut_ptr() as *mut i8, &mut read_len));
+        self.pos += read_len;
+        Ok(read_len as usize)
+    }
+
+    fn write_internal(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.size = None;
+        chkerr!(self.ctxt,
+                dpiLob_writeBytes(self.handle, self.pos + 1, buf.as_ptr() as *const i8, buf.len() as u64));
+        self.pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+}
+
+impl Lob for Blob {
+    fn size(&self) -> Result<u64> {
+        lob_size(self.ctxt, self.handle)
+    }
+
+    fn byte_size(&self) -> Result<u64> {
+        self.size()
+    }
+
+    fn oracle_type(&self) -> &OracleType {
+        &OracleType::BLOB
+    }
+
+    fn chunk_size(&self) -> Result<u32> {
+        lob_chunk_size(self.ctxt, self.handle)
+    }
+
+    fn write_at(&mut self, offset: u64, data: &[u8]) -> Result<()> {
+        self.size = None;
+        write_blob_pieces(self.ctxt, self.handle, offset + 1, data)
+    }
+
+    fn open_resource(&mut self) -> Result<()> {
+        lob_open_resource(self.ctxt, self.handle)
+    }
+
+    fn close_resource(&mut self) -> Result<()> {
+        lob_close_resource(self.ctxt, self.handle)
+    }
+
+    fn is_resource_open(&self) -> Result<bool> {
+        lob_is_resource_open(self.ctxt, self.handle)
+    }
+
+    fn is_temporary(&self) -> Result<bool> {
+        lob_is_temporary(self.ctxt, self.conn_handle, self)
+    }
+}
+
+impl Clone for Blob {
+    fn clone(&self) -> Blob {
+        Blob::new(self.ctxt, self.conn_handle, self.handle)
+    }
+}
+
+impl Drop for Blob {
+    fn drop(&mut self) {
+        let _ = unsafe { dpiLob_release(self.handle) };
+        if !self.conn_handle.is_null() {
+            let _ = unsafe { dpiConn_release(self.conn_handle) };
+        }
+    }
+}
+
+impl io::Read for Blob {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_internal(buf)?)
+    }
+}
+
+impl io::Write for Blob {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.write_internal(buf)?)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for Blob {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let size = match pos {
+            io::SeekFrom::End(_) => self.cached_size()?,
+            _ => 0,
+        };
+        self.pos = seek_position(self.pos, size, pos)?;
+        Ok(self.pos)
+    }
+}
+
+impl FromSql for Blob {
+    fn from_sql(val: &SqlValue) -> Result<Blob> {
+        val.as_blob()
+    }
+}
+
+impl ToSql for Blob {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::BLOB)
+    }
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_blob(self)
+    }
+}
+
+/// An iterator over the content of a BLOB
+///
+/// This is created by [Blob.chunks](struct.Blob.html#method.chunks).
+pub struct BlobChunks<'a> {
+    blob: &'a Blob,
+    offset: u64,
+    chunk_size: u64,
+    size: Option<u64>,
+    done: bool,
+}
+
+impl<'a> BlobChunks<'a> {
+    fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        let size = match self.size {
+            Some(size) => size,
+            None => {
+                let size = self.blob.size()?;
+                self.size = Some(size);
+                size
+            },
+        };
+        if self.offset > size {
+            return Ok(None);
+        }
+        let amount = cmp::min(self.chunk_size, size - self.offset + 1);
+        let mut buf = vec![0u8; amount as usize];
+        let mut read_len = amount;
+        chkerr!(self.blob.ctxt,
+                dpiLob_readBytes(self.blob.handle, self.offset, amount, buf.as_mut_ptr() as *mut i8, &mut read_len));
+        if read_len == 0 {
+            return Ok(None);
+        }
+        buf.truncate(read_len as usize);
+        self.offset += read_len;
+        Ok(Some(buf))
+    }
+}
+
+impl<'a> Iterator for BlobChunks<'a> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        if self.done {
+            return None;
+        }
+        match self.next_chunk() {
+            Ok(Some(chunk)) => Some(Ok(chunk)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// CLOB or NCLOB locator read and written as a stream
+///
+/// This implements [Read][], [Write][] and [Seek][] so that large
+/// CLOBs are processed without loading the whole content into memory.
+/// Data read from and written to it are UTF-8 bytes. Positions are,
+/// however, zero-based offsets in characters because Oracle counts the
+/// length of CLOBs in characters. Note that a character outside of the
+/// basic multilingual plane is counted as two.
+///
+/// It can be bound to a statement as it is. The locator is passed to the
+/// server without copying the content through the client.
+///
+/// UTF-8 sequences split across two `write` calls are joined before
+/// being written. [flush][] fails when an incomplete sequence remains.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::{BufRead, BufReader};
+/// use oracle::Clob;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select doc from documents where id = 1", &[]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// let clob: Clob = row.get(0).unwrap();
+/// for line in BufReader::new(clob).lines() {
+///     println!("{}", line.unwrap());
+/// }
+/// ```
+///
+/// [Read]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [Write]: https://doc.rust-lang.org/std/io/trait.Write.html
+/// [Seek]: https://doc.rust-lang.org/std/io/trait.Seek.html
+/// [flush]: https://doc.rust-lang.org/std/io/trait.Write.html#tymethod.flush
+pub struct Clob {
+    ctxt: &'static Context,
+    // connection which the LOB belongs to, null when it is unknown
+    conn_handle: *mut dpiConn,
+    pub(crate) handle: *mut dpiLob,
+    oratype: OracleType,
+    // character offset of the next data fetched from the server
+    pos: u64,
+    size: Option<u64>,
+    // the rest of a character fetched for a buffer shorter than MAX_BYTES_PER_CHAR
+    read_buf: Vec<u8>,
+    // an incomplete UTF-8 sequence at the end of the last write
+    write_buf: Vec<u8>,
+}
+
+impl Clob {
+    pub(crate) fn new(ctxt: &'static Context, conn_handle: *mut dpiConn, handle: *mut dpiLob, oratype: OracleType) -> Clob {
+        unsafe { dpiLob_addRef(handle) };
+        if !conn_handle.is_null() {
+            unsafe { dpiConn_addRef(conn_handle) };
+        }
+        Clob {
+            ctxt: ctxt,
+            conn_handle: conn_handle,
+            handle: handle,
+            oratype: oratype,
+            pos: 0,
+            size: None,
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+        }
+    }
+
+    fn cached_size(&mut self) -> Result<u64> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => {
+                let size = self.size()?;
+                self.size = Some(size);
+                Ok(size)
+            },
+        }
+    }
+
+    /// Returns an iterator over the content in pieces of at most `size`
+    /// characters from the beginning regardless of the current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: u64) -> ClobChunks {
+        assert!(size != 0, "chunk size must be non-zero");
+        ClobChunks {
+            clob: self,
+            offset: 1,
+            chunk_size: size,
+            size: None,
+            done: false,
+        }
+    }
+
+    /// Returns an adapter implementing [fmt::Write][] to write formatted
+    /// text at the current position by `write!` without building the
+    /// whole string. Text is buffered and written in multiples of the
+    /// chunk size. Call [ClobWriter.finish()][] to get errors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fmt::Write;
+    /// use oracle::{Clob, OracleType};
+    ///
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.prepare("insert into reports values (1, empty_clob()) returning body into :1").unwrap();
+    /// stmt.execute(&[&OracleType::CLOB]).unwrap();
+    /// let mut clob: Clob = stmt.bind_value(1).unwrap();
+    /// let mut writer = clob.text_writer().unwrap();
+    /// for i in 1..1000 {
+    ///     write!(writer, "{} * {} = {}\n", i, i, i * i).unwrap();
+    /// }
+    /// writer.finish().unwrap();
+    /// conn.commit().unwrap();
+    /// ```
+    ///
+    /// [fmt::Write]: https://doc.rust-lang.org/std/fmt/trait.Write.html
+    /// [ClobWriter.finish()]: struct.ClobWriter.html#method.finish
+    pub fn text_writer(&mut self) -> Result<ClobWriter> {
+        let buf_size = write_size(self.chunk_size()?);
+        Ok(ClobWriter {
+            clob: self,
+            buf: String::with_capacity(buf_size),
+            buf_size: buf_size,
+            error: None,
+        })
+    }
+
+    fn read_chars(&mut self, buf: &mut [u8], chars: u64) -> Result<usize> {
+        let size = self.cached_size()?;
+        if self.pos >= size {
+            return Ok(0);
+        }
+        let amount = cmp::min(chars, size - self.pos);
+        let mut read_len = buf.len() as u64;
+        chkerr!(self.ctxt,
+                dpiLob_readBytes(self.handle, self.pos + 1, amount, buf.as_mut_ptr() as *mut i8, &mut read_len));
+        self.pos += ucs2_len(str::from_utf8(&buf[..(read_len as usize)])?);
+        Ok(read_len as usize)
+    }
+
+    fn read_internal(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.read_buf.is_empty() && buf.len() < MAX_BYTES_PER_CHAR {
+            let mut tmp = [0u8; MAX_BYTES_PER_CHAR];
+            let len = self.read_chars(&mut tmp, 1)?;
+            self.read_buf.extend_from_slice(&tmp[..len]);
+        }
+        if !self.read_buf.is_empty() {
+            let len = cmp::min(buf.len(), self.read_buf.len());
+            buf[..len].copy_from_slice(&self.read_buf[..len]);
+            self.read_buf.drain(..len);
+            return Ok(len);
+        }
+        let chars = (buf.len() / MAX_BYTES_PER_CHAR) as u64;
+        self.read_chars(buf, chars)
+    }
+
+    fn write_internal(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.read_buf.clear();
+        self.size = None;
+        self.write_buf.extend_from_slice(buf);
+        let valid_len = match str::from_utf8(&self.write_buf) {
+            Ok(_) => self.write_buf.len(),
+            Err(err) => match err.error_len() {
+                None => err.valid_up_to(),
+                Some(_) => {
+                    let prev_len = self.write_buf.len() - buf.len();
+                    self.write_buf.truncate(prev_len);
+                    return Err(Error::from(err));
+                },
+            },
+        };
+        if valid_len > 0 {
+            chkerr!(self.ctxt,
+                    dpiLob_writeBytes(self.handle, self.pos + 1, self.write_buf.as_ptr() as *const i8, valid_len as u64));
+            self.pos += ucs2_len(unsafe { str::from_utf8_unchecked(&self.write_buf[..valid_len]) });
+            self.write_buf.drain(..valid_len);
+        }
+        Ok(buf.len())
+    }
+}
+
+/// An iterator over the content of a CLOB
+///
+/// This is created by [Clob.chunks](struct.Clob.html#method.chunks).
+pub struct ClobChunks<'a> {
+    clob: &'a Clob,
+    offset: u64,
+    chunk_size: u64,
+    size: Option<u64>,
+    done: bool,
+}
+
+impl<'a> ClobChunks<'a> {
+    fn next_chunk(&mut self) -> Result<Option<String>> {
+        let size = match self.size {
+            Some(size) => size,
+            None => {
+                let size = self.clob.size()?;
+                self.size = Some(size);
+                size
+            },
+        };
+        if self.offset > size {
+            return Ok(None);
+        }
+        let amount = cmp::min(self.chunk_size, size - self.offset + 1);
+        let mut buf = vec![0u8; amount as usize * MAX_BYTES_PER_CHAR];
+        let mut read_len = buf.len() as u64;
+        chkerr!(self.clob.ctxt,
+                dpiLob_readBytes(self.clob.handle, self.offset, amount, buf.as_mut_ptr() as *mut i8, &mut read_len));
+        if read_len == 0 {
+            return Ok(None);
+        }
+        buf.truncate(read_len as usize);
+        let chunk = String::from_utf8(buf).map_err(|err| err.utf8_error())?;
+        self.offset += ucs2_len(&chunk);
+        Ok(Some(chunk))
+    }
+}
+
+impl<'a> Iterator for ClobChunks<'a> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        if self.done {
+            return None;
+        }
+        match self.next_chunk() {
+            Ok(Some(chunk)) => Some(Ok(chunk)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// An adapter to write formatted text to a CLOB
+///
+/// This is created by [Clob.text_writer()](struct.Clob.html#method.text_writer).
+/// Buffered text is written when this is dropped but errors are
+/// ignored then. Use [finish()](#method.finish) to get them.
+pub struct ClobWriter<'a> {
+    clob: &'a mut Clob,
+    buf: String,
+    buf_size: usize,
+    error: Option<Error>,
+}
+
+impl<'a> ClobWriter<'a> {
+    /// Writes buffered text and returns the first error occurred
+    /// while writing.
+    pub fn finish(mut self) -> Result<()> {
+        self.flush_buf();
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn flush_buf(&mut self) {
+        if !self.buf.is_empty() && self.error.is_none() {
+            if let Err(err) = self.clob.write_internal(self.buf.as_bytes()) {
+                self.error = Some(err);
+            }
+        }
+        self.buf.clear();
+    }
+}
+
+impl<'a> fmt::Write for ClobWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+        self.buf.push_str(s);
+        if self.buf.len() >= self.buf_size {
+            self.flush_buf();
+        }
+        match self.error {
+            Some(_) => Err(fmt::Error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Drop for ClobWriter<'a> {
+    fn drop(&mut self) {
+        self.flush_buf();
+    }
+}
+
+impl Lob for Clob {
+    fn size(&self) -> Result<u64> {
+        lob_size(self.ctxt, self.handle)
+    }
+
+    fn byte_size(&self) -> Result<u64> {
+        let size = self.size()?;
+        lob_buffer_size(self.ctxt, self.handle, size)
+    }
+
+    fn oracle_type(&self) -> &OracleType {
+        &self.oratype
+    }
+
+    fn chunk_size(&self) -> Result<u32> {
+        lob_chunk_size(self.ctxt, self.handle)
+    }
+
+    fn write_at(&mut self, offset: u64, data: &[u8]) -> Result<()> {
+        let data = str::from_utf8(data)?;
+        self.size = None;
+        write_clob_pieces(self.ctxt, self.handle, offset + 1, data)
+    }
+
+    fn open_resource(&mut self) -> Result<()> {
+        lob_open_resource(self.ctxt, self.handle)
+    }
+
+    fn close_resource(&mut self) -> Result<()> {
+        lob_close_resource(self.ctxt, self.handle)
+    }
+
+    fn is_resource_open(&self) -> Result<bool> {
+        lob_is_resource_open(self.ctxt, self.handle)
+    }
+
+    fn is_temporary(&self) -> Result<bool> {
+        lob_is_temporary(self.ctxt, self.conn_handle, self)
+    }
+}
+
+impl Clone for Clob {
+    fn clone(&self) -> Clob {
+        Clob::new(self.ctxt, self.conn_handle, self.handle, self.oratype.clone())
+    }
+}
+
+impl Drop for Clob {
+    fn drop(&mut self) {
+        let _ = unsafe { dpiLob_release(self.handle) };
+        if !self.conn_handle.is_null() {
+            let _ = unsafe { dpiConn_release(self.conn_handle) };
+        }
+    }
+}
+
+impl io::Read for Clob {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_internal(buf)?)
+    }
+}
+
+impl io::Write for Clob {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.write_internal(buf)?)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.write_buf.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "incomplete UTF-8 sequence at the end of data"))
+        }
+    }
+}
+
+impl io::Seek for Clob {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let size = match pos {
+            io::SeekFrom::End(_) => self.cached_size()?,
+            _ => 0,
+        };
+        self.pos = seek_position(self.pos, size, pos)?;
+        self.read_buf.clear();
+        self.write_buf.clear();
+        Ok(self.pos)
+    }
+}
+
+impl FromSql for Clob {
+    fn from_sql(val: &SqlValue) -> Result<Clob> {
+        val.as_clob()
+    }
+}
+
+impl ToSql for Clob {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(self.oratype.clone())
+    }
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_clob(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ucs2_length() {
+        assert_eq!(ucs2_len("abc"), 3);
+        assert_eq!(ucs2_len("\u{3042}\u{3044}"), 2);
+        assert_eq!(ucs2_len("\u{1F600}"), 2);
+    }
+
+    #[test]
+    fn write_size_by_chunk_size() {
+        assert_eq!(write_size(8132), 128 * 8132);
+        assert_eq!(write_size(32768), 1024 * 1024);
+        assert_eq!(write_size(2 * 1024 * 1024), 2 * 1024 * 1024);
+        assert_eq!(write_size(0), 1024 * 1024);
+    }
+
+    #[test]
+    fn seek() {
+        assert_eq!(seek_position(5, 10, io::SeekFrom::Start(3)).unwrap(), 3);
+        assert_eq!(seek_position(5, 10, io::SeekFrom::Current(-2)).unwrap(), 3);
+        assert_eq!(seek_position(5, 10, io::SeekFrom::End(-1)).unwrap(), 9);
+        assert!(seek_position(5, 10, io::SeekFrom::Current(-6)).is_err());
+    }
+}
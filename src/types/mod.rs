@@ -30,23 +30,64 @@
 // authors and should not be interpreted as representing official policies, either expressed
 // or implied, of the authors.
 
+use std::borrow::Cow;
+
 use Error;
 use IntervalDS;
 use IntervalYM;
+use OracleNumber;
 use OracleType;
 use Result;
+use Row;
+use RowId;
 use SqlValue;
 use Timestamp;
 
+#[cfg(feature = "bigdecimal")]
+pub mod bigdecimal;
+pub mod bfile;
+pub mod bool_convention;
 #[cfg(feature = "chrono")]
 pub mod chrono;
+#[cfg(feature = "chrono-tz")]
+pub mod chrono_tz;
+pub mod duration;
 pub mod interval_ds;
 pub mod interval_ym;
+pub mod lob;
+pub mod number;
 pub mod object;
 pub mod oracle_type;
+pub mod rowid;
+#[cfg(feature = "rust_decimal")]
+pub mod rust_decimal;
+#[cfg(feature = "sdo_geometry")]
+pub mod sdo_geometry;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "serde_json")]
+pub mod serde_json;
 pub mod timestamp;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "uuid")]
+pub mod uuid;
+pub mod vector;
 pub mod version;
 
+// The maximum length in bytes of strings bound as VARCHAR2 or NVARCHAR2.
+// Longer ones are bound as CLOB or NCLOB. This is also the maximum size of
+// VARCHAR2 and RAW variables. Larger ones are created as LONG and LONG RAW.
+pub(crate) const MAX_STRING_SIZE: usize = 32767;
+
+fn string_oratype(len: usize) -> OracleType {
+    if len <= MAX_STRING_SIZE {
+        OracleType::NVarchar2(len as u32)
+    } else {
+        OracleType::NCLOB
+    }
+}
+
 /// A trait to convert Oracle values to rust values.
 ///
 /// Values in Oracle are converted to Rust type as possible as it can.
@@ -56,14 +97,98 @@ pub mod version;
 /// | --- | --- |
 /// | CHAR, NCHAR, VARCHAR2, NVARCHAR2 | String |
 /// | ″ | i8, i16, i32, i64, u8, u16, u32, u64 by `String.parse()` |
+/// | NUMBER | [OracleNumber][] (without loss of precision) |
+/// | NUMBER, BINARY_FLOAT, BINARY_DOUBLE | f32, f64 (`~` and `-~` of NUMBER as infinity) |
+/// | BINARY_FLOAT, BINARY_DOUBLE | integer types (NaN and infinity are errors) |
+/// | CLOB, NCLOB | String (whole content), [Clob][] (stream) |
+/// | BLOB | Vec\<u8> (whole content), [Blob][] (stream) |
+/// | BFILE | [Bfile][], Vec\<u8> (whole content) |
+/// | ROWID, UROWID | [RowId][], String |
+/// | BOOLEAN | bool |
+/// | NUMBER, CHAR, NCHAR, VARCHAR2, NVARCHAR2 | bool by [BoolConvention][] |
+/// | CLOB, VARCHAR2 in VECTOR text form (`FROM_VECTOR()`) | Vec\<f32>, Vec\<f64>, Vec\<i8> |
+/// | TIMESTAMP WITH TIME ZONE | chrono::DateTime\<chrono_tz::Tz> in UTC (`chrono-tz` feature) |
+/// | ″ (by `TO_CHAR` with `TZR`) | chrono::DateTime\<chrono_tz::Tz> in the region (`chrono-tz` feature) |
+/// | ″ (by `EXTRACT(TIMEZONE_REGION FROM ...)`) | chrono_tz::Tz (`chrono-tz` feature) |
 /// | ... | ... |
 ///
 /// This conversion is used also to get values from output parameters.
 ///
+/// [Bfile]: struct.Bfile.html
+/// [Blob]: struct.Blob.html
+/// [BoolConvention]: struct.BoolConvention.html
+/// [Clob]: struct.Clob.html
+/// [OracleNumber]: struct.OracleNumber.html
+/// [RowId]: struct.RowId.html
+///
 pub trait FromSql {
     fn from_sql(val: &SqlValue) -> Result<Self> where Self: Sized;
 }
 
+/// A trait to convert Oracle values to rust values which may borrow them.
+///
+/// This is implemented for all types implementing [FromSql][], `&str` and
+/// `&[u8]`. [Row.get][] uses this to get `&str` and `&[u8]` referring to the
+/// fetch buffer without allocating memory per column per row. They are
+/// available while the row is borrowed, which prevents the next fetch from
+/// overwriting the buffer.
+///
+/// `&str` is always checked strictly. Invalid UTF-8 byte sequences are
+/// [Error::InvalidUtf8][] regardless of [Connection.set_strict_utf8][].
+/// Use `String` to replace them with U+FFFD.
+///
+/// | Oracle Type | Rust Type |
+/// | --- | --- |
+/// | CHAR, NCHAR, VARCHAR2, NVARCHAR2, NUMBER | &str, Option\<&str> |
+/// | RAW, LONG RAW | &[u8], Option\<&[u8]> |
+///
+/// [FromSql]: trait.FromSql.html
+/// [Row.get]: struct.Row.html#method.get
+/// [Error::InvalidUtf8]: enum.Error.html#variant.InvalidUtf8
+/// [Connection.set_strict_utf8]: struct.Connection.html#method.set_strict_utf8
+pub trait FromSqlRef<'a>: Sized {
+    /// Converts the `pos`-th column value of `row`.
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<Self>;
+}
+
+impl<'a, T> FromSqlRef<'a> for T where T: FromSql {
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<T> {
+        T::from_sql(&row.columns()[pos])
+    }
+}
+
+impl<'a> FromSqlRef<'a> for &'a str {
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<&'a str> {
+        row.columns()[pos].as_str_ref()
+    }
+}
+
+impl<'a> FromSqlRef<'a> for &'a [u8] {
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<&'a [u8]> {
+        row.columns()[pos].as_bytes_ref()
+    }
+}
+
+impl<'a> FromSqlRef<'a> for Option<&'a str> {
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<Option<&'a str>> {
+        match row.columns()[pos].as_str_ref() {
+            Ok(v) => Ok(Some(v)),
+            Err(Error::NullValue) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<'a> FromSqlRef<'a> for Option<&'a [u8]> {
+    fn from_sql_ref(row: &'a Row, pos: usize) -> Result<Option<&'a [u8]>> {
+        match row.columns()[pos].as_bytes_ref() {
+            Ok(v) => Ok(Some(v)),
+            Err(Error::NullValue) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
 pub trait ToSqlNull {
     fn oratype_for_null() -> Result<OracleType>;
 }
@@ -74,12 +199,19 @@ pub trait ToSqlNull {
 ///
 /// | Rust Type | Oracle Type |
 /// | --- | --- |
-/// | str, String | NVARCHAR2(length of the rust value) |
+/// | str, String, Cow\<str>, Box\<str> | NVARCHAR2(length of the rust value) or NCLOB when it is longer than 32767 bytes |
 /// | i8, i16, i32, i64, u8, u16, u32, u64, f32, f64 | NUMBER |
-/// | Vec\<u8> | RAW(length of the rust value) |
+/// | f32, f64 (NaN and infinity or by [Connection.set_binary_float_bind][]) | BINARY_FLOAT, BINARY_DOUBLE |
+/// | [OracleNumber][] | NUMBER |
+/// | [u8], Vec\<u8>, Cow\<[u8]>, Box\<[u8]> | RAW(length of the rust value) or LONG RAW when it is longer than 32767 bytes |
 /// | [Timestamp][] | TIMESTAMP(9) WITH TIME ZONE |
 /// | [IntervalDS][] | INTERVAL DAY(9) TO SECOND(9) |
 /// | [IntervalYM][] | INTERVAL YEAR(9) TO MONTH |
+/// | std::time::Duration, [SignedDuration][] | INTERVAL DAY(9) TO SECOND(9) |
+/// | [RowId][] | VARCHAR2(length of the rowid) |
+/// | bool | BOOLEAN |
+/// | Vec\<f32>, Vec\<f64>, Vec\<i8> | VARCHAR2 or CLOB in VECTOR text form |
+/// | [Clob][], [Blob][], [Bfile][] | CLOB, BLOB, BFILE (the locator itself) |
 ///
 /// When `chrono` feature is enabled, the following conversions are added.
 ///
@@ -91,17 +223,67 @@ pub trait ToSqlNull {
 /// | [chrono::naive::NaiveDateTime][] | TIMESTAMP(9) |
 /// | [chrono::Duration][] | INTERVAL DAY(9) TO SECOND(9) |
 ///
+/// When `chrono-tz` feature is enabled, `chrono::DateTime<chrono_tz::Tz>` is
+/// bound as other `chrono::DateTime` types. It is fetched with the region name
+/// from strings formatted by `TO_CHAR(value, 'YYYY-MM-DD HH24:MI:SS.FF9 TZR')`.
+///
+/// When `rust_decimal` feature is enabled, the following conversion is added.
+///
+/// | Rust Type | Oracle Type |
+/// | --- | --- |
+/// | [rust_decimal::Decimal][] | NUMBER |
+///
+/// When `bigdecimal` feature is enabled, the following conversion is added.
+///
+/// | Rust Type | Oracle Type |
+/// | --- | --- |
+/// | [bigdecimal::BigDecimal][] | NUMBER |
+///
+/// When `serde_json` feature is enabled, the following conversion is added.
+///
+/// | Rust Type | Oracle Type |
+/// | --- | --- |
+/// | [serde_json::Value][] | NVARCHAR2(length of JSON text) or CLOB |
+///
+/// When `uuid` feature is enabled, the following conversion is added.
+///
+/// | Rust Type | Oracle Type |
+/// | --- | --- |
+/// | [uuid::Uuid][] | RAW(16) |
+///
+/// [Bfile]: struct.Bfile.html
+/// [Blob]: struct.Blob.html
+/// [Clob]: struct.Clob.html
+/// [Connection.set_binary_float_bind]: struct.Connection.html#method.set_binary_float_bind
 /// [Timestamp]: struct.Timestamp.html
 /// [IntervalDS]: struct.IntervalDS.html
 /// [IntervalYM]: struct.IntervalYM.html
+/// [OracleNumber]: struct.OracleNumber.html
+/// [RowId]: struct.RowId.html
+/// [SignedDuration]: struct.SignedDuration.html
 /// [chrono::Date]: https://docs.rs/chrono/0.4/chrono/struct.Date.html
 /// [chrono::DateTime]: https://docs.rs/chrono/0.4/chrono/struct.DateTime.html
 /// [chrono::naive::NaiveDate]: https://docs.rs/chrono/0.4/chrono/naive/struct.NaiveDate.html
 /// [chrono::naive::NaiveDateTime]: https://docs.rs/chrono/0.4/chrono/naive/struct.NaiveDateTime.html
 /// [chrono::Duration]: https://docs.rs/chrono/0.4/chrono/struct.Duration.html
+/// [rust_decimal::Decimal]: https://docs.rs/rust_decimal/1/rust_decimal/struct.Decimal.html
+/// [bigdecimal::BigDecimal]: https://docs.rs/bigdecimal/0.4/bigdecimal/struct.BigDecimal.html
+/// [serde_json::Value]: https://docs.rs/serde_json/1/serde_json/enum.Value.html
+/// [uuid::Uuid]: https://docs.rs/uuid/1/uuid/struct.Uuid.html
 ///
 pub trait ToSql {
     fn oratype(&self) -> Result<OracleType>;
+
+    /// Returns the Oracle type used instead of `oratype()` when
+    /// [Connection.set_binary_float_bind(true)][] is called.
+    /// `f32` and `f64` return BINARY_FLOAT and BINARY_DOUBLE.
+    /// Others return `oratype()` by default.
+    ///
+    /// [Connection.set_binary_float_bind(true)]: struct.Connection.html#method.set_binary_float_bind
+    fn binary_float_oratype(&self) -> Result<OracleType> {
+        self.oratype()
+    }
+
     fn to_sql(&self, val: &mut SqlValue) -> Result<()>;
 }
 
@@ -152,60 +334,108 @@ impl_from_and_to_sql!(u8, as_u8, set_u8, OracleType::Number(0,0));
 impl_from_and_to_sql!(u16, as_u16, set_u16, OracleType::Number(0,0));
 impl_from_and_to_sql!(u32, as_u32, set_u32, OracleType::Number(0,0));
 impl_from_and_to_sql!(u64, as_u64, set_u64, OracleType::Number(0,0));
-impl_from_and_to_sql!(f64, as_f64, set_f64, OracleType::Number(0,0));
-impl_from_and_to_sql!(f32, as_f32, set_f32, OracleType::Number(0,0));
+impl_from_sql!(f64, as_f64);
+impl_from_sql!(f32, as_f32);
 impl_from_and_to_sql!(bool, as_bool, set_bool, OracleType::Boolean);
 impl_from_sql!(String, as_string);
 impl_from_sql!(Vec<u8>, as_bytes);
 impl_from_and_to_sql!(Timestamp, as_timestamp, Timestamp, set_timestamp, OracleType::TimestampTZ(9));
 impl_from_and_to_sql!(IntervalDS, as_interval_ds, IntervalDS, set_interval_ds, OracleType::IntervalDS(9,9));
 impl_from_and_to_sql!(IntervalYM, as_interval_ym, IntervalYM, set_interval_ym, OracleType::IntervalYM(9));
+impl_from_sql!(RowId, as_rowid);
+impl_from_and_to_sql!(OracleNumber, as_oracle_number, set_oracle_number, OracleType::Number(0,0));
 
-impl ToSqlNull for String {
-    fn oratype_for_null() -> Result<OracleType> {
-        Ok(OracleType::NVarchar2(0))
-    }
+// NaN and infinity are bound as BINARY_DOUBLE and BINARY_FLOAT because NUMBER
+// cannot hold NaN and ODPI-C cannot set infinity to NUMBER. Oracle converts
+// infinity to `~` or `-~` when it is stored in a NUMBER column.
+macro_rules! impl_to_sql_for_float {
+    ($type:ty, $set_func:ident, $binary_type:expr) => {
+        impl ToSqlNull for $type {
+            fn oratype_for_null() -> Result<OracleType> {
+                Ok(OracleType::Number(0,0))
+            }
+        }
+        impl ToSql for $type {
+            fn oratype(&self) -> Result<OracleType> {
+                if self.is_finite() {
+                    Ok(OracleType::Number(0,0))
+                } else {
+                    Ok($binary_type)
+                }
+            }
+            fn binary_float_oratype(&self) -> Result<OracleType> {
+                Ok($binary_type)
+            }
+            fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+                val.$set_func(self)
+            }
+        }
+    };
 }
 
-impl ToSql for String {
-    fn oratype(&self) -> Result<OracleType> {
-        Ok(OracleType::NVarchar2(self.len() as u32))
-    }
-    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
-        val.set_string(self)
-    }
-}
+impl_to_sql_for_float!(f64, set_f64, OracleType::BinaryDouble);
+impl_to_sql_for_float!(f32, set_f32, OracleType::BinaryFloat);
 
-impl ToSqlNull for Vec<u8> {
+impl ToSqlNull for RowId {
     fn oratype_for_null() -> Result<OracleType> {
-        Ok(OracleType::Raw(0))
+        Ok(OracleType::Varchar2(0))
     }
 }
 
-impl ToSql for Vec<u8> {
+impl ToSql for RowId {
     fn oratype(&self) -> Result<OracleType> {
-        Ok(OracleType::Raw(self.len() as u32))
+        Ok(OracleType::Varchar2(self.as_str().len() as u32))
     }
     fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
-        val.set_bytes(self)
+        val.set_rowid(self)
     }
 }
 
-impl<'a> ToSqlNull for &'a str {
-    fn oratype_for_null() -> Result<OracleType> {
-        Ok(OracleType::NVarchar2(0))
-    }
+macro_rules! impl_to_sql_for_str {
+    ([$($gen:tt)*] $type:ty) => {
+        impl<$($gen)*> ToSqlNull for $type {
+            fn oratype_for_null() -> Result<OracleType> {
+                Ok(OracleType::NVarchar2(0))
+            }
+        }
+        impl<$($gen)*> ToSql for $type {
+            fn oratype(&self) -> Result<OracleType> {
+                Ok(string_oratype(self.len()))
+            }
+            fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+                val.set_string(self)
+            }
+        }
+    };
 }
 
-impl<'a> ToSql for &'a str {
-    fn oratype(&self) -> Result<OracleType> {
-        Ok(OracleType::NVarchar2(self.len() as u32))
-    }
-    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
-        val.set_string(self)
-    }
+macro_rules! impl_to_sql_for_bytes {
+    ([$($gen:tt)*] $type:ty) => {
+        impl<$($gen)*> ToSqlNull for $type {
+            fn oratype_for_null() -> Result<OracleType> {
+                Ok(OracleType::Raw(0))
+            }
+        }
+        impl<$($gen)*> ToSql for $type {
+            fn oratype(&self) -> Result<OracleType> {
+                Ok(OracleType::Raw(self.len() as u32))
+            }
+            fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+                val.set_bytes(self)
+            }
+        }
+    };
 }
 
+impl_to_sql_for_str!([] String);
+impl_to_sql_for_str!(['a] &'a str);
+impl_to_sql_for_str!(['a] Cow<'a, str>);
+impl_to_sql_for_str!([] Box<str>);
+impl_to_sql_for_bytes!([] Vec<u8>);
+impl_to_sql_for_bytes!(['a] &'a [u8]);
+impl_to_sql_for_bytes!(['a] Cow<'a, [u8]>);
+impl_to_sql_for_bytes!([] Box<[u8]>);
+
 impl<T: FromSql> FromSql for Option<T> {
     fn from_sql(val: &SqlValue) -> Result<Option<T>> {
         match <T>::from_sql(val) {
@@ -223,6 +453,12 @@ impl<T: ToSql + ToSqlNull> ToSql for Option<T> {
             None => <T>::oratype_for_null(),
         }
     }
+    fn binary_float_oratype(&self) -> Result<OracleType> {
+        match *self {
+            Some(ref t) => t.binary_float_oratype(),
+            None => <T>::oratype_for_null(),
+        }
+    }
     fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
         match *self {
             Some(ref t) => t.to_sql(val),
@@ -0,0 +1,555 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cmp;
+use std::fmt;
+use std::ops;
+use std::str;
+
+use util::Scanner;
+use ParseOracleTypeError;
+
+// The maximum number of significant digits of Oracle NUMBER
+const MAX_DIGITS: usize = 40;
+
+/// Oracle-specific NUMBER data type
+///
+/// This holds a decimal number exactly without rounding through `i64`
+/// or `f64`. Use this to fetch NUMBER columns whose precision is larger
+/// than 18 or which have fractional parts. Structs generated by
+/// [codegen][] use this for such columns. When `serde` feature is
+/// enabled, this is serialized as a decimal string.
+///
+/// Arithmetic operators `+`, `-`, `*`, `/` and unary `-` are available.
+/// Results are rounded to 40 significant digits, which is the maximum
+/// precision of Oracle NUMBER. Division by zero panics like integer types.
+///
+/// # Examples
+///
+/// ```
+/// use oracle::OracleNumber;
+///
+/// let a: OracleNumber = "12345678901234567890.123456789".parse().unwrap();
+/// let b: OracleNumber = "0.000000001".parse().unwrap();
+/// assert_eq!((&a + &b).to_string(), "12345678901234567890.12345679");
+/// assert_eq!((&a * &b).to_string(), "12345678901.234567890123456789");
+/// ```
+///
+/// ```no_run
+/// use oracle::OracleNumber;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select 1234567890123456789012345678901234567890 from dual", &[]).unwrap();
+/// let num: OracleNumber = stmt.fetch().unwrap().get(0).unwrap();
+/// assert_eq!(num.to_string(), "1234567890123456789012345678901234567890");
+/// ```
+///
+/// [codegen]: codegen/index.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OracleNumber {
+    // true when the number is negative. false for zero.
+    negative: bool,
+    // decimal digits of the coefficient without leading and trailing
+    // zeros, most significant first. This is empty for zero.
+    digits: Vec<u8>,
+    // The number is `coefficient * 10^exponent`.
+    exponent: i32,
+}
+
+impl OracleNumber {
+    /// Creates zero.
+    pub fn zero() -> OracleNumber {
+        OracleNumber {
+            negative: false,
+            digits: Vec::new(),
+            exponent: 0,
+        }
+    }
+
+    /// Returns `true` when the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Returns `true` when the number is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the absolute value.
+    pub fn abs(&self) -> OracleNumber {
+        OracleNumber {
+            negative: false,
+            digits: self.digits.clone(),
+            exponent: self.exponent,
+        }
+    }
+
+    // Returns a string such as `-12345E-3`, which never has more digits
+    // than MAX_DIGITS even when the number is very large or very small.
+    pub(crate) fn to_exp_string(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let mut s = String::with_capacity(self.digits.len() + 7);
+        if self.negative {
+            s.push('-');
+        }
+        s.extend(self.digits.iter().map(|d| (b'0' + *d) as char));
+        if self.exponent != 0 {
+            s.push_str(&format!("E{}", self.exponent));
+        }
+        s
+    }
+
+    // Strips leading and trailing zeros and rounds to MAX_DIGITS
+    // significant digits, rounding half away from zero.
+    fn new(negative: bool, mut digits: Vec<u8>, mut exponent: i32) -> OracleNumber {
+        let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+        digits.drain(..leading_zeros);
+        if digits.len() > MAX_DIGITS {
+            let round_up = digits[MAX_DIGITS] >= 5;
+            exponent += (digits.len() - MAX_DIGITS) as i32;
+            digits.truncate(MAX_DIGITS);
+            if round_up {
+                let mut idx = MAX_DIGITS;
+                loop {
+                    if idx == 0 {
+                        digits.insert(0, 1);
+                        break;
+                    }
+                    idx -= 1;
+                    if digits[idx] == 9 {
+                        digits[idx] = 0;
+                    } else {
+                        digits[idx] += 1;
+                        break;
+                    }
+                }
+            }
+        }
+        while let Some(&0) = digits.last() {
+            digits.pop();
+            exponent += 1;
+        }
+        if digits.is_empty() {
+            OracleNumber::zero()
+        } else {
+            OracleNumber {
+                negative: negative,
+                digits: digits,
+                exponent: exponent,
+            }
+        }
+    }
+
+    // Returns the coefficient padded with trailing zeros so that its
+    // exponent becomes `exponent`, which must not be greater than self's.
+    fn digits_with_exponent(&self, exponent: i32) -> Vec<u8> {
+        let mut digits = self.digits.clone();
+        digits.resize(self.digits.len() + (self.exponent - exponent) as usize, 0);
+        digits
+    }
+
+    fn cmp_abs(&self, other: &OracleNumber) -> cmp::Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => cmp::Ordering::Equal,
+            (true, false) => cmp::Ordering::Less,
+            (false, true) => cmp::Ordering::Greater,
+            (false, false) => {
+                let adjusted_exp = self.digits.len() as i32 + self.exponent;
+                let other_adjusted_exp = other.digits.len() as i32 + other.exponent;
+                adjusted_exp.cmp(&other_adjusted_exp)
+                    .then_with(|| self.digits.cmp(&other.digits))
+            },
+        }
+    }
+
+    fn add_signed(&self, other: &OracleNumber, other_negative: bool) -> OracleNumber {
+        if other.is_zero() {
+            return self.clone();
+        }
+        if self.is_zero() {
+            return OracleNumber::new(other_negative, other.digits.clone(), other.exponent);
+        }
+        let exponent = cmp::min(self.exponent, other.exponent);
+        let lhs = self.digits_with_exponent(exponent);
+        let rhs = other.digits_with_exponent(exponent);
+        if self.negative == other_negative {
+            OracleNumber::new(self.negative, add_digits(&lhs, &rhs), exponent)
+        } else {
+            match self.cmp_abs(other) {
+                cmp::Ordering::Equal => OracleNumber::zero(),
+                cmp::Ordering::Greater => OracleNumber::new(self.negative, sub_digits(&lhs, &rhs), exponent),
+                cmp::Ordering::Less => OracleNumber::new(other_negative, sub_digits(&rhs, &lhs), exponent),
+            }
+        }
+    }
+
+    fn mul(&self, other: &OracleNumber) -> OracleNumber {
+        if self.is_zero() || other.is_zero() {
+            return OracleNumber::zero();
+        }
+        let mut result = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, a) in self.digits.iter().enumerate().rev() {
+            for (j, b) in other.digits.iter().enumerate().rev() {
+                result[i + j + 1] += (*a as u32) * (*b as u32);
+            }
+        }
+        for i in (1..result.len()).rev() {
+            result[i - 1] += result[i] / 10;
+            result[i] %= 10;
+        }
+        let digits = result.into_iter().map(|d| d as u8).collect();
+        OracleNumber::new(self.negative != other.negative, digits, self.exponent + other.exponent)
+    }
+
+    fn div(&self, other: &OracleNumber) -> OracleNumber {
+        if other.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+        if self.is_zero() {
+            return OracleNumber::zero();
+        }
+        // Pad the dividend so that the quotient has one more digit than
+        // MAX_DIGITS, which is used to round the result.
+        let padding = (MAX_DIGITS + 1 + other.digits.len()).saturating_sub(self.digits.len());
+        let mut dividend = self.digits.clone();
+        dividend.resize(self.digits.len() + padding, 0);
+        let mut quotient = Vec::with_capacity(dividend.len());
+        let mut remainder: Vec<u8> = Vec::new();
+        for d in dividend {
+            remainder.push(d);
+            let mut q = 0;
+            while cmp_digits(&remainder, &other.digits) != cmp::Ordering::Less {
+                remainder = sub_digits(&remainder, &other.digits);
+                q += 1;
+            }
+            quotient.push(q);
+        }
+        OracleNumber::new(self.negative != other.negative, quotient,
+                          self.exponent - other.exponent - padding as i32)
+    }
+}
+
+// Compares unsigned integers represented by decimal digits.
+fn cmp_digits(lhs: &[u8], rhs: &[u8]) -> cmp::Ordering {
+    let lhs = &lhs[lhs.iter().take_while(|d| **d == 0).count()..];
+    let rhs = &rhs[rhs.iter().take_while(|d| **d == 0).count()..];
+    lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs))
+}
+
+// Adds unsigned integers represented by decimal digits.
+fn add_digits(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    let len = cmp::max(lhs.len(), rhs.len()) + 1;
+    let mut result = vec![0u8; len];
+    let mut carry = 0;
+    for i in 0..len {
+        let a = if i < lhs.len() { lhs[lhs.len() - 1 - i] } else { 0 };
+        let b = if i < rhs.len() { rhs[rhs.len() - 1 - i] } else { 0 };
+        let sum = a + b + carry;
+        result[len - 1 - i] = sum % 10;
+        carry = sum / 10;
+    }
+    result
+}
+
+// Subtracts unsigned integers represented by decimal digits.
+// `lhs` must not be less than `rhs`.
+fn sub_digits(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    let mut result = lhs.to_vec();
+    let mut borrow = 0;
+    for i in 0..lhs.len() {
+        let idx = lhs.len() - 1 - i;
+        let b = if i < rhs.len() { rhs[rhs.len() - 1 - i] } else { 0 } + borrow;
+        if result[idx] >= b {
+            result[idx] -= b;
+            borrow = 0;
+        } else {
+            result[idx] = result[idx] + 10 - b;
+            borrow = 1;
+        }
+    }
+    result
+}
+
+impl Default for OracleNumber {
+    fn default() -> OracleNumber {
+        OracleNumber::zero()
+    }
+}
+
+impl cmp::PartialOrd for OracleNumber {
+    fn partial_cmp(&self, other: &OracleNumber) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::Ord for OracleNumber {
+    fn cmp(&self, other: &OracleNumber) -> cmp::Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => cmp::Ordering::Greater,
+            (true, false) => cmp::Ordering::Less,
+            (false, false) => self.cmp_abs(other),
+            (true, true) => other.cmp_abs(self),
+        }
+    }
+}
+
+impl fmt::Display for OracleNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut s = String::with_capacity(self.digits.len() + 3);
+        if self.negative {
+            s.push('-');
+        }
+        let digits: String = self.digits.iter().map(|d| (b'0' + *d) as char).collect();
+        if self.exponent >= 0 {
+            s.push_str(&digits);
+            for _ in 0..self.exponent {
+                s.push('0');
+            }
+        } else {
+            let int_len = self.digits.len() as i32 + self.exponent;
+            if int_len > 0 {
+                s.push_str(&digits[..int_len as usize]);
+                s.push('.');
+                s.push_str(&digits[int_len as usize..]);
+            } else {
+                s.push_str("0.");
+                for _ in 0..-int_len {
+                    s.push('0');
+                }
+                s.push_str(&digits);
+            }
+        }
+        f.pad(&s)
+    }
+}
+
+impl str::FromStr for OracleNumber {
+    type Err = ParseOracleTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseOracleTypeError::new("OracleNumber");
+        let mut digits = Vec::new();
+        let mut exponent = 0i32;
+        let mut has_digits = false;
+        let mut s = Scanner::new(s);
+        let negative = match s.char() {
+            Some('+') => {
+                s.next();
+                false
+            },
+            Some('-') => {
+                s.next();
+                true
+            },
+            _ => false,
+        };
+        while let Some(d) = s.char().and_then(|c| c.to_digit(10)) {
+            digits.push(d as u8);
+            has_digits = true;
+            s.next();
+        }
+        if let Some('.') = s.char() {
+            s.next();
+            while let Some(d) = s.char().and_then(|c| c.to_digit(10)) {
+                digits.push(d as u8);
+                exponent -= 1;
+                has_digits = true;
+                s.next();
+            }
+        }
+        if !has_digits {
+            return Err(err());
+        }
+        match s.char() {
+            Some('e') | Some('E') => {
+                s.next();
+                let exp_negative = match s.char() {
+                    Some('+') => {
+                        s.next();
+                        false
+                    },
+                    Some('-') => {
+                        s.next();
+                        true
+                    },
+                    _ => false,
+                };
+                let exp = s.read_digits().ok_or(err())?;
+                if exp > 1000 {
+                    return Err(err());
+                }
+                exponent += if exp_negative { -(exp as i32) } else { exp as i32 };
+            },
+            _ => (),
+        }
+        if s.char().is_some() {
+            return Err(err());
+        }
+        Ok(OracleNumber::new(negative, digits, exponent))
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for OracleNumber {
+                fn from(val: $type) -> OracleNumber {
+                    val.to_string().parse().unwrap()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, |$lhs:ident, $rhs:ident| $expr:expr) => {
+        impl<'a, 'b> ops::$trait<&'b OracleNumber> for &'a OracleNumber {
+            type Output = OracleNumber;
+            fn $method(self, rhs: &'b OracleNumber) -> OracleNumber {
+                let ($lhs, $rhs) = (self, rhs);
+                $expr
+            }
+        }
+
+        impl ops::$trait<OracleNumber> for OracleNumber {
+            type Output = OracleNumber;
+            fn $method(self, rhs: OracleNumber) -> OracleNumber {
+                ops::$trait::$method(&self, &rhs)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, |lhs, rhs| lhs.add_signed(rhs, rhs.negative));
+impl_binary_op!(Sub, sub, |lhs, rhs| lhs.add_signed(rhs, !rhs.negative && !rhs.is_zero()));
+impl_binary_op!(Mul, mul, |lhs, rhs| lhs.mul(rhs));
+impl_binary_op!(Div, div, |lhs, rhs| lhs.div(rhs));
+
+impl<'a> ops::Neg for &'a OracleNumber {
+    type Output = OracleNumber;
+    fn neg(self) -> OracleNumber {
+        OracleNumber {
+            negative: !self.negative && !self.is_zero(),
+            digits: self.digits.clone(),
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl ops::Neg for OracleNumber {
+    type Output = OracleNumber;
+    fn neg(self) -> OracleNumber {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(s: &str) -> OracleNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(num("0").to_string(), "0");
+        assert_eq!(num("-0.000").to_string(), "0");
+        assert_eq!(num("123").to_string(), "123");
+        assert_eq!(num("+123.4500").to_string(), "123.45");
+        assert_eq!(num("-.5").to_string(), "-0.5");
+        assert_eq!(num("0.00123").to_string(), "0.00123");
+        assert_eq!(num("1.5e3").to_string(), "1500");
+        assert_eq!(num("15E-3").to_string(), "0.015");
+        assert_eq!(num("12345678901234567890123456789012345678901234").to_string(),
+                   "12345678901234567890123456789012345678900000");
+        assert_eq!(num("0.99999999999999999999999999999999999999999").to_string(), "1");
+        assert_eq!(num("-1.5e125").to_exp_string(), "-15E124");
+        assert_eq!(num("0.00123").to_exp_string(), "123E-5");
+        assert_eq!(num("123").to_exp_string(), "123");
+        assert!("".parse::<OracleNumber>().is_err());
+        assert!("-".parse::<OracleNumber>().is_err());
+        assert!(".".parse::<OracleNumber>().is_err());
+        assert!("1e".parse::<OracleNumber>().is_err());
+        assert!("1.2.3".parse::<OracleNumber>().is_err());
+        assert!("abc".parse::<OracleNumber>().is_err());
+        assert!("1e1001".parse::<OracleNumber>().is_err());
+        assert!("1e99999999999999999999".parse::<OracleNumber>().is_err());
+        assert!("1e-99999999999999999999".parse::<OracleNumber>().is_err());
+    }
+
+    #[test]
+    fn from_int() {
+        assert_eq!(OracleNumber::from(0i32).to_string(), "0");
+        assert_eq!(OracleNumber::from(i64::min_value()).to_string(), "-9223372036854775808");
+        assert_eq!(OracleNumber::from(u64::max_value()).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!((num("1.5") + num("2.25")).to_string(), "3.75");
+        assert_eq!((num("1.5") + num("-2.25")).to_string(), "-0.75");
+        assert_eq!((num("-1.5") - num("-1.5")).to_string(), "0");
+        assert_eq!((num("0") - num("1.5")).to_string(), "-1.5");
+        assert_eq!((num("99999999999999999999") + num("1")).to_string(), "100000000000000000000");
+        assert_eq!((num("1e30") + num("1e-30")).to_string(), "1000000000000000000000000000000");
+        assert_eq!((num("-1.5") * num("2")).to_string(), "-3");
+        assert_eq!((num("12345678901234567890") * num("98765432109876543210")).to_string(),
+                   "1219326311370217952237463801111263526900");
+        assert_eq!((num("1") / num("4")).to_string(), "0.25");
+        assert_eq!((num("-10") / num("4")).to_string(), "-2.5");
+        assert_eq!((num("1") / num("3")).to_string(), "0.3333333333333333333333333333333333333333");
+        assert_eq!((num("2") / num("3")).to_string(), "0.6666666666666666666666666666666666666667");
+        assert_eq!((-num("1.5")).to_string(), "-1.5");
+        assert_eq!((-num("0")).to_string(), "0");
+        assert_eq!(num("-1.5").abs().to_string(), "1.5");
+    }
+
+    #[test]
+    fn compare() {
+        assert!(num("1") > num("0.999"));
+        assert!(num("-1") < num("-0.999"));
+        assert!(num("-1") < num("0"));
+        assert!(num("0") < num("0.001"));
+        assert!(num("100") > num("99.99"));
+        assert!(num("1.01") > num("1.001"));
+        assert_eq!(num("1.50"), num("1.5"));
+    }
+}
@@ -32,6 +32,7 @@
 
 use std::cmp;
 use std::fmt;
+use std::marker::PhantomData;
 use std::ptr;
 use std::rc::Rc;
 
@@ -82,6 +83,22 @@ impl Collection {
         &self.objtype
     }
 
+    /// Returns a deep copy of the collection.
+    ///
+    /// [Clone][] shares the underlying instance, which may be owned by
+    /// a fetched row. Use this to modify a fetched collection without
+    /// affecting the row.
+    ///
+    /// [Clone]: https://doc.rust-lang.org/std/clone/trait.Clone.html
+    pub fn clone_value(&self) -> Result<Collection> {
+        let mut handle = ptr::null_mut();
+        chkerr!(self.ctxt,
+                dpiObject_copy(self.handle, &mut handle));
+        Ok(Collection::new(self.ctxt, handle, self.objtype.clone()))
+    }
+
+    /// Returns the number of elements including deleted ones of
+    /// nested tables.
     pub fn size(&self) -> Result<i32> {
         let mut size = 0;
         chkerr!(self.ctxt,
@@ -89,6 +106,12 @@ impl Collection {
         Ok(size)
     }
 
+    /// Returns the first index of existing elements. Indexes aren't
+    /// always contiguous. Associative arrays (PL/SQL index-by tables)
+    /// and nested tables whose elements were deleted are sparse.
+    /// Traverse them by this and [next_index](#method.next_index).
+    ///
+    /// This returns `Err(Error::NoMoreData)` when the collection is empty.
     pub fn first_index(&self) -> Result<i32> {
         let mut index = 0;
         let mut exists = 0;
@@ -101,6 +124,9 @@ impl Collection {
         }
     }
 
+    /// Returns the last index of existing elements.
+    ///
+    /// This returns `Err(Error::NoMoreData)` when the collection is empty.
     pub fn last_index(&self) -> Result<i32> {
         let mut index = 0;
         let mut exists = 0;
@@ -113,6 +139,9 @@ impl Collection {
         }
     }
 
+    /// Returns the index of the existing element next to `index`.
+    ///
+    /// This returns `Err(Error::NoMoreData)` when no element follows.
     pub fn next_index(&self, index: i32) -> Result<i32> {
         let mut next = 0;
         let mut exists = 0;
@@ -125,7 +154,10 @@ impl Collection {
         }
     }
 
-    pub fn prev_index(&self, index: i32) -> Result<i32> {
+    /// Returns the index of the existing element prior to `index`.
+    ///
+    /// This returns `Err(Error::NoMoreData)` when no element precedes.
+    pub fn prior_index(&self, index: i32) -> Result<i32> {
         let mut prev = 0;
         let mut exists = 0;
         chkerr!(self.ctxt,
@@ -137,13 +169,47 @@ impl Collection {
         }
     }
 
-    pub fn exist(&self, index: i32) -> Result<bool> {
+    #[doc(hidden)]
+    #[deprecated(note = "use prior_index() instead")]
+    pub fn prev_index(&self, index: i32) -> Result<i32> {
+        self.prior_index(index)
+    }
+
+    /// Returns true when an element exists at `index`.
+    pub fn exists(&self, index: i32) -> Result<bool> {
         let mut exists = 0;
         chkerr!(self.ctxt,
                 dpiObject_getElementExistsByIndex(self.handle, index, &mut exists));
         Ok(exists != 0)
     }
 
+    #[doc(hidden)]
+    #[deprecated(note = "use exists() instead")]
+    pub fn exist(&self, index: i32) -> Result<bool> {
+        self.exists(index)
+    }
+
+    /// Returns an iterator over elements converted to `T` in the order of
+    /// indexes. Indexes of deleted elements are skipped.
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let objtype = conn.object_type("MDSYS.SDO_ELEM_INFO_ARRAY").unwrap();
+    /// let mut coll = objtype.new_collection().unwrap();
+    /// coll.push(&1).unwrap();
+    /// coll.push(&1003).unwrap();
+    /// let vals = coll.iter::<i32>().collect::<oracle::Result<Vec<_>>>().unwrap();
+    /// assert_eq!(vals, vec![1, 1003]);
+    /// ```
+    pub fn iter<T>(&self) -> CollectionIter<T> where T: FromSql {
+        CollectionIter {
+            coll: self,
+            next_index: None,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
+
     pub fn get<T>(&self, index: i32) -> Result<T> where T: FromSql {
         let oratype = self.objtype.element_oracle_type().unwrap();
         let mut data = Default::default();
@@ -179,12 +245,27 @@ impl Collection {
         Ok(())
     }
 
+    /// Appends all values of `iter` to the end of the collection.
+    pub fn extend<I, T>(&mut self, iter: I) -> Result<()> where I: IntoIterator<Item = T>, T: ToSql {
+        for value in iter {
+            self.push(&value)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the element at `index`. Indexes of following elements
+    /// aren't changed. Deleted elements of nested tables leave a gap,
+    /// which is skipped by [next_index](#method.next_index) and
+    /// [iter](#method.iter) but counted by [size](#method.size).
+    /// VARRAYs don't support deletion.
     pub fn remove(&mut self, index: i32) -> Result<()> {
         chkerr!(self.ctxt,
                 dpiObject_deleteElementByIndex(self.handle, index));
         Ok(())
     }
 
+    /// Removes `len` elements from the end of the collection.
+    /// This fails when `len` is greater than [size](#method.size).
     pub fn trim(&mut self, len: usize) -> Result<()> {
         chkerr!(self.ctxt,
                 dpiObject_trim(self.handle, len as u32));
@@ -192,6 +273,48 @@ impl Collection {
     }
 }
 
+/// An iterator over elements of a collection
+///
+/// This is created by [Collection.iter()](struct.Collection.html#method.iter).
+pub struct CollectionIter<'a, T> {
+    coll: &'a Collection,
+    next_index: Option<i32>,
+    done: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T> CollectionIter<'a, T> where T: FromSql {
+    fn next_item(&mut self) -> Result<T> {
+        let index = match self.next_index {
+            Some(index) => self.coll.next_index(index)?,
+            None => self.coll.first_index()?,
+        };
+        self.next_index = Some(index);
+        self.coll.get(index)
+    }
+}
+
+impl<'a, T> Iterator for CollectionIter<'a, T> where T: FromSql {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+        match self.next_item() {
+            Ok(val) => Some(Ok(val)),
+            Err(Error::NoMoreData) => {
+                self.done = true;
+                None
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
 impl Clone for Collection {
     fn clone(&self) -> Collection {
         unsafe { dpiObject_addRef(self.handle) };
@@ -264,6 +387,32 @@ impl fmt::Debug for Collection {
 // Object
 //
 
+/// Object data type of Oracle database
+///
+/// Attributes are got and set by their names with the same conversions
+/// as columns and bind values. See [FromSql][] and [ToSql][].
+///
+/// NULL attributes are got as `None` by `Option<T>` and set by `None`.
+/// Getting NULL as a non-`Option` type returns `Error::NullValue`.
+/// Use [set_null][] for attributes of object types, which have no
+/// `Option` counterpart.
+///
+/// ```no_run
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// // create type udt_person as object (name varchar2(30), age number);
+/// let objtype = conn.object_type("UDT_PERSON").unwrap();
+/// let mut obj = objtype.new_object().unwrap();
+/// obj.set("NAME", &"Smith").unwrap();
+/// obj.set("AGE", &None::<i32>).unwrap();
+/// let name: String = obj.get("NAME").unwrap();
+/// let age: Option<i32> = obj.get("AGE").unwrap();
+/// assert_eq!(name, "Smith");
+/// assert_eq!(age, None);
+/// ```
+///
+/// [FromSql]: trait.FromSql.html
+/// [ToSql]: trait.ToSql.html
+/// [set_null]: #method.set_null
 pub struct Object {
     ctxt: &'static Context,
     pub(crate) handle: *mut dpiObject,
@@ -283,15 +432,28 @@ impl Object {
         &self.objtype
     }
 
+    /// Returns a deep copy of the object.
+    ///
+    /// [Clone][] shares the underlying instance, which may be owned by
+    /// a fetched row. Use this to modify a fetched object and bind it
+    /// again without affecting the row.
+    ///
+    /// [Clone]: https://doc.rust-lang.org/std/clone/trait.Clone.html
+    pub fn clone_value(&self) -> Result<Object> {
+        let mut handle = ptr::null_mut();
+        chkerr!(self.ctxt,
+                dpiObject_copy(self.handle, &mut handle));
+        Ok(Object::new(self.ctxt, handle, self.objtype.clone()))
+    }
+
     fn type_attr(&self, name: &str) -> Result<&ObjectTypeAttr> {
-        for attr in self.objtype.attributes() {
-            if attr.name() == name {
-                return Ok(attr);
-            }
-        }
-        Err(Error::InvalidAttributeName(name.to_string()))
+        self.objtype.attribute(name)
+            .ok_or_else(|| Error::InvalidAttributeName(name.to_string()))
     }
 
+    /// Gets the value of the attribute as any type implementing [FromSql][].
+    ///
+    /// [FromSql]: trait.FromSql.html
     pub fn get_by_attr<T>(&self, attr: &ObjectTypeAttr) -> Result<T> where T: FromSql {
         let mut data = Default::default();
         let mut buf = [0i8; 172]; // DPI_NUMBER_AS_TEXT_CHARS in odpi/src/dpiImpl.h
@@ -307,12 +469,40 @@ impl Object {
         sql_value.get()
     }
 
+    /// Gets the value of the attribute specified by name as any type
+    /// implementing [FromSql][]. The name is compared case-insensitively
+    /// when no attribute has exactly the same name.
+    ///
+    /// [FromSql]: trait.FromSql.html
     pub fn get<T>(&self, name: &str) -> Result<T> where T: FromSql {
         self.get_by_attr(self.type_attr(name)?)
     }
 
+    /// Sets any type implementing [ToSql][] to the attribute specified by name.
+    /// The name is compared case-insensitively when no attribute has exactly
+    /// the same name.
+    ///
+    /// [ToSql]: trait.ToSql.html
     pub fn set(&mut self, name: &str, value: &ToSql) -> Result<()> {
         let attrtype = self.type_attr(name)?;
+        self.set_attr_value(attrtype, value)
+    }
+
+    /// Sets any type implementing [ToSql][] to the attribute.
+    ///
+    /// [ToSql]: trait.ToSql.html
+    pub fn set_by_attr(&mut self, attrtype: &ObjectTypeAttr, value: &ToSql) -> Result<()> {
+        self.set_attr_value(attrtype, value)
+    }
+
+    /// Sets NULL to the attribute specified by name.
+    /// This works for attributes of any type including object types.
+    pub fn set_null(&mut self, name: &str) -> Result<()> {
+        let attrtype = self.type_attr(name)?;
+        self.set_attr_value(attrtype, &attrtype.oratype)
+    }
+
+    fn set_attr_value(&self, attrtype: &ObjectTypeAttr, value: &ToSql) -> Result<()> {
         let mut data = Default::default();
         let mut sql_value = SqlValue::from_oratype(self.ctxt, &attrtype.oratype, &mut data)?;
         sql_value.set(value)?;
@@ -323,6 +513,43 @@ impl Object {
     }
 }
 
+/// A trait to convert Oracle objects to rust values.
+///
+/// Implement this for structs corresponding to object types and get them
+/// by [SqlValue.as_object_of()][] instead of getting attributes one by one.
+///
+/// [SqlValue.as_object_of()]: struct.SqlValue.html#method.as_object_of
+///
+/// # Examples
+///
+/// ```no_run
+/// use oracle::{Connection, FromObject, Object, Result};
+///
+/// // create type udt_point as object (x number, y number);
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl FromObject for Point {
+///     fn from_object(obj: &Object) -> Result<Point> {
+///         Ok(Point {
+///             x: obj.get("X")?,
+///             y: obj.get("Y")?,
+///         })
+///     }
+/// }
+///
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select udt_point(1, 2) from dual", &[]).unwrap();
+/// let row = stmt.fetch().unwrap();
+/// let point: Point = row.columns()[0].as_object_of().unwrap();
+/// assert_eq!((point.x, point.y), (1, 2));
+/// ```
+pub trait FromObject {
+    fn from_object(obj: &Object) -> Result<Self> where Self: Sized;
+}
+
 impl Clone for Object {
     fn clone(&self) -> Object {
         unsafe { dpiObject_addRef(self.handle) };
@@ -424,11 +651,48 @@ impl ObjectType {
         })
     }
 
+    /// Creates an object type which isn't associated with any database
+    /// connection.
+    ///
+    /// This doesn't need Oracle client libraries. It is intended for tools
+    /// handling metadata, such as code generators, which don't always connect
+    /// to a database. [new_object][] returns `None` for object types created
+    /// by this.
+    ///
+    /// [new_object]: #method.new_object
+    pub fn new_offline(schema: &str, name: &str, attrs: Vec<ObjectTypeAttr>) -> ObjectType {
+        ObjectType {
+            internal: Rc::new(ObjectTypeInternal::new_offline(schema, name, None, attrs)),
+        }
+    }
+
+    /// Creates a collection type which isn't associated with any database
+    /// connection.
+    ///
+    /// See [new_offline](#method.new_offline). [new_collection][] returns
+    /// `None` for collection types created by this.
+    ///
+    /// [new_collection]: #method.new_collection
+    pub fn new_offline_collection(schema: &str, name: &str, elem_oratype: OracleType) -> ObjectType {
+        ObjectType {
+            internal: Rc::new(ObjectTypeInternal::new_offline(schema, name, Some(elem_oratype), Vec::new())),
+        }
+    }
+
+    /// True when the object type isn't associated with any database
+    /// connection. See [new_offline](#method.new_offline).
+    pub fn is_offline(&self) -> bool {
+        self.internal.handle.is_null()
+    }
+
     pub(crate) fn handle(&self) -> *mut dpiObjectType {
         self.internal.handle
     }
 
     /// Gets schema name
+    ///
+    /// The name of the package isn't available for PL/SQL types declared
+    /// in packages because the bundled ODPI-C doesn't provide it.
     pub fn schema(&self) -> &String {
         &self.internal.schema
     }
@@ -438,7 +702,7 @@ impl ObjectType {
         &self.internal.name
     }
 
-    /// True when it is a collectoin. Otherwise false.
+    /// True when it is a collection. Otherwise false.
     pub fn is_collection(&self) -> bool {
         self.internal.elem_oratype.is_some()
     }
@@ -477,11 +741,25 @@ impl ObjectType {
         &self.internal.attrs
     }
 
+    /// Gets attribute information by name. The name is compared
+    /// case-insensitively when no attribute has exactly the same name.
+    /// This returns `None` when it isn't found or the type is a collection.
+    pub fn attribute(&self, name: &str) -> Option<&ObjectTypeAttr> {
+        let attrs = &self.internal.attrs;
+        attrs.iter().find(|attr| attr.name() == name)
+            .or_else(|| attrs.iter().find(|attr| attr.name().eq_ignore_ascii_case(name)))
+    }
+
+    /// Creates a new object whose attributes are NULL. This returns `None`
+    /// when it is a collection or an offline object type.
     pub fn new_object(&self) -> Option<Object> {
         if self.is_collection() {
             return None
         }
-        let ctxt = self.internal.ctxt;
+        let ctxt = match self.internal.ctxt {
+            Some(ctxt) => ctxt,
+            None => return None,
+        };
         let mut handle = ptr::null_mut();
         if unsafe {dpiObjectType_createObject(self.internal.handle, &mut handle)} != DPI_SUCCESS as i32 {
             return None;
@@ -489,17 +767,42 @@ impl ObjectType {
         Some(Object::new(ctxt, handle, self.clone()))
     }
 
+    /// Creates a new empty collection. This returns `None` when it isn't
+    /// a collection or is an offline collection type.
     pub fn new_collection(&self) -> Option<Collection> {
         if !self.is_collection() {
             return None
         }
-        let ctxt = self.internal.ctxt;
+        let ctxt = match self.internal.ctxt {
+            Some(ctxt) => ctxt,
+            None => return None,
+        };
         let mut handle = ptr::null_mut();
         if unsafe {dpiObjectType_createObject(self.internal.handle, &mut handle)} != DPI_SUCCESS as i32 {
             return None;
         }
         Some(Collection::new(ctxt, handle, self.clone()))
     }
+
+    /// Creates a collection filled with values of `iter`.
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let objtype = conn.object_type("MDSYS.SDO_ELEM_INFO_ARRAY").unwrap();
+    /// let coll = objtype.new_collection_from_iter(vec![1, 1003, 3]).unwrap();
+    /// conn.execute("insert into shapes values (:1)", &[&coll]).unwrap();
+    /// ```
+    pub fn new_collection_from_iter<I, T>(&self, iter: I) -> Result<Collection> where I: IntoIterator<Item = T>, T: ToSql {
+        if !self.is_collection() {
+            return Err(Error::InvalidTypeConversion(self.to_string(), "Collection".to_string()));
+        }
+        let mut coll = match self.new_collection() {
+            Some(coll) => coll,
+            None => return Err(Error::InternalError(format!("failed to create a collection of {}", self))),
+        };
+        coll.extend(iter)?;
+        Ok(coll)
+    }
 }
 
 impl cmp::PartialEq for ObjectType {
@@ -528,25 +831,34 @@ impl fmt::Debug for ObjectType {
 ///
 /// See [ObjectType.attributes()](struct.ObjectType.html#method.attributes)
 pub struct ObjectTypeAttr {
-    ctxt: &'static Context,
     handle: *mut dpiObjectAttr,
     name: String,
     oratype: OracleType,
 }
 
 impl ObjectTypeAttr {
-    fn new(ctxt: &'static Context, handle: *mut dpiObjectAttr) -> Result<ObjectTypeAttr> {
+    #[allow(non_snake_case)]
+    fn from_dpiObjectAttr(ctxt: &'static Context, handle: *mut dpiObjectAttr) -> Result<ObjectTypeAttr> {
         let mut info = Default::default();
         chkerr!(ctxt,
                 dpiObjectAttr_getInfo(handle, &mut info));
         Ok(ObjectTypeAttr {
-            ctxt: ctxt,
             handle: handle,
             name: OdpiStr::new(info.name, info.nameLength).to_string(),
             oratype: OracleType::from_type_info(ctxt, &info.typeInfo)?,
         })
     }
 
+    /// Creates attribute information which isn't associated with any
+    /// database connection. See [ObjectType::new_offline](struct.ObjectType.html#method.new_offline).
+    pub fn new_offline(name: &str, oracle_type: OracleType) -> ObjectTypeAttr {
+        ObjectTypeAttr {
+            handle: ptr::null_mut(),
+            name: name.to_string(),
+            oratype: oracle_type,
+        }
+    }
+
     /// Gets the attribute name
     pub fn name(&self) -> &String {
         &self.name
@@ -560,9 +872,10 @@ impl ObjectTypeAttr {
 
 impl Clone for ObjectTypeAttr {
     fn clone(&self) -> ObjectTypeAttr {
-        unsafe { dpiObjectAttr_addRef(self.handle) };
+        if !self.handle.is_null() {
+            unsafe { dpiObjectAttr_addRef(self.handle) };
+        }
         ObjectTypeAttr {
-            ctxt: self.ctxt,
             handle: self.handle,
             name: self.name.clone(),
             oratype: self.oratype.clone(),
@@ -572,7 +885,9 @@ impl Clone for ObjectTypeAttr {
 
 impl Drop for ObjectTypeAttr {
     fn drop(&mut self) {
-        unsafe { dpiObjectAttr_release(self.handle); };
+        if !self.handle.is_null() {
+            unsafe { dpiObjectAttr_release(self.handle); };
+        }
     }
 }
 
@@ -581,7 +896,7 @@ impl Drop for ObjectTypeAttr {
 //
 
 struct ObjectTypeInternal {
-    ctxt: &'static Context,
+    ctxt: Option<&'static Context>,
     handle: *mut dpiObjectType,
     schema: String,
     name: String,
@@ -608,7 +923,7 @@ impl ObjectTypeInternal {
                                                 attr_handles.as_mut_ptr()));
             let mut attrs = Vec::with_capacity(attrnum);
             for i in 0..attrnum {
-                match ObjectTypeAttr::new(ctxt, attr_handles[i]) {
+                match ObjectTypeAttr::from_dpiObjectAttr(ctxt, attr_handles[i]) {
                     Ok(attr) => attrs.push(attr),
                     Err(err) => {
                         for j in i..attrnum {
@@ -622,7 +937,7 @@ impl ObjectTypeInternal {
         };
         unsafe { dpiObjectType_addRef(handle); }
         Ok(ObjectTypeInternal {
-            ctxt: ctxt,
+            ctxt: Some(ctxt),
             handle: handle,
             schema: OdpiStr::new(info.schema, info.schemaLength).to_string(),
             name: OdpiStr::new(info.name, info.nameLength).to_string(),
@@ -630,6 +945,17 @@ impl ObjectTypeInternal {
             attrs: attrs,
         })
     }
+
+    fn new_offline(schema: &str, name: &str, elem_oratype: Option<OracleType>, attrs: Vec<ObjectTypeAttr>) -> ObjectTypeInternal {
+        ObjectTypeInternal {
+            ctxt: None,
+            handle: ptr::null_mut(),
+            schema: schema.to_string(),
+            name: name.to_string(),
+            elem_oratype: elem_oratype,
+            attrs: attrs,
+        }
+    }
 }
 
 impl Drop for ObjectTypeInternal {
@@ -642,7 +968,17 @@ impl Drop for ObjectTypeInternal {
 
 impl cmp::PartialEq for ObjectTypeInternal {
     fn eq(&self, other: &Self) -> bool {
-        self.handle == other.handle
+        if self.handle.is_null() || other.handle.is_null() {
+            // offline object types
+            self.handle == other.handle
+                && self.schema == other.schema
+                && self.name == other.name
+                && self.elem_oratype == other.elem_oratype
+                && self.attrs.len() == other.attrs.len()
+                && self.attrs.iter().zip(other.attrs.iter()).all(|(a, b)| a.name == b.name && a.oratype == b.oratype)
+        } else {
+            self.handle == other.handle
+        }
     }
 }
 
@@ -39,6 +39,7 @@ use Result;
 use binding::*;
 use Context;
 use ObjectType;
+use types::MAX_STRING_SIZE;
 
 // NativeType corresponds to dpiNativeTypeNum in ODPI
 // except Char, Number, Raw, CLOB and BLOB.
@@ -56,6 +57,7 @@ pub enum NativeType {
     IntervalYM, // oracle::IntervalYM in rust
     CLOB,
     BLOB,
+    BFILE,
     Object(ObjectType),
     #[allow(dead_code)]
     Stmt,
@@ -79,6 +81,7 @@ impl NativeType {
             NativeType::IntervalYM => DPI_NATIVE_TYPE_INTERVAL_YM,
             NativeType::CLOB => DPI_NATIVE_TYPE_LOB,
             NativeType::BLOB => DPI_NATIVE_TYPE_LOB,
+            NativeType::BFILE => DPI_NATIVE_TYPE_LOB,
             NativeType::Object(_) => DPI_NATIVE_TYPE_OBJECT,
             NativeType::Stmt => DPI_NATIVE_TYPE_STMT,
             NativeType::Boolean => DPI_NATIVE_TYPE_BOOLEAN,
@@ -98,6 +101,8 @@ impl NativeType {
 #[derive(Debug, Clone, PartialEq)]
 pub enum OracleType {
     /// VARCHAR2(size)
+    ///
+    /// Bind variables larger than 32767 bytes are created as LONG.
     Varchar2(u32),
 
     /// NVARCHAR2(size)
@@ -113,6 +118,8 @@ pub enum OracleType {
     Rowid,
 
     /// RAW(size)
+    ///
+    /// Bind variables larger than 32767 bytes are created as LONG RAW.
     Raw(u32),
 
     /// BINARY_FLOAT
@@ -202,7 +209,13 @@ pub enum OracleType {
     /// REF CURSOR (not supported)
     RefCursor,
 
-    /// BOOLEAN (not supported)
+    /// BOOLEAN
+    ///
+    /// PL/SQL BOOLEAN, which needs Oracle client and server 12.1 or later.
+    /// With Oracle client and server 23ai or later, values bound as BOOLEAN
+    /// can also be used in SQL statements. However SQL BOOLEAN columns
+    /// cannot be fetched because the bundled ODPI-C cannot describe them.
+    /// Convert them in queries, for example `case when col then 1 else 0 end`.
     Boolean,
 
     /// Object
@@ -267,6 +280,10 @@ impl OracleType {
         // However I don't want to do it to hide internal information such
         // as dpiNativeTypeNum.
         match *self {
+            // VARCHAR2 and RAW larger than MAX_STRING_SIZE are created as
+            // LONG and LONG RAW, whose buffers are allocated dynamically.
+            OracleType::Varchar2(size) if size as usize > MAX_STRING_SIZE =>
+                Ok((DPI_ORACLE_TYPE_LONG_VARCHAR, NativeType::Char, 0, 0)),
             OracleType::Varchar2(size) =>
                 Ok((DPI_ORACLE_TYPE_VARCHAR, NativeType::Char, size, 1)),
             OracleType::NVarchar2(size) =>
@@ -277,6 +294,8 @@ impl OracleType {
                 Ok((DPI_ORACLE_TYPE_NCHAR, NativeType::Char, size, 0)),
             OracleType::Rowid =>
                 Ok((DPI_ORACLE_TYPE_ROWID, NativeType::Rowid, 0, 0)),
+            OracleType::Raw(size) if size as usize > MAX_STRING_SIZE =>
+                Ok((DPI_ORACLE_TYPE_LONG_RAW, NativeType::Raw, 0, 0)),
             OracleType::Raw(size) =>
                 Ok((DPI_ORACLE_TYPE_RAW, NativeType::Raw, size, 1)),
             OracleType::BinaryFloat =>
@@ -305,11 +324,11 @@ impl OracleType {
             OracleType::BLOB =>
                 Ok((DPI_ORACLE_TYPE_BLOB, NativeType::BLOB, 0, 0)),
             OracleType::BFILE =>
-                Ok((DPI_ORACLE_TYPE_BFILE, NativeType::BLOB, 0, 0)),
+                Ok((DPI_ORACLE_TYPE_BFILE, NativeType::BFILE, 0, 0)),
 //            OracleType::RefCursor =>
 //                Ok((DPI_ORACLE_TYPE_STMT, NativeType::Stmt, 0, 0)),
-//            OracleType::Boolean =>
-//                Ok((DPI_ORACLE_TYPE_BOOLEAN, NativeType::Boolean, 0, 0)),
+            OracleType::Boolean =>
+                Ok((DPI_ORACLE_TYPE_BOOLEAN, NativeType::Boolean, 0, 0)),
             OracleType::Object(ref objtype) =>
                 Ok((DPI_ORACLE_TYPE_OBJECT, NativeType::Object(objtype.clone()), 0, 0)),
             OracleType::Long =>
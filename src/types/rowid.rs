@@ -0,0 +1,90 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::fmt;
+use std::str;
+
+use Error;
+
+/// Oracle ROWID and UROWID
+///
+/// This is fetched from a ROWID column or the `rowid` pseudo column and
+/// bound to revisit the row later.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oracle::RowId;
+///
+/// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select rowid, sal from emp", &[]).unwrap();
+/// let mut rows = Vec::new();
+/// while let Ok(row) = stmt.fetch() {
+///     let (rowid, sal) = row.get_as::<(RowId, f64)>().unwrap();
+///     rows.push((rowid, sal));
+/// }
+/// for &(ref rowid, sal) in &rows {
+///     conn.execute("update emp set sal = :1 where rowid = :2", &[&(sal * 1.1), rowid]).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RowId {
+    value: String,
+}
+
+impl RowId {
+    pub(crate) fn new(value: String) -> RowId {
+        RowId { value: value }
+    }
+
+    /// Returns the string representation of the rowid.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for RowId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl str::FromStr for RowId {
+    type Err = Error;
+
+    /// Creates a rowid from the string representation.
+    /// The validity of the string is checked by the Oracle server when
+    /// it is used.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RowId { value: s.to_string() })
+    }
+}
@@ -0,0 +1,76 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use rust_decimal::Decimal;
+
+use Error;
+use FromSql;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSqlNull;
+use ToSql;
+
+//
+// rust_decimal::Decimal
+//
+
+/// Converts NUMBER to Decimal without loss of precision.
+///
+/// This returns `Error::Overflow` when the number cannot be represented
+/// by Decimal exactly, for example, when it has more than 28 fractional
+/// digits.
+impl FromSql for Decimal {
+    fn from_sql(val: &SqlValue) -> Result<Decimal> {
+        if let Some(num) = val.as_small_integer()? {
+            return Ok(Decimal::from(num));
+        }
+        let s = val.as_oracle_number()?.to_string();
+        Decimal::from_str_exact(&s).map_err(|_| Error::Overflow(s, "Decimal"))
+    }
+}
+
+impl ToSqlNull for Decimal {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::Number(0, 0))
+    }
+}
+
+impl ToSql for Decimal {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::Number(0, 0))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_string(&self.to_string())
+    }
+}
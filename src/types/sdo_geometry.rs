@@ -0,0 +1,319 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+#[cfg(feature = "geo-types")]
+use geo_types;
+
+use Collection;
+use Connection;
+use Error;
+use FromObject;
+use FromSql;
+use Object;
+use Result;
+use SqlValue;
+
+//
+// SdoPoint
+//
+
+/// Coordinates of `MDSYS.SDO_POINT_TYPE`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdoPoint {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+}
+
+impl FromObject for SdoPoint {
+    fn from_object(obj: &Object) -> Result<SdoPoint> {
+        Ok(SdoPoint {
+            x: obj.get("X")?,
+            y: obj.get("Y")?,
+            z: obj.get("Z")?,
+        })
+    }
+}
+
+//
+// SdoGeometry
+//
+
+/// Spatial geometry mapped from `MDSYS.SDO_GEOMETRY`
+///
+/// This is available when `sdo_geometry` feature is enabled.
+/// `SDO_ELEM_INFO` and `SDO_ORDINATES` are empty vectors when they
+/// are NULL.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oracle::{Connection, SdoGeometry};
+///
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let mut stmt = conn.execute("select shape from cola_markets where name = 'cola_a'", &[]).unwrap();
+/// let geom: SdoGeometry = stmt.fetch().unwrap().get(0).unwrap();
+/// assert_eq!(geom.gtype, 2003);
+/// assert_eq!(geom.elem_info, vec![1, 1003, 3]);
+/// assert_eq!(geom.ordinates, vec![1.0, 1.0, 5.0, 7.0]);
+///
+/// // bind it as an object
+/// let obj = geom.to_object(&conn).unwrap();
+/// conn.execute("insert into cola_markets values (5, 'cola_e', :1)", &[&obj]).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdoGeometry {
+    /// `SDO_GTYPE` in the form of `DLTT`
+    pub gtype: i32,
+    pub srid: Option<i32>,
+    pub point: Option<SdoPoint>,
+    pub elem_info: Vec<i32>,
+    pub ordinates: Vec<f64>,
+}
+
+impl SdoGeometry {
+    /// Returns the number of dimensions, the `D` digit of `SDO_GTYPE`.
+    pub fn dimensions(&self) -> i32 {
+        self.gtype / 1000
+    }
+
+    /// Returns the geometry type, the `TT` digits of `SDO_GTYPE`.
+    /// For example, 1 is a point, 2 is a line and 3 is a polygon.
+    pub fn geometry_type(&self) -> i32 {
+        self.gtype % 100
+    }
+
+    /// Creates an `MDSYS.SDO_GEOMETRY` object, which may be bound to
+    /// a statement.
+    pub fn to_object(&self, conn: &Connection) -> Result<Object> {
+        let objtype = conn.object_type("MDSYS.SDO_GEOMETRY")?;
+        let mut obj = new_object(&objtype)?;
+        obj.set("SDO_GTYPE", &self.gtype)?;
+        obj.set("SDO_SRID", &self.srid)?;
+        match self.point {
+            Some(ref point) => {
+                let mut pt = new_object(&conn.object_type("MDSYS.SDO_POINT_TYPE")?)?;
+                pt.set("X", &point.x)?;
+                pt.set("Y", &point.y)?;
+                pt.set("Z", &point.z)?;
+                obj.set("SDO_POINT", &pt)?;
+            },
+            None => obj.set_null("SDO_POINT")?,
+        }
+        if self.elem_info.is_empty() {
+            obj.set_null("SDO_ELEM_INFO")?;
+        } else {
+            let objtype = conn.object_type("MDSYS.SDO_ELEM_INFO_ARRAY")?;
+            obj.set("SDO_ELEM_INFO", &objtype.new_collection_from_iter(self.elem_info.iter().cloned())?)?;
+        }
+        if self.ordinates.is_empty() {
+            obj.set_null("SDO_ORDINATES")?;
+        } else {
+            let objtype = conn.object_type("MDSYS.SDO_ORDINATE_ARRAY")?;
+            obj.set("SDO_ORDINATES", &objtype.new_collection_from_iter(self.ordinates.iter().cloned())?)?;
+        }
+        Ok(obj)
+    }
+}
+
+fn new_object(objtype: &::ObjectType) -> Result<Object> {
+    objtype.new_object()
+        .ok_or_else(|| Error::InternalError(format!("failed to create an object of {}", objtype)))
+}
+
+fn collection_to_vec<T>(coll: Option<Collection>) -> Result<Vec<T>> where T: FromSql {
+    match coll {
+        Some(coll) => coll.iter().collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+impl FromObject for SdoGeometry {
+    fn from_object(obj: &Object) -> Result<SdoGeometry> {
+        let point = match obj.get::<Option<Object>>("SDO_POINT")? {
+            Some(ref pt) => Some(SdoPoint::from_object(pt)?),
+            None => None,
+        };
+        Ok(SdoGeometry {
+            gtype: obj.get("SDO_GTYPE")?,
+            srid: obj.get("SDO_SRID")?,
+            point: point,
+            elem_info: collection_to_vec(obj.get("SDO_ELEM_INFO")?)?,
+            ordinates: collection_to_vec(obj.get("SDO_ORDINATES")?)?,
+        })
+    }
+}
+
+impl FromSql for SdoGeometry {
+    fn from_sql(val: &SqlValue) -> Result<SdoGeometry> {
+        val.as_object_of()
+    }
+}
+
+//
+// geo_types::Geometry
+//
+
+#[cfg(feature = "geo-types")]
+impl SdoGeometry {
+    /// Converts the geometry to [geo_types::Geometry][]. This is available
+    /// when `geo-types` feature is enabled.
+    ///
+    /// Points, lines, polygons, their collections and optimized rectangles
+    /// consisting of straight line segments are supported. Coordinates
+    /// other than X and Y are ignored. Arcs and other elements return
+    /// `Error::InvalidTypeConversion`.
+    ///
+    /// [geo_types::Geometry]: https://docs.rs/geo-types/0.7/geo_types/geometry/enum.Geometry.html
+    pub fn to_geo(&self) -> Result<geo_types::Geometry<f64>> {
+        let dims = if self.dimensions() >= 2 { self.dimensions() as usize } else { 2 };
+        let unsupported = || Error::InvalidTypeConversion(format!("SDO_GEOMETRY(gtype {}, elem_info {:?})", self.gtype, self.elem_info),
+                                                          "geo_types::Geometry".to_string());
+        if self.elem_info.is_empty() {
+            return match self.point {
+                Some(SdoPoint { x: Some(x), y: Some(y), .. }) if self.geometry_type() == 1 =>
+                    Ok(geo_types::Point::new(x, y).into()),
+                _ => Err(unsupported()),
+            };
+        }
+        if self.elem_info.len() % 3 != 0 {
+            return Err(unsupported());
+        }
+        let mut points = Vec::new();
+        let mut lines = Vec::new();
+        let mut polygons: Vec<geo_types::Polygon<f64>> = Vec::new();
+        let num_elems = self.elem_info.len() / 3;
+        for i in 0..num_elems {
+            let start = (self.elem_info[i * 3] - 1) as usize;
+            let end = if i + 1 < num_elems { (self.elem_info[(i + 1) * 3] - 1) as usize } else { self.ordinates.len() };
+            if start > end || end > self.ordinates.len() || (end - start) % dims != 0 {
+                return Err(unsupported());
+            }
+            let coords = self.ordinates[start..end].chunks(dims)
+                .map(|c| geo_types::Coord { x: c[0], y: c[1] })
+                .collect::<Vec<_>>();
+            match (self.elem_info[i * 3 + 1], self.elem_info[i * 3 + 2]) {
+                (1, _) =>
+                    points.extend(coords.into_iter().map(geo_types::Point::from)),
+                (2, 1) =>
+                    lines.push(geo_types::LineString::new(coords)),
+                (1003, interp) | (2003, interp) => {
+                    let ring = match interp {
+                        1 => geo_types::LineString::new(coords),
+                        3 if coords.len() == 2 => {
+                            let (a, b) = (coords[0], coords[1]);
+                            geo_types::LineString::from(vec![(a.x, a.y), (b.x, a.y), (b.x, b.y), (a.x, b.y), (a.x, a.y)])
+                        },
+                        _ => return Err(unsupported()),
+                    };
+                    if self.elem_info[i * 3 + 1] == 1003 {
+                        polygons.push(geo_types::Polygon::new(ring, Vec::new()));
+                    } else {
+                        match polygons.last_mut() {
+                            Some(polygon) => polygon.interiors_push(ring),
+                            None => return Err(unsupported()),
+                        }
+                    }
+                },
+                _ => return Err(unsupported()),
+            }
+        }
+        match self.geometry_type() {
+            1 if points.len() == 1 => Ok(points.remove(0).into()),
+            2 if lines.len() == 1 => Ok(lines.remove(0).into()),
+            3 if polygons.len() == 1 => Ok(polygons.remove(0).into()),
+            5 => Ok(geo_types::MultiPoint::new(points).into()),
+            6 => Ok(geo_types::MultiLineString::new(lines).into()),
+            7 => Ok(geo_types::MultiPolygon::new(polygons).into()),
+            _ => Err(unsupported()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(gtype: i32, elem_info: Vec<i32>, ordinates: Vec<f64>) -> SdoGeometry {
+        SdoGeometry {
+            gtype: gtype,
+            srid: None,
+            point: None,
+            elem_info: elem_info,
+            ordinates: ordinates,
+        }
+    }
+
+    #[test]
+    fn gtype() {
+        let geom = geometry(3002, vec![], vec![]);
+        assert_eq!(geom.dimensions(), 3);
+        assert_eq!(geom.geometry_type(), 2);
+    }
+
+    #[cfg(feature = "geo-types")]
+    #[test]
+    fn to_geo() {
+        use geo_types::{Geometry, LineString, MultiPoint, Point, Polygon};
+
+        let mut geom = geometry(2001, vec![], vec![]);
+        geom.point = Some(SdoPoint { x: Some(12.0), y: Some(14.0), z: None });
+        assert_eq!(geom.to_geo().unwrap(), Geometry::Point(Point::new(12.0, 14.0)));
+
+        let geom = geometry(3002, vec![1, 2, 1], vec![1.0, 2.0, 0.0, 3.0, 4.0, 0.0]);
+        assert_eq!(geom.to_geo().unwrap(),
+                   Geometry::LineString(LineString::from(vec![(1.0, 2.0), (3.0, 4.0)])));
+
+        let geom = geometry(2003, vec![1, 1003, 3], vec![1.0, 1.0, 5.0, 7.0]);
+        let rect = LineString::from(vec![(1.0, 1.0), (5.0, 1.0), (5.0, 7.0), (1.0, 7.0), (1.0, 1.0)]);
+        assert_eq!(geom.to_geo().unwrap(), Geometry::Polygon(Polygon::new(rect, vec![])));
+
+        let geom = geometry(2003, vec![1, 1003, 1, 11, 2003, 1],
+                            vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0, 0.0, 0.0,
+                                 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 1.0]);
+        match geom.to_geo().unwrap() {
+            Geometry::Polygon(polygon) => {
+                assert_eq!(polygon.exterior().0.len(), 5);
+                assert_eq!(polygon.interiors().len(), 1);
+            },
+            geom => panic!("unexpected geometry: {:?}", geom),
+        }
+
+        let geom = geometry(2005, vec![1, 1, 2], vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(geom.to_geo().unwrap(),
+                   Geometry::MultiPoint(MultiPoint::from(vec![(1.0, 2.0), (3.0, 4.0)])));
+
+        // arc string
+        let geom = geometry(2002, vec![1, 2, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 2.0]);
+        assert!(geom.to_geo().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use serde::ser::Serialize;
+use serde::ser::Error as SerError;
+use serde::ser::SerializeMap;
+use serde::ser::SerializeStruct;
+use serde::ser::Serializer;
+
+use ColumnInfo;
+use ObjectType;
+use ObjectTypeAttr;
+use OracleNumber;
+use OracleType;
+use Row;
+use SqlValue;
+
+//
+// OracleType
+//
+
+/// Serialized as a type name such as `NUMBER(10,2)`
+impl Serialize for OracleType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+//
+// OracleNumber
+//
+
+/// Serialized as a decimal string such as `12345.678` not to lose precision
+impl Serialize for OracleNumber {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+//
+// ColumnInfo
+//
+
+impl Serialize for ColumnInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut s = serializer.serialize_struct("ColumnInfo", 8)?;
+        s.serialize_field("name", self.name())?;
+        s.serialize_field("oracle_type", self.oracle_type())?;
+        s.serialize_field("nullable", &self.nullable())?;
+        s.serialize_field("precision", &self.precision())?;
+        s.serialize_field("scale", &self.scale())?;
+        s.serialize_field("size_in_bytes", &self.size_in_bytes())?;
+        s.serialize_field("size_in_chars", &self.size_in_chars())?;
+        s.serialize_field("is_national", &self.is_national())?;
+        s.end()
+    }
+}
+
+//
+// ObjectType
+//
+
+impl Serialize for ObjectType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut s = serializer.serialize_struct("ObjectType", 4)?;
+        s.serialize_field("schema", self.schema())?;
+        s.serialize_field("name", self.name())?;
+        s.serialize_field("element_oracle_type", &self.element_oracle_type())?;
+        s.serialize_field("attributes", self.attributes())?;
+        s.end()
+    }
+}
+
+//
+// ObjectTypeAttr
+//
+
+impl Serialize for ObjectTypeAttr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut s = serializer.serialize_struct("ObjectTypeAttr", 2)?;
+        s.serialize_field("name", self.name())?;
+        s.serialize_field("oracle_type", self.oracle_type())?;
+        s.end()
+    }
+}
+
+//
+// SqlValue
+//
+
+/// Serialized as a number, a string, a boolean or none depending on the
+/// Oracle type.
+///
+/// | Oracle Type | Serialized as |
+/// | --- | --- |
+/// | NUMBER(p, s) (p - s ≤ 18 and s ≤ 0) | i64 |
+/// | NUMBER(p, s) (p ≤ 15), FLOAT(p) (p ≤ 53), BINARY_FLOAT, BINARY_DOUBLE | f64 |
+/// | other NUMBER and FLOAT | string not to lose precision |
+/// | BOOLEAN | bool |
+/// | NULL | none |
+/// | others | string converted by `as_string()` |
+impl Serialize for SqlValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        if self.is_null().map_err(S::Error::custom)? {
+            return serializer.serialize_none();
+        }
+        match *self.oracle_type().map_err(S::Error::custom)? {
+            OracleType::Number(prec, scale)
+                if prec > 0 && scale <= 0 && prec as i32 - scale as i32 <= 18 =>
+                serializer.serialize_i64(self.as_i64().map_err(S::Error::custom)?),
+            OracleType::Number(prec, _) if prec > 0 && prec <= 15 =>
+                serializer.serialize_f64(self.as_f64().map_err(S::Error::custom)?),
+            OracleType::Float(prec) if prec > 0 && prec <= 53 =>
+                serializer.serialize_f64(self.as_f64().map_err(S::Error::custom)?),
+            OracleType::BinaryFloat |
+            OracleType::BinaryDouble =>
+                serializer.serialize_f64(self.as_f64().map_err(S::Error::custom)?),
+            OracleType::Int64 =>
+                serializer.serialize_i64(self.as_i64().map_err(S::Error::custom)?),
+            OracleType::UInt64 =>
+                serializer.serialize_u64(self.as_u64().map_err(S::Error::custom)?),
+            OracleType::Boolean =>
+                serializer.serialize_bool(self.as_bool().map_err(S::Error::custom)?),
+            _ =>
+                serializer.serialize_str(&self.as_string().map_err(S::Error::custom)?),
+        }
+    }
+}
+
+//
+// Row
+//
+
+/// Serialized as a map keyed by column names. Duplicated column names are
+/// suffixed as [Statement.column_names][].
+///
+/// [Statement.column_names]: struct.Statement.html#method.column_names
+impl Serialize for Row {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let iter = self.iter();
+        let mut map = serializer.serialize_map(Some(iter.len()))?;
+        for (name, val) in iter {
+            map.serialize_entry(name, val)?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,255 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::io::Write;
+
+use serde_json;
+use serde_json::Value;
+
+use Collection;
+use Error;
+use FromSql;
+use Object;
+use OracleType;
+use Result;
+use SqlValue;
+use Statement;
+use ToSqlNull;
+use ToSql;
+use types::MAX_STRING_SIZE;
+
+//
+// serde_json::Value
+//
+
+/// Parses JSON text in character data types and CLOB.
+///
+/// Native JSON data type (OSON) of Oracle 21c isn't supported because
+/// the bundled ODPI-C has no `dpiJson` API. Use
+/// `JSON_SERIALIZE(col RETURNING CLOB)` to fetch native JSON columns.
+impl FromSql for Value {
+    fn from_sql(val: &SqlValue) -> Result<Value> {
+        let s = val.as_string()?;
+        serde_json::from_str(&s).map_err(|err| Error::ParseError(Box::new(err)))
+    }
+}
+
+impl ToSqlNull for Value {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::NVarchar2(0))
+    }
+}
+
+/// Binds JSON text as NVARCHAR2, or CLOB when it may be longer than 32767 bytes.
+///
+/// The value is serialized only once, in `to_sql()`. The bind type is chosen
+/// from an upper bound of the length of the JSON text. Therefore text a
+/// little shorter than 32767 bytes may be bound as CLOB.
+///
+/// This binds JSON text, not native JSON data type (OSON) of Oracle 21c.
+/// Oracle converts it implicitly when it is inserted into a JSON column.
+impl ToSql for Value {
+    fn oratype(&self) -> Result<OracleType> {
+        let len = max_json_len(self);
+        if len <= MAX_STRING_SIZE {
+            Ok(OracleType::NVarchar2(len as u32))
+        } else {
+            Ok(OracleType::CLOB)
+        }
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        val.set_string(&self.to_string())
+    }
+}
+
+// Upper bound of the length of compact JSON text of `val`.
+// Numbers are counted as 24 bytes, which is enough for i64, u64 and f64.
+fn max_json_len(val: &Value) -> usize {
+    match *val {
+        Value::Null => 4,
+        Value::Bool(b) => if b { 4 } else { 5 },
+        Value::Number(_) => 24,
+        Value::String(ref s) => max_json_str_len(s),
+        Value::Array(ref vals) =>
+            2 + vals.len() + vals.iter().map(max_json_len).sum::<usize>(),
+        Value::Object(ref map) =>
+            2 + map.len() * 2
+            + map.iter().map(|(k, v)| max_json_str_len(k) + max_json_len(v)).sum::<usize>(),
+    }
+}
+
+fn max_json_str_len(s: &str) -> usize {
+    2 + s.bytes().map(|b| match b {
+        b'"' | b'\\' => 2,
+        b if b < 0x20 => 6,
+        _ => 1,
+    }).sum::<usize>()
+}
+
+//
+// Object and Collection
+//
+
+// JSON representation of a SQL value, which recurses into objects and
+// collections instead of converting them to their literal strings.
+struct JsonValue(Value);
+
+impl FromSql for JsonValue {
+    fn from_sql(val: &SqlValue) -> Result<JsonValue> {
+        if val.is_null()? {
+            return Ok(JsonValue(Value::Null));
+        }
+        let value = match *val.oracle_type()? {
+            OracleType::Object(ref objtype) =>
+                if objtype.is_collection() {
+                    val.as_collection()?.to_json()?
+                } else {
+                    val.as_object()?.to_json()?
+                },
+            _ => serde_json::to_value(val).map_err(|err| Error::ParseError(Box::new(err)))?,
+        };
+        Ok(JsonValue(value))
+    }
+}
+
+impl Object {
+    /// Converts the object to a JSON object keyed by attribute names.
+    ///
+    /// Attribute values are converted as described in
+    /// [Serialize for SqlValue][]. Attributes of object types are
+    /// converted to nested JSON objects or arrays.
+    ///
+    /// [Serialize for SqlValue]: struct.SqlValue.html#impl-Serialize
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select sdo_point_type(1, 2, null) from dual", &[]).unwrap();
+    /// let obj: oracle::Object = stmt.fetch().unwrap().get(0).unwrap();
+    /// // NUMBER without precision is converted to a string.
+    /// assert_eq!(obj.to_json().unwrap().to_string(), r#"{"X":"1","Y":"2","Z":null}"#);
+    /// ```
+    pub fn to_json(&self) -> Result<Value> {
+        let mut map = serde_json::Map::new();
+        for attr in self.object_type().attributes() {
+            let val: JsonValue = self.get_by_attr(attr)?;
+            map.insert(attr.name().to_string(), val.0);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+impl Collection {
+    /// Converts the collection to a JSON array of its elements in index order.
+    ///
+    /// Elements are converted in the same way as attributes by
+    /// [Object.to_json()][].
+    ///
+    /// [Object.to_json()]: struct.Object.html#method.to_json
+    pub fn to_json(&self) -> Result<Value> {
+        let vals = self.iter::<JsonValue>()
+            .map(|val| val.map(|v| v.0))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Value::Array(vals))
+    }
+}
+
+//
+// Statement
+//
+
+impl<'conn> Statement<'conn> {
+    /// Fetches the remaining rows and writes them to `writer` as
+    /// [JSON Lines][], one JSON object keyed by column names per row.
+    /// This returns the number of written rows.
+    ///
+    /// Column values are converted as described in [Serialize for SqlValue][].
+    /// NUMBER columns are written as JSON numbers when their precisions
+    /// fit in `i64` or `f64`. Otherwise, they are written as strings.
+    ///
+    /// [JSON Lines]: http://jsonlines.org/
+    /// [Serialize for SqlValue]: struct.SqlValue.html#impl-Serialize
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+    /// let mut stmt = conn.execute("select empno, ename from emp", &[]).unwrap();
+    /// let stdout = std::io::stdout();
+    /// stmt.to_json_lines(&mut stdout.lock()).unwrap();
+    /// // {"EMPNO":7369,"ENAME":"SMITH"}
+    /// // {"EMPNO":7499,"ENAME":"ALLEN"}
+    /// // ...
+    /// ```
+    pub fn to_json_lines<W>(&mut self, writer: &mut W) -> Result<u64> where W: Write {
+        let mut num_rows = 0;
+        loop {
+            match self.fetch() {
+                Ok(row) => {
+                    serde_json::to_writer(&mut *writer, row).map_err(|err| {
+                        if err.is_io() {
+                            Error::IoError(err.into())
+                        } else {
+                            Error::ParseError(Box::new(err))
+                        }
+                    })?;
+                    writer.write_all(b"\n")?;
+                    num_rows += 1;
+                },
+                Err(Error::NoMoreData) => return Ok(num_rows),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_json_len_is_upper_bound() {
+        let texts = [
+            r#"null"#,
+            r#"false"#,
+            r#"[1,-9223372036854775808,18446744073709551615,-1.7976931348623157e308,1e-300]"#,
+            r#"{"key":"quote\" backslash\\ newline\n ctrl\u0001","":[true,{},[]]}"#,
+            r#""日本語""#,
+        ];
+        for text in texts.iter() {
+            let val: Value = serde_json::from_str(text).unwrap();
+            assert!(val.to_string().len() <= max_json_len(&val), "{}", text);
+        }
+    }
+}
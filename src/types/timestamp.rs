@@ -32,21 +32,31 @@
 
 use std::cmp;
 use std::fmt;
+use std::ops;
+use std::result;
 use std::str;
 
 use binding::dpiTimestamp;
 use util::Scanner;
+use Error;
+use IntervalDS;
 use OracleType;
 use ParseOracleTypeError;
+use Result;
 
 /// [Datetime][] data type
 ///
 /// [Datetime]: https://docs.oracle.com/database/122/NLSPG/datetime-data-types-and-time-zone-support.htm#NLSPG-GUID-3A1B7AC6-2EDB-4DDC-9C9D-223D4C72AC74
 ///
-/// This struct doesn't have arithmetic methods and they won't be added to avoid
-/// reinventing the wheel. If you need methods such as adding an interval to a
-/// timestamp, enable `chrono` feature and use [chrono::Date][], [chrono::DateTime][],
-/// [chrono::naive::NaiveDate][] or [chrono::naive::NaiveDateTime][] instead.
+/// This struct has only simple arithmetic: conversion from and to Unix time
+/// and addition and subtraction of [IntervalDS][]. Dates before 1582-10-15
+/// are in the Julian calendar and year -1 is 1 BC as Oracle does. `+` and
+/// `-` panic when the result is out of range of Oracle timestamps. Use
+/// `checked_` methods not to panic. If you need more, enable `chrono` feature and use
+/// [chrono::Date][], [chrono::DateTime][], [chrono::naive::NaiveDate][] or
+/// [chrono::naive::NaiveDateTime][] instead.
+///
+/// [IntervalDS]: struct.IntervalDS.html
 ///
 /// [chrono::Date]: https://docs.rs/chrono/0.4/chrono/struct.Date.html
 /// [chrono::DateTime]: https://docs.rs/chrono/0.4/chrono/struct.DateTime.html
@@ -86,6 +96,29 @@ use ParseOracleTypeError;
 /// assert_eq!(ts4.precision(), 3);
 /// ```
 ///
+/// Convert from and to Unix time and add an interval.
+///
+/// ```
+/// use oracle::{IntervalDS, Timestamp};
+///
+/// let ts = Timestamp::from_unix_timestamp(1502277753, 500000000);
+/// assert_eq!(ts.to_string(), "2017-08-09 11:22:33.500000000");
+///
+/// // The time zone offset is taken into account.
+/// let ts2 = ts.and_tz_hm_offset(-8, 0);
+/// assert_eq!(ts2.unix_timestamp(), 1502277753 + 8 * 3600);
+///
+/// // Add an interval.
+/// let ts3 = ts + IntervalDS::new(1, 2, 3, 4, 500000000);
+/// assert_eq!(ts3.to_string(), "2017-08-10 13:25:38.000000000");
+/// ```
+///
+/// Values of `TIMESTAMP WITH LOCAL TIME ZONE` are fetched in the session
+/// time zone with its offset. Use [Connection.set_session_time_zone][] to
+/// fix it regardless of the client environment.
+///
+/// [Connection.set_session_time_zone]: struct.Connection.html#method.set_session_time_zone
+///
 /// Fetch and bind interval values.
 ///
 /// ```
@@ -281,6 +314,167 @@ impl Timestamp {
     pub fn tz_offset(&self) -> i32 {
         self.tz_hour_offset * 3600 + self.tz_minute_offset * 60
     }
+
+    /// Creates a timestamp without time zone from seconds and nanoseconds
+    /// since 1970-01-01 00:00:00 UTC.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nanosecond` is not less than 1,000,000,000 or the
+    /// result is out of range of Oracle timestamps. Use
+    /// [checked_from_unix_timestamp](#method.checked_from_unix_timestamp)
+    /// not to panic.
+    pub fn from_unix_timestamp(secs: i64, nanosecond: u32) -> Timestamp {
+        match Timestamp::checked_from_unix_timestamp(secs, nanosecond) {
+            Ok(ts) => ts,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates a timestamp without time zone from seconds and nanoseconds
+    /// since 1970-01-01 00:00:00 UTC. This returns `Error::Overflow` when
+    /// `nanosecond` is not less than 1,000,000,000 or the result is out of
+    /// range of Oracle timestamps.
+    pub fn checked_from_unix_timestamp(secs: i64, nanosecond: u32) -> Result<Timestamp> {
+        if nanosecond >= 1_000_000_000 {
+            return Err(Error::Overflow(nanosecond.to_string(), "nanosecond"));
+        }
+        Timestamp::from_epoch_secs(secs, nanosecond)
+            .ok_or_else(|| Error::Overflow(format!("{}.{:09}", secs, nanosecond), "Timestamp"))
+    }
+
+    /// Returns seconds since 1970-01-01 00:00:00 UTC. The timestamp is
+    /// regarded as UTC when it has no time zone.
+    pub fn unix_timestamp(&self) -> i64 {
+        self.epoch_secs() - self.tz_offset() as i64
+    }
+
+    /// Returns nanoseconds since 1970-01-01 00:00:00 UTC. The timestamp is
+    /// regarded as UTC when it has no time zone.
+    pub fn unix_timestamp_nanos(&self) -> i128 {
+        self.unix_timestamp() as i128 * 1_000_000_000 + self.nanosecond as i128
+    }
+
+    /// Adds an interval. This returns `Error::Overflow` when the result is
+    /// out of range of Oracle timestamps. The time zone and precision are kept.
+    pub fn checked_add_interval_ds(&self, it: &IntervalDS) -> Result<Timestamp> {
+        self.add_interval_ds(it)
+            .ok_or_else(|| Error::Overflow(format!("{} + {}", self, it), "Timestamp"))
+    }
+
+    /// Subtracts an interval. This returns `Error::Overflow` when the result
+    /// is out of range of Oracle timestamps. The time zone and precision are kept.
+    pub fn checked_sub_interval_ds(&self, it: &IntervalDS) -> Result<Timestamp> {
+        let neg = IntervalDS::new(-it.days(), -it.hours(), -it.minutes(),
+                                  -it.seconds(), -it.nanoseconds());
+        self.add_interval_ds(&neg)
+            .ok_or_else(|| Error::Overflow(format!("{} - {}", self, it), "Timestamp"))
+    }
+
+    fn add_interval_ds(&self, it: &IntervalDS) -> Option<Timestamp> {
+        let nsecs = self.nanosecond as i64 + it.nanoseconds() as i64;
+        let secs = self.epoch_secs()
+            + it.days() as i64 * 86400 + it.hours() as i64 * 3600
+            + it.minutes() as i64 * 60 + it.seconds() as i64
+            + nsecs.div_euclid(1_000_000_000);
+        let ts = Timestamp::from_epoch_secs(secs, nsecs.rem_euclid(1_000_000_000) as u32)?;
+        Some(Timestamp {
+            tz_hour_offset: self.tz_hour_offset,
+            tz_minute_offset: self.tz_minute_offset,
+            precision: self.precision,
+            with_tz: self.with_tz,
+            .. ts
+        })
+    }
+
+    // Seconds since 1970-01-01 00:00:00 in the local time of the timestamp.
+    fn epoch_secs(&self) -> i64 {
+        days_from_civil(self.year as i64, self.month as i64, self.day as i64) * 86400
+            + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
+
+    fn from_epoch_secs(secs: i64, nanosecond: u32) -> Option<Timestamp> {
+        let (year, month, day) = civil_from_days(secs.div_euclid(86400))?;
+        let secs = secs.rem_euclid(86400);
+        Some(Timestamp::new(year as i32, month as u32, day as u32,
+                            (secs / 3600) as u32, (secs % 3600 / 60) as u32, (secs % 60) as u32,
+                            nanosecond))
+    }
+}
+
+// Julian day numbers of 1970-01-01, 1582-10-15, when the Gregorian
+// calendar started, and the minimum and maximum dates of Oracle.
+const JDN_UNIX_EPOCH: i64 = 2440588;
+const JDN_GREGORIAN_START: i64 = 2299161;
+const JDN_MIN: i64 = 366; // -4712-01-01 (4712 BC)
+const JDN_MAX: i64 = 5373484; // 9999-12-31
+
+// Days since 1970-01-01. Dates before 1582-10-15 are in the Julian calendar
+// and there is no year 0 as in Oracle.
+// See https://en.wikipedia.org/wiki/Julian_day#Converting_Gregorian_calendar_date_to_Julian_Day_Number
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // astronomical year numbering, where 1 BC is year 0
+    let year = if year < 0 { year + 1 } else { year };
+    let a = (14 - month) / 12;
+    let y = year + 4800 - a;
+    let m = month + 12 * a - 3;
+    let jdn = if (year, month, day) >= (1582, 10, 15) {
+        day + (153 * m + 2) / 5 + 365 * y + y / 4 - y / 100 + y / 400 - 32045
+    } else {
+        day + (153 * m + 2) / 5 + 365 * y + y / 4 - 32083
+    };
+    jdn - JDN_UNIX_EPOCH
+}
+
+// The inverse of days_from_civil. This returns `None` when the date is out
+// of range of Oracle dates.
+fn civil_from_days(days: i64) -> Option<(i64, i64, i64)> {
+    let jdn = days + JDN_UNIX_EPOCH;
+    if jdn < JDN_MIN || JDN_MAX < jdn {
+        return None;
+    }
+    let (b, c) = if jdn >= JDN_GREGORIAN_START {
+        let a = jdn + 32044;
+        let b = (4 * a + 3) / 146097;
+        (b, a - 146097 * b / 4)
+    } else {
+        (0, jdn + 32082)
+    };
+    let d = (4 * c + 3) / 1461;
+    let e = c - 1461 * d / 4;
+    let m = (5 * e + 2) / 153;
+    let day = e - (153 * m + 2) / 5 + 1;
+    let month = m + 3 - 12 * (m / 10);
+    let year = 100 * b + d - 4800 + m / 10;
+    Some((if year <= 0 { year - 1 } else { year }, month, day))
+}
+
+impl ops::Add<IntervalDS> for Timestamp {
+    type Output = Timestamp;
+
+    /// # Panics
+    ///
+    /// Panics when the result is out of range of Oracle timestamps.
+    fn add(self, rhs: IntervalDS) -> Timestamp {
+        match self.checked_add_interval_ds(&rhs) {
+            Ok(ts) => ts,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl ops::Sub<IntervalDS> for Timestamp {
+    type Output = Timestamp;
+
+    /// # Panics
+    ///
+    /// Panics when the result is out of range of Oracle timestamps.
+    fn sub(self, rhs: IntervalDS) -> Timestamp {
+        match self.checked_sub_interval_ds(&rhs) {
+            Ok(ts) => ts,
+            Err(err) => panic!("{}", err),
+        }
+    }
 }
 
 impl cmp::PartialEq for Timestamp {
@@ -328,7 +522,7 @@ impl fmt::Display for Timestamp {
 impl str::FromStr for Timestamp {
     type Err = ParseOracleTypeError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
         let err = || ParseOracleTypeError::new("Timestamp");
         let mut s = Scanner::new(s);
         let minus = if let Some('-') = s.char() {
@@ -560,4 +754,66 @@ mod tests {
         ts.tz_minute_offset = 45;
         assert_eq!("-123-03-04 05:06:07.123 +00:45".parse(), Ok(ts));
     }
+
+    #[test]
+    fn unix_timestamp() {
+        let ts = Timestamp::from_unix_timestamp(0, 0);
+        assert_eq!(ts, Timestamp::new(1970, 1, 1, 0, 0, 0, 0));
+        let ts = Timestamp::from_unix_timestamp(951782400, 1);
+        assert_eq!(ts, Timestamp::new(2000, 2, 29, 0, 0, 0, 1));
+        assert_eq!(ts.unix_timestamp(), 951782400);
+        assert_eq!(ts.unix_timestamp_nanos(), 951782400_000_000_001);
+        let ts = Timestamp::from_unix_timestamp(-1, 0);
+        assert_eq!(ts, Timestamp::new(1969, 12, 31, 23, 59, 59, 0));
+        let ts = Timestamp::new(-4712, 1, 1, 0, 0, 0, 0);
+        assert_eq!(Timestamp::from_unix_timestamp(ts.unix_timestamp(), 0), ts);
+        let ts = Timestamp::new(9999, 12, 31, 23, 59, 59, 999999999);
+        assert_eq!(Timestamp::from_unix_timestamp(ts.unix_timestamp(), 999999999), ts);
+        let ts = Timestamp::new(1970, 1, 1, 9, 0, 0, 0).and_tz_hm_offset(9, 0);
+        assert_eq!(ts.unix_timestamp(), 0);
+        let ts = Timestamp::new(1969, 12, 31, 15, 30, 0, 0).and_tz_hm_offset(-8, -30);
+        assert_eq!(ts.unix_timestamp(), 0);
+
+        // the minimum date and the start of the Gregorian calendar
+        assert_eq!(Timestamp::new(-4712, 1, 1, 0, 0, 0, 0).unix_timestamp(), (366 - 2440588) * 86400);
+        assert_eq!(Timestamp::new(1582, 10, 15, 0, 0, 0, 0).unix_timestamp(), -12219292800);
+        assert_eq!(Timestamp::new(1582, 10, 4, 0, 0, 0, 0).unix_timestamp(), -12219379200);
+        assert_eq!(Timestamp::from_unix_timestamp(-12219379200, 0), Timestamp::new(1582, 10, 4, 0, 0, 0, 0));
+        // 1 BC is a leap year in the Julian calendar.
+        let ts = Timestamp::new(-1, 2, 29, 0, 0, 0, 0);
+        assert_eq!(Timestamp::from_unix_timestamp(ts.unix_timestamp(), 0), ts);
+
+        assert!(Timestamp::checked_from_unix_timestamp(0, 1_000_000_000).is_err());
+        assert!(Timestamp::checked_from_unix_timestamp((366 - 2440588) * 86400 - 1, 0).is_err());
+        assert!(Timestamp::checked_from_unix_timestamp(253402300800, 0).is_err());
+    }
+
+    #[test]
+    fn add_sub_interval_ds() {
+        let ts = Timestamp::new(2016, 2, 28, 23, 59, 59, 900000000).and_tz_hm_offset(9, 0).and_prec(3);
+        let it = IntervalDS::new(0, 0, 0, 0, 200000000);
+        let ts2 = ts + it;
+        assert_eq!(ts2, Timestamp::new(2016, 2, 29, 0, 0, 0, 100000000).and_tz_hm_offset(9, 0));
+        assert_eq!(ts2.precision(), 3);
+        assert_eq!(ts2 - it, ts);
+        let it = IntervalDS::new(-1, -2, -3, -4, -500000000);
+        assert_eq!(ts + it, Timestamp::new(2016, 2, 27, 21, 56, 55, 400000000).and_tz_hm_offset(9, 0));
+        let ts = Timestamp::new(9999, 12, 31, 0, 0, 0, 0);
+        assert!(ts.checked_add_interval_ds(&IntervalDS::new(1, 0, 0, 0, 0)).is_err());
+        let ts = Timestamp::new(-4712, 1, 1, 0, 0, 0, 0);
+        assert!(ts.checked_sub_interval_ds(&IntervalDS::new(0, 0, 0, 0, 1)).is_err());
+
+        // The Julian calendar before 1582-10-15 and no year 0
+        let day = IntervalDS::new(1, 0, 0, 0, 0);
+        assert_eq!(Timestamp::new(1582, 10, 4, 0, 0, 0, 0) + day, Timestamp::new(1582, 10, 15, 0, 0, 0, 0));
+        assert_eq!(Timestamp::new(1582, 10, 15, 0, 0, 0, 0) - day, Timestamp::new(1582, 10, 4, 0, 0, 0, 0));
+        assert_eq!(Timestamp::new(1500, 2, 28, 0, 0, 0, 0) + day, Timestamp::new(1500, 2, 29, 0, 0, 0, 0));
+        assert_eq!(Timestamp::new(-1, 12, 31, 0, 0, 0, 0) + day, Timestamp::new(1, 1, 1, 0, 0, 0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_interval_ds_overflow() {
+        let _ = Timestamp::new(9999, 12, 31, 0, 0, 0, 0) + IntervalDS::new(1, 0, 0, 0, 0);
+    }
 }
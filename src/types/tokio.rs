@@ -0,0 +1,288 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use std::cmp;
+use std::future::Future;
+use std::io;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::task::{self, JoinHandle};
+
+use Blob;
+use Clob;
+use Connection;
+
+/// A LOB type used by [AsyncLob][] (`tokio` feature)
+///
+/// This is implemented by [Clob][] and [Blob][] and cannot be implemented
+/// outside of this crate.
+///
+/// [AsyncLob]: struct.AsyncLob.html
+/// [Clob]: struct.Clob.html
+/// [Blob]: struct.Blob.html
+pub trait AsyncLobType: Read + Write + Unpin + 'static + private::Sealed {}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for ::Blob {}
+    impl Sealed for ::Clob {}
+}
+
+impl AsyncLobType for Blob {}
+impl AsyncLobType for Clob {}
+
+// A LOB moved to a blocking thread.
+struct SendLob<L>(L);
+
+// SAFETY: Connections are created with DPI_MODE_CREATE_THREADED, set when
+// Context is initialized in lib.rs, so OCI runs in threaded mode. OCI
+// handles may then be used by any thread as long as they aren't used
+// concurrently. AsyncLob holds an exclusive borrow of the connection, so
+// the connection isn't used by the owner thread while the LOB is used by
+// a blocking thread, and it waits for the blocking thread when dropped.
+// This is limited to Clob and Blob because other types may hold
+// thread-bound data.
+unsafe impl<L> Send for SendLob<L> where L: AsyncLobType {}
+
+enum Op {
+    Read(io::Result<Vec<u8>>),
+    Write(io::Result<usize>),
+}
+
+/// Asynchronous reader and writer of [Clob][] or [Blob][] (`tokio` feature)
+///
+/// This implements tokio's `AsyncRead` and `AsyncWrite`. Each read and
+/// write runs in a thread by `tokio::task::spawn_blocking` so that the
+/// executor isn't blocked while waiting for the server. As `tokio::fs::File`,
+/// `poll_write` returns before the data is written. Call `flush().await`
+/// to wait for it and get errors.
+///
+/// `AsyncLob` borrows the connection which the LOB belongs to mutably
+/// so that the connection isn't used while the LOB is used by another
+/// thread. When `AsyncLob` is dropped during an operation, the drop waits
+/// for the operation to finish.
+///
+/// # Examples
+///
+/// ```no_run
+/// # extern crate oracle;
+/// # extern crate tokio;
+/// use oracle::{AsyncLob, Blob};
+///
+/// # fn main() {
+/// let mut conn = oracle::Connection::new("scott", "tiger", "").unwrap();
+/// let blob: Blob = {
+///     let mut stmt = conn.execute("select image from photos where id = 1", &[]).unwrap();
+///     stmt.fetch().unwrap().get(0).unwrap()
+/// };
+/// let mut reader = AsyncLob::new(&mut conn, blob);
+/// let mut out = tokio::io::sink();
+/// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// rt.block_on(tokio::io::copy(&mut reader, &mut out)).unwrap();
+/// # }
+/// ```
+///
+/// [Clob]: struct.Clob.html
+/// [Blob]: struct.Blob.html
+pub struct AsyncLob<'conn, L> {
+    lob: Option<L>,
+    task: Option<JoinHandle<(SendLob<L>, Op)>>,
+    // disconnected when the running task finishes or is dropped
+    task_done: Option<mpsc::Receiver<()>>,
+    // data read by a canceled read, returned by the next read
+    read_buf: Vec<u8>,
+    // true when the last read reached the end of data
+    eof: bool,
+    // an error of a write which hasn't been reported yet
+    write_err: Option<io::Error>,
+    conn: PhantomData<&'conn mut Connection>,
+}
+
+impl<'conn, L> AsyncLob<'conn, L> where L: AsyncLobType {
+    /// Creates an asynchronous reader and writer of `lob`, which
+    /// belongs to `conn`.
+    pub fn new(_conn: &'conn mut Connection, lob: L) -> AsyncLob<'conn, L> {
+        AsyncLob {
+            lob: Some(lob),
+            task: None,
+            task_done: None,
+            read_buf: Vec::new(),
+            eof: false,
+            write_err: None,
+            conn: PhantomData,
+        }
+    }
+
+    /// Returns the LOB. This returns `None` while a read or write is in
+    /// progress. Call `flush().await` beforehand to wait for writes.
+    ///
+    /// This also returns `None` after a read or write panics in its
+    /// blocking thread. The LOB is dropped with the thread in that case
+    /// and cannot be recovered. Reads and writes after that fail.
+    pub fn into_inner(mut self) -> Option<L> {
+        self.lob.take()
+    }
+
+    // Waits for the running task and keeps its result.
+    fn poll_task(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        let (lob, op) = match self.task {
+            Some(ref mut task) => match Pin::new(task).poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(result)) => result,
+                Poll::Ready(Err(err)) => {
+                    self.task = None;
+                    self.task_done = None;
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+                },
+            },
+            None => return Poll::Ready(Ok(())),
+        };
+        self.task = None;
+        self.task_done = None;
+        self.lob = Some(lob.0);
+        match op {
+            Op::Read(Ok(data)) => {
+                self.eof = data.is_empty();
+                self.read_buf = data;
+            },
+            Op::Read(Err(err)) => return Poll::Ready(Err(err)),
+            Op::Write(Ok(_)) => (),
+            Op::Write(Err(err)) => self.write_err = Some(err),
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn take_lob(&mut self) -> io::Result<SendLob<L>> {
+        match self.lob.take() {
+            Some(lob) => Ok(SendLob(lob)),
+            None => Err(io::Error::new(io::ErrorKind::Other, "LOB was lost by a failed operation")),
+        }
+    }
+
+    fn spawn<F>(&mut self, f: F) where F: FnOnce() -> (SendLob<L>, Op) + Send + 'static {
+        let (tx, rx) = mpsc::channel::<()>();
+        self.task = Some(task::spawn_blocking(move || {
+            let _tx = tx;
+            f()
+        }));
+        self.task_done = Some(rx);
+    }
+
+    fn take_write_err(&mut self) -> io::Result<()> {
+        match self.write_err.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'conn, L> AsyncRead for AsyncLob<'conn, L> where L: AsyncLobType {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        loop {
+            if this.poll_task(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+            this.take_write_err()?;
+            if !this.read_buf.is_empty() {
+                let len = cmp::min(buf.remaining(), this.read_buf.len());
+                buf.put_slice(&this.read_buf[..len]);
+                this.read_buf.drain(..len);
+                return Poll::Ready(Ok(()));
+            }
+            if this.eof {
+                this.eof = false;
+                return Poll::Ready(Ok(()));
+            }
+            let mut lob = this.take_lob()?;
+            let len = buf.remaining();
+            this.spawn(move || {
+                let mut data = vec![0u8; len];
+                let result = lob.0.read(&mut data).map(|n| {
+                    data.truncate(n);
+                    data
+                });
+                (lob, Op::Read(result))
+            });
+        }
+    }
+}
+
+impl<'conn, L> AsyncWrite for AsyncLob<'conn, L> where L: AsyncLobType {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.poll_task(cx)?.is_pending() {
+            return Poll::Pending;
+        }
+        this.take_write_err()?;
+        let mut lob = this.take_lob()?;
+        let data = buf.to_vec();
+        this.spawn(move || {
+            let result = lob.0.write_all(&data).map(|_| data.len());
+            (lob, Op::Write(result))
+        });
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.poll_task(cx)?.is_pending() {
+            return Poll::Pending;
+        }
+        this.take_write_err()?;
+        match this.lob {
+            Some(ref mut lob) => Poll::Ready(lob.flush()),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<'conn, L> Drop for AsyncLob<'conn, L> {
+    fn drop(&mut self) {
+        // Wait for the blocking thread before the borrow of the connection ends.
+        if let Some(ref rx) = self.task_done {
+            let _ = rx.recv();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+use uuid::Uuid;
+
+use Error;
+use FromSql;
+use OracleType;
+use Result;
+use SqlValue;
+use ToSqlNull;
+use ToSql;
+
+//
+// uuid::Uuid
+//
+
+/// Converts RAW(16) or UUID text such as `67e55044-10b1-426f-9247-bb680e5fe0c8`
+/// in character data types to Uuid.
+impl FromSql for Uuid {
+    fn from_sql(val: &SqlValue) -> Result<Uuid> {
+        match *val.oracle_type()? {
+            OracleType::Raw(_) |
+            OracleType::LongRaw => {
+                let bytes = val.as_bytes()?;
+                Uuid::from_slice(&bytes).map_err(|err| Error::ParseError(Box::new(err)))
+            },
+            _ => {
+                let s = val.as_string()?;
+                Uuid::parse_str(s.trim()).map_err(|err| Error::ParseError(Box::new(err)))
+            },
+        }
+    }
+}
+
+impl ToSqlNull for Uuid {
+    fn oratype_for_null() -> Result<OracleType> {
+        Ok(OracleType::Raw(16))
+    }
+}
+
+/// Binds Uuid as RAW(16). When it is bound as a character data type
+/// such as `(&uuid, &OracleType::Varchar2(36))`, it is set in the
+/// hyphenated lower-case form.
+impl ToSql for Uuid {
+    fn oratype(&self) -> Result<OracleType> {
+        Ok(OracleType::Raw(16))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+        match *val.oracle_type()? {
+            OracleType::Raw(_) |
+            OracleType::LongRaw =>
+                val.set_bytes(self.as_bytes()),
+            _ =>
+                val.set_string(&self.hyphenated().to_string()),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+// Conversion between Oracle 23ai VECTOR in text form and `Vec<f32>`,
+// `Vec<f64>` and `Vec<i8>`.
+//
+// The bundled ODPI-C doesn't support VECTOR data type. It cannot even
+// describe VECTOR columns, so they must be converted to text such as
+// `[1.0E+000,2.5E+000]` by `FROM_VECTOR()` in queries. Oracle converts
+// text to VECTOR implicitly on insert. For the same reason `ColumnInfo`
+// has no dimension metadata of VECTOR columns. Use
+// `VECTOR_DIMENSION_COUNT()` in queries to get it.
+
+use std::fmt::Write;
+
+use Error;
+use OracleType;
+use ParseOracleTypeError;
+use Result;
+use SqlValue;
+use FromSql;
+use ToSqlNull;
+use ToSql;
+use types::MAX_STRING_SIZE;
+
+fn parse_vector<T>(s: &str) -> Result<Vec<T>> where T: ::std::str::FromStr {
+    let err = || ParseOracleTypeError::new("VECTOR");
+    let s = s.trim();
+    if !s.starts_with('[') || !s.ends_with(']') {
+        return Err(err().into());
+    }
+    let s = s[1..s.len() - 1].trim();
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    s.split(',').map(|elem| elem.trim().parse().map_err(|_| err().into())).collect()
+}
+
+// Element types of VECTOR
+trait VectorElem: ::std::fmt::Display {
+    // VECTOR cannot hold NaN and infinity.
+    fn is_finite(&self) -> bool;
+}
+
+impl VectorElem for f32 {
+    fn is_finite(&self) -> bool {
+        f32::is_finite(*self)
+    }
+}
+
+impl VectorElem for f64 {
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+}
+
+impl VectorElem for i8 {
+    fn is_finite(&self) -> bool {
+        true
+    }
+}
+
+// Writes a vector in text form such as `[1,2.5]`.
+fn write_vector<T, W>(w: &mut W, vec: &[T]) -> Result<()> where T: VectorElem, W: Write {
+    let _ = w.write_char('[');
+    for (i, elem) in vec.iter().enumerate() {
+        if !elem.is_finite() {
+            return Err(Error::Overflow(elem.to_string(), "VECTOR"));
+        }
+        if i > 0 {
+            let _ = w.write_char(',');
+        }
+        let _ = write!(w, "{}", elem);
+    }
+    let _ = w.write_char(']');
+    Ok(())
+}
+
+// Counts the length of text written to it without keeping the text.
+struct LenCounter(usize);
+
+impl Write for LenCounter {
+    fn write_str(&mut self, s: &str) -> ::std::fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+fn vector_text_len<T>(vec: &[T]) -> Result<usize> where T: VectorElem {
+    let mut counter = LenCounter(0);
+    write_vector(&mut counter, vec)?;
+    Ok(counter.0)
+}
+
+fn vector_to_string<T>(vec: &[T]) -> Result<String> where T: VectorElem {
+    let mut s = String::with_capacity(vec.len() * 8 + 2);
+    write_vector(&mut s, vec)?;
+    Ok(s)
+}
+
+macro_rules! impl_vector {
+    ($type:ty) => {
+        impl FromSql for Vec<$type> {
+            fn from_sql(val: &SqlValue) -> Result<Vec<$type>> {
+                parse_vector(&val.as_string()?)
+            }
+        }
+
+        impl ToSqlNull for Vec<$type> {
+            fn oratype_for_null() -> Result<OracleType> {
+                Ok(OracleType::Varchar2(0))
+            }
+        }
+
+        impl ToSql for Vec<$type> {
+            fn oratype(&self) -> Result<OracleType> {
+                let len = vector_text_len(self)?;
+                if len <= MAX_STRING_SIZE {
+                    Ok(OracleType::Varchar2(len as u32))
+                } else {
+                    Ok(OracleType::CLOB)
+                }
+            }
+
+            fn to_sql(&self, val: &mut SqlValue) -> Result<()> {
+                val.set_string(&vector_to_string(self)?)
+            }
+        }
+    };
+}
+
+impl_vector!(f32);
+impl_vector!(f64);
+impl_vector!(i8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_vector::<f32>("[1.0E+000,2.5E+000]").unwrap(), vec![1.0f32, 2.5f32]);
+        assert_eq!(parse_vector::<f64>("[ -1.5E-010 , 3 ]").unwrap(), vec![-1.5e-10f64, 3.0f64]);
+        assert_eq!(parse_vector::<i8>("[1,-2,127]").unwrap(), vec![1i8, -2i8, 127i8]);
+        assert_eq!(parse_vector::<f32>("[]").unwrap(), Vec::<f32>::new());
+        assert!(parse_vector::<f32>("1,2").is_err());
+        assert!(parse_vector::<i8>("[1,128]").is_err());
+        assert!(parse_vector::<f64>("[1,,2]").is_err());
+    }
+
+    #[test]
+    fn to_string() {
+        assert_eq!(vector_to_string(&[1.0f32, 2.5f32]).unwrap(), "[1,2.5]");
+        assert_eq!(vector_to_string(&[-1i8, 2i8]).unwrap(), "[-1,2]");
+        assert_eq!(vector_to_string::<f64>(&[]).unwrap(), "[]");
+        assert!(vector_to_string(&[1.0f32, ::std::f32::NAN]).is_err());
+        assert!(vector_to_string(&[::std::f64::INFINITY]).is_err());
+        assert!(vector_to_string(&[::std::f64::NEG_INFINITY]).is_err());
+        assert_eq!(vector_text_len(&[1.0f32, 2.5f32]).unwrap(), 7);
+        assert_eq!(vector_text_len::<f64>(&[]).unwrap(), 2);
+        assert!(vector_text_len(&[::std::f32::NAN]).is_err());
+    }
+}
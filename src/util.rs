@@ -64,11 +64,14 @@ impl<'a> Scanner<'a> {
         self.char
     }
 
+    /// Reads decimal digits. This returns `None` when no digits are found
+    /// or when the number overflows `u64`. All digits are consumed and
+    /// counted by [ndigits](#method.ndigits) in both cases.
     pub fn read_digits(&mut self) -> Option<u64> {
-        let mut num = 0;
+        let mut num: Option<u64> = Some(0);
         self.ndigits = 0;
         loop {
-            num = num * 10 + match self.char {
+            let digit = match self.char {
                 Some('0') =>  0,
                 Some('1') =>  1,
                 Some('2') =>  2,
@@ -81,22 +84,62 @@ impl<'a> Scanner<'a> {
                 Some('9') =>  9,
                 _ => {
                     if self.ndigits > 0 {
-                        return Some(num);
+                        return num;
                     } else {
                         return None;
                     }
                 }
             };
+            num = num.and_then(|n| n.checked_mul(10)).and_then(|n| n.checked_add(digit));
             self.char = self.chars.next();
             self.ndigits += 1;
         }
     }
 
+    /// Skips decimal digits and returns true when one or more digits are found.
+    pub fn skip_digits(&mut self) -> bool {
+        let _ = self.read_digits();
+        self.ndigits > 0
+    }
+
     pub fn ndigits(&self) -> u32 {
         self.ndigits
     }
 }
 
+/// Parses a string as a float. `~` and `-~`, which are text forms of
+/// positive and negative infinity of Oracle NUMBER, are also accepted.
+pub fn parse_float<T>(s: &str) -> Result<T> where T: str::FromStr + From<f32>, Error: From<T::Err> {
+    match s {
+        "~" => Ok(T::from(f32::INFINITY)),
+        "-~" => Ok(T::from(f32::NEG_INFINITY)),
+        _ => Ok(s.parse()?),
+    }
+}
+
+/// Parses the text form of an Oracle NUMBER consisting of an optional
+/// minus sign and at most 18 digits, which always fits in i64.
+///
+/// This returns `None` for other forms such as fractions and exponents.
+/// Callers fall back to `str::parse()` in the case.
+pub fn parse_small_integer(bytes: &[u8]) -> Option<i64> {
+    let (neg, digits) = match bytes.first() {
+        Some(&b'-') => (true, &bytes[1..]),
+        _ => (false, bytes),
+    };
+    if digits.is_empty() || digits.len() > 18 {
+        return None;
+    }
+    let mut num = 0i64;
+    for &b in digits {
+        if b < b'0' || b'9' < b {
+            return None;
+        }
+        num = num * 10 + (b - b'0') as i64;
+    }
+    Some(if neg { -num } else { num })
+}
+
 pub fn check_number_format(s: &str) -> result::Result<(), ParseOracleTypeError> {
     let err = || ParseOracleTypeError::new("Oracle number");
     let mut s = Scanner::new(s);
@@ -107,13 +150,13 @@ pub fn check_number_format(s: &str) -> result::Result<(), ParseOracleTypeError>
     }
 
     // decimal part
-    if s.read_digits().is_none() {
+    if !s.skip_digits() {
         return Err(err());
     }
     // optional fractional part
     if let Some('.') = s.char() {
         s.next();
-        if s.read_digits().is_none() {
+        if !s.skip_digits() {
             return Err(err());
         }
     }
@@ -127,7 +170,7 @@ pub fn check_number_format(s: &str) -> result::Result<(), ParseOracleTypeError>
                 },
                 _ => (),
             }
-            if s.read_digits().is_none() {
+            if !s.skip_digits() {
                 return Err(err());
             }
         },
@@ -205,6 +248,99 @@ pub fn write_literal(f: &mut fmt::Formatter, s: &Result<String>, oratype: &Oracl
     }
 }
 
+// MD5 message digest defined in RFC 1321
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    const S: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+        5,  9, 14, 20, 5,  9, 14, 20, 5,  9, 14, 20, 5,  9, 14, 20,
+        4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+        6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+    const K: [u32; 64] = [
+        0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+        0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+        0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+        0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+        0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+        0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+        0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+        0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+    ];
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    let bitlen = (data.len() as u64).wrapping_mul(8);
+    for i in 0..8 {
+        msg.push((bitlen >> (i * 8)) as u8);
+    }
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for chunk in msg.chunks(64) {
+        let mut m = [0u32; 16];
+        for i in 0..16 {
+            m[i] = (chunk[i * 4] as u32) | (chunk[i * 4 + 1] as u32) << 8
+                | (chunk[i * 4 + 2] as u32) << 16 | (chunk[i * 4 + 3] as u32) << 24;
+        }
+        let (mut a, mut b, mut c, mut d) = (state[0], state[1], state[2], state[3]);
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f.wrapping_add(a).wrapping_add(K[i]).wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(S[i]));
+        }
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+    }
+    let mut digest = [0u8; 16];
+    for i in 0..4 {
+        for j in 0..4 {
+            digest[i * 4 + j] = (state[i] >> (j * 8)) as u8;
+        }
+    }
+    digest
+}
+
+// Computes SQL_ID in the same way with the Oracle server, which is
+// calculated from the last 64 bits of the MD5 digest of the SQL text
+// terminated by a null character.
+pub fn sql_id(sql: &str) -> String {
+    const ALPHABET: &'static [u8] = b"0123456789abcdfghjkmnpqrstuvwxyz";
+    let mut data = Vec::with_capacity(sql.len() + 1);
+    data.extend_from_slice(sql.as_bytes());
+    data.push(0);
+    let digest = md5(&data);
+    let word = |i: usize| (digest[i] as u64) | (digest[i + 1] as u64) << 8
+        | (digest[i + 2] as u64) << 16 | (digest[i + 3] as u64) << 24;
+    let mut num = word(8) << 32 | word(12);
+    let mut id = vec![b'0'; 13];
+    for i in (0..13).rev() {
+        id[i] = ALPHABET[(num & 0x1f) as usize];
+        num >>= 5;
+    }
+    String::from_utf8(id).unwrap()
+}
+
+// Checks whether `s` is a nonquoted identifier, which can be embedded
+// in SQL statements safely.
+pub fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => (),
+        _ => return false,
+    }
+    s.len() <= 128 && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '#')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,6 +354,13 @@ mod tests {
         s.next();
         assert_eq!(s.read_digits(), Some(4567890));
         assert_eq!(s.char(), None);
+
+        let mut s = Scanner::new("18446744073709551615 18446744073709551616");
+        assert_eq!(s.read_digits(), Some(18446744073709551615));
+        s.next();
+        assert_eq!(s.read_digits(), None);
+        assert_eq!(s.ndigits(), 20);
+        assert_eq!(s.char(), None);
     }
 
     #[test]
@@ -240,6 +383,64 @@ mod tests {
         assert_eq!(check_number_format("a"), err);
         assert_eq!(check_number_format("0.0"), ok);
         assert_eq!(check_number_format("9.9"), ok);
+        assert_eq!(check_number_format("123456789012345678901234567890.1234567890"), ok);
+    }
+
+    #[test]
+    fn test_parse_float() {
+        assert_eq!(parse_float::<f64>("1.5").unwrap(), 1.5);
+        assert_eq!(parse_float::<f64>("~").unwrap(), f64::INFINITY);
+        assert_eq!(parse_float::<f64>("-~").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(parse_float::<f32>("-~").unwrap(), f32::NEG_INFINITY);
+        assert!(parse_float::<f64>("NaN").unwrap().is_nan());
+        assert!(parse_float::<f64>("~~").is_err());
+    }
+
+    #[test]
+    fn test_parse_small_integer() {
+        assert_eq!(parse_small_integer(b"0"), Some(0));
+        assert_eq!(parse_small_integer(b"123"), Some(123));
+        assert_eq!(parse_small_integer(b"-123"), Some(-123));
+        assert_eq!(parse_small_integer(b"999999999999999999"), Some(999999999999999999));
+        assert_eq!(parse_small_integer(b"-999999999999999999"), Some(-999999999999999999));
+        assert_eq!(parse_small_integer(b"1000000000000000000"), None);
+        assert_eq!(parse_small_integer(b"1.5"), None);
+        assert_eq!(parse_small_integer(b"1E+20"), None);
+        assert_eq!(parse_small_integer(b"~"), None);
+        assert_eq!(parse_small_integer(b"-"), None);
+        assert_eq!(parse_small_integer(b""), None);
+    }
+
+    #[test]
+    fn test_md5() {
+        let hex = |digest: [u8; 16]| {
+            let mut s = String::new();
+            set_hex_string(&mut s, &digest);
+            s
+        };
+        assert_eq!(hex(md5(b"")), "D41D8CD98F00B204E9800998ECF8427E");
+        assert_eq!(hex(md5(b"abc")), "900150983CD24FB0D6963F7D28E17F72");
+        assert_eq!(hex(md5(b"12345678901234567890123456789012345678901234567890123456789012345678901234567890")),
+                   "57EDF4A22BE3C955AC49DA2E2107B67A");
+    }
+
+    #[test]
+    fn test_sql_id() {
+        assert_eq!(sql_id("select * from dual"), "a5ks9fhw2v9s1");
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("sp1"));
+        assert!(is_identifier("Before_Update$#"));
+        assert!(!is_identifier(""));
+        assert!(!is_identifier("1sp"));
+        assert!(!is_identifier("_sp"));
+        assert!(!is_identifier("sp 1"));
+        assert!(!is_identifier("sp;drop table emp"));
+        assert!(!is_identifier("\"sp\""));
+        assert!(is_identifier(&"a".repeat(128)));
+        assert!(!is_identifier(&"a".repeat(129)));
     }
 
     #[test]
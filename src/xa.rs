@@ -0,0 +1,293 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+//! Distributed transactions
+
+use std::fmt;
+
+use Connection;
+use Error;
+use Result;
+
+/// Transaction identifier of a distributed transaction
+///
+/// This consists of a format identifier, a global transaction identifier
+/// and a branch qualifier as defined by the X/Open XA specification.
+/// The lengths of the latter two must be 64 bytes or less.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use oracle::*;
+/// let conn = Connection::new("scott", "tiger", "").unwrap();
+/// let xid = Xid::new(0x1234, b"global-transaction-1", b"branch-1");
+/// conn.begin_distrib_trans(&xid).unwrap();
+/// conn.execute("update emp set sal = sal * 1.1 where empno = 7839", &[]).unwrap();
+/// if conn.prepare_distrib_trans().unwrap() {
+///     // The transaction coordinator decided to commit.
+///     conn.commit().unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Xid {
+    format_id: i32,
+    global_transaction_id: Vec<u8>,
+    branch_qualifier: Vec<u8>,
+}
+
+impl Xid {
+    /// Creates a transaction identifier.
+    ///
+    /// `format_id` is `i32` because `formatID` of XA, declared as `long`,
+    /// is 32 bits on Windows.
+    pub fn new(format_id: i32, global_transaction_id: &[u8], branch_qualifier: &[u8]) -> Xid {
+        Xid {
+            format_id: format_id,
+            global_transaction_id: global_transaction_id.to_vec(),
+            branch_qualifier: branch_qualifier.to_vec(),
+        }
+    }
+
+    /// Returns the format identifier.
+    pub fn format_id(&self) -> i32 {
+        self.format_id
+    }
+
+    /// Returns the global transaction identifier.
+    pub fn global_transaction_id(&self) -> &[u8] {
+        &self.global_transaction_id
+    }
+
+    /// Returns the branch qualifier.
+    pub fn branch_qualifier(&self) -> &[u8] {
+        &self.branch_qualifier
+    }
+}
+
+/// XA return code of a failed function in `DBMS_XA`
+///
+/// See [Error::XaError](enum.Error.html#variant.XaError).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum XaErrorCode {
+    /// The branch was rolled back for an unspecified reason. (XA_RBROLLBACK)
+    RbRollback,
+    /// The branch was rolled back because of a communication failure. (XA_RBCOMMFAIL)
+    RbCommFail,
+    /// The branch was rolled back because a deadlock was detected. (XA_RBDEADLOCK)
+    RbDeadlock,
+    /// The branch was rolled back because of a violation of resource integrity. (XA_RBINTEGRITY)
+    RbIntegrity,
+    /// The branch was rolled back for a reason not listed here. (XA_RBOTHER)
+    RbOther,
+    /// The branch was rolled back because of a protocol error. (XA_RBPROTO)
+    RbProto,
+    /// The branch was rolled back because it took too long. (XA_RBTIMEOUT)
+    RbTimeout,
+    /// The branch was rolled back. It may be retried. (XA_RBTRANSIENT)
+    RbTransient,
+    /// The branch cannot be resumed in another session. (XA_NOMIGRATE)
+    NoMigrate,
+    /// The branch may have been heuristically completed. (XA_HEURHAZ)
+    HeurHaz,
+    /// The branch was heuristically committed. (XA_HEURCOM)
+    HeurCom,
+    /// The branch was heuristically rolled back. (XA_HEURRB)
+    HeurRb,
+    /// The branch was heuristically committed in part and rolled back in part. (XA_HEURMIX)
+    HeurMix,
+    /// The routine returned with no effect. It may be retried. (XA_RETRY)
+    Retry,
+    /// The branch was read-only and has been committed. (XA_RDONLY)
+    RdOnly,
+    /// An asynchronous operation is already outstanding. (XAER_ASYNC)
+    Async,
+    /// A resource manager error occurred in the branch. (XAER_RMERR)
+    RmErr,
+    /// The transaction identifier is not valid. (XAER_NOTA)
+    NoTa,
+    /// Invalid arguments were given. (XAER_INVAL)
+    Inval,
+    /// The routine was called in an improper context. (XAER_PROTO)
+    Proto,
+    /// The resource manager is unavailable. (XAER_RMFAIL)
+    RmFail,
+    /// The transaction identifier already exists. (XAER_DUPID)
+    DupId,
+    /// The resource manager is doing work outside the global transaction. (XAER_OUTSIDE)
+    Outside,
+    /// Return code not listed above
+    Other(i32),
+}
+
+impl XaErrorCode {
+    /// Returns the numeric XA return code.
+    pub fn code(&self) -> i32 {
+        match *self {
+            XaErrorCode::RbRollback => 100,
+            XaErrorCode::RbCommFail => 101,
+            XaErrorCode::RbDeadlock => 102,
+            XaErrorCode::RbIntegrity => 103,
+            XaErrorCode::RbOther => 104,
+            XaErrorCode::RbProto => 105,
+            XaErrorCode::RbTimeout => 106,
+            XaErrorCode::RbTransient => 107,
+            XaErrorCode::NoMigrate => 9,
+            XaErrorCode::HeurHaz => 8,
+            XaErrorCode::HeurCom => 7,
+            XaErrorCode::HeurRb => 6,
+            XaErrorCode::HeurMix => 5,
+            XaErrorCode::Retry => 4,
+            XaErrorCode::RdOnly => 3,
+            XaErrorCode::Async => -2,
+            XaErrorCode::RmErr => -3,
+            XaErrorCode::NoTa => -4,
+            XaErrorCode::Inval => -5,
+            XaErrorCode::Proto => -6,
+            XaErrorCode::RmFail => -7,
+            XaErrorCode::DupId => -8,
+            XaErrorCode::Outside => -9,
+            XaErrorCode::Other(code) => code,
+        }
+    }
+
+    pub(crate) fn from_code(code: i32) -> XaErrorCode {
+        match code {
+            100 => XaErrorCode::RbRollback,
+            101 => XaErrorCode::RbCommFail,
+            102 => XaErrorCode::RbDeadlock,
+            103 => XaErrorCode::RbIntegrity,
+            104 => XaErrorCode::RbOther,
+            105 => XaErrorCode::RbProto,
+            106 => XaErrorCode::RbTimeout,
+            107 => XaErrorCode::RbTransient,
+            9 => XaErrorCode::NoMigrate,
+            8 => XaErrorCode::HeurHaz,
+            7 => XaErrorCode::HeurCom,
+            6 => XaErrorCode::HeurRb,
+            5 => XaErrorCode::HeurMix,
+            4 => XaErrorCode::Retry,
+            3 => XaErrorCode::RdOnly,
+            -2 => XaErrorCode::Async,
+            -3 => XaErrorCode::RmErr,
+            -4 => XaErrorCode::NoTa,
+            -5 => XaErrorCode::Inval,
+            -6 => XaErrorCode::Proto,
+            -7 => XaErrorCode::RmFail,
+            -8 => XaErrorCode::DupId,
+            -9 => XaErrorCode::Outside,
+            _ => XaErrorCode::Other(code),
+        }
+    }
+
+    /// Returns true when the branch was rolled back (`XA_RB*`).
+    pub fn is_rollback(&self) -> bool {
+        let code = self.code();
+        100 <= code && code <= 107
+    }
+}
+
+impl fmt::Display for XaErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            XaErrorCode::RbRollback => write!(f, "XA_RBROLLBACK"),
+            XaErrorCode::RbCommFail => write!(f, "XA_RBCOMMFAIL"),
+            XaErrorCode::RbDeadlock => write!(f, "XA_RBDEADLOCK"),
+            XaErrorCode::RbIntegrity => write!(f, "XA_RBINTEGRITY"),
+            XaErrorCode::RbOther => write!(f, "XA_RBOTHER"),
+            XaErrorCode::RbProto => write!(f, "XA_RBPROTO"),
+            XaErrorCode::RbTimeout => write!(f, "XA_RBTIMEOUT"),
+            XaErrorCode::RbTransient => write!(f, "XA_RBTRANSIENT"),
+            XaErrorCode::NoMigrate => write!(f, "XA_NOMIGRATE"),
+            XaErrorCode::HeurHaz => write!(f, "XA_HEURHAZ"),
+            XaErrorCode::HeurCom => write!(f, "XA_HEURCOM"),
+            XaErrorCode::HeurRb => write!(f, "XA_HEURRB"),
+            XaErrorCode::HeurMix => write!(f, "XA_HEURMIX"),
+            XaErrorCode::Retry => write!(f, "XA_RETRY"),
+            XaErrorCode::RdOnly => write!(f, "XA_RDONLY"),
+            XaErrorCode::Async => write!(f, "XAER_ASYNC"),
+            XaErrorCode::RmErr => write!(f, "XAER_RMERR"),
+            XaErrorCode::NoTa => write!(f, "XAER_NOTA"),
+            XaErrorCode::Inval => write!(f, "XAER_INVAL"),
+            XaErrorCode::Proto => write!(f, "XAER_PROTO"),
+            XaErrorCode::RmFail => write!(f, "XAER_RMFAIL"),
+            XaErrorCode::DupId => write!(f, "XAER_DUPID"),
+            XaErrorCode::Outside => write!(f, "XAER_OUTSIDE"),
+            XaErrorCode::Other(code) => write!(f, "XA return code {}", code),
+        }
+    }
+}
+
+// Calls a function in DBMS_XA taking a transaction identifier and
+// returning an XA return code. `args` are additional arguments.
+pub(crate) fn call_dbms_xa(conn: &Connection, func: &str, xid: &Xid, args: &str) -> Result<()> {
+    let sql = format!("begin :rc := dbms_xa.{}(dbms_xa_xid(:format_id, :gtrid, :bqual){}); end;", func, args);
+    let stmt = conn.execute_named_internal(&sql, &[("rc", &0i32),
+                                                   ("format_id", &xid.format_id),
+                                                   ("gtrid", &xid.global_transaction_id),
+                                                   ("bqual", &xid.branch_qualifier)])?;
+    let rc: i32 = stmt.bind_value("rc")?;
+    if rc == 0 { // DBMS_XA.XA_OK
+        Ok(())
+    } else {
+        Err(Error::XaError(XaErrorCode::from_code(rc)))
+    }
+}
+
+// Returns identifiers of pending prepared branches.
+pub(crate) fn recover(conn: &Connection) -> Result<Vec<Xid>> {
+    let sql = "select formatid, globalid, branchid from dba_pending_transactions";
+    let mut stmt = conn.execute_internal(sql, &[])?;
+    let rows: Vec<(i32, Vec<u8>, Vec<u8>)> = stmt.fetch_all()?;
+    Ok(rows.into_iter().map(|(format_id, gtrid, bqual)| Xid {
+        format_id: format_id,
+        global_transaction_id: gtrid,
+        branch_qualifier: bqual,
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xa_error_code() {
+        assert_eq!(XaErrorCode::from_code(-4), XaErrorCode::NoTa);
+        assert_eq!(XaErrorCode::from_code(106), XaErrorCode::RbTimeout);
+        assert_eq!(XaErrorCode::from_code(-100), XaErrorCode::Other(-100));
+        for code in -10..110 {
+            assert_eq!(XaErrorCode::from_code(code).code(), code);
+        }
+        assert!(XaErrorCode::RbDeadlock.is_rollback());
+        assert!(!XaErrorCode::HeurRb.is_rollback());
+        assert_eq!(XaErrorCode::DupId.to_string(), "XAER_DUPID");
+    }
+}
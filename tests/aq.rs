@@ -0,0 +1,441 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+#[macro_use]
+extern crate lazy_static;
+extern crate oracle;
+mod common;
+
+use std::sync::{Mutex, MutexGuard, Once};
+use oracle::*;
+
+lazy_static! {
+    // The queues are shared by all sessions.
+    static ref QUEUES: Mutex<()> = Mutex::new(());
+}
+
+static CREATE_QUEUES: Once = Once::new();
+
+// Creates the queues used by the tests unless they exist and serializes
+// the tests using them. The test user needs EXECUTE privilege on
+// DBMS_AQADM.
+//
+// BOOKS: payload type UDT_BOOK
+// RAW_QUEUE: payload type RAW
+// MULTI_RAW_QUEUE: payload type RAW, multiple consumers
+// JSON_QUEUE: payload type JSON, Oracle 21c or later
+fn lock_queues() -> MutexGuard<'static, ()> {
+    let guard = QUEUES.lock().unwrap_or_else(|err| err.into_inner());
+    CREATE_QUEUES.call_once(|| {
+        let conn = common::connect().unwrap();
+        let json_queue = if conn.server_version().unwrap().0.major() >= 21 {
+            "create_queue('JSON_QUEUE', 'JSON');"
+        } else {
+            ""
+        };
+        conn.execute(&format!("\
+declare
+    cnt integer;
+    procedure create_queue(queue_name varchar2, payload_type varchar2, multiple_consumers boolean default false) is
+    begin
+        select count(*) into cnt from user_queues where name = queue_name;
+        if cnt = 0 then
+            dbms_aqadm.create_queue_table(queue_name || '_TAB', payload_type, multiple_consumers => multiple_consumers);
+            dbms_aqadm.create_queue(queue_name, queue_name || '_TAB');
+        end if;
+        dbms_aqadm.start_queue(queue_name);
+    end;
+begin
+    select count(*) into cnt from user_types where type_name = 'UDT_BOOK';
+    if cnt = 0 then
+        execute immediate 'create type UDT_BOOK as object (TITLE varchar2(100), AUTHORS varchar2(100), PRICE number(5,2))';
+    end if;
+    create_queue('BOOKS', 'UDT_BOOK');
+    create_queue('RAW_QUEUE', 'RAW');
+    create_queue('MULTI_RAW_QUEUE', 'RAW', true);
+    {}
+end;", json_queue), &[]).unwrap();
+    });
+    guard
+}
+
+fn new_book(objtype: &ObjectType, title: &str, price: f64) -> Object {
+    let mut book = objtype.new_object().unwrap();
+    book.set("TITLE", &title).unwrap();
+    book.set("AUTHORS", &"Test Author").unwrap();
+    book.set("PRICE", &price).unwrap();
+    book
+}
+
+fn clear_queue(queue: &mut Queue) {
+    queue.deq_options_mut().wait(0);
+    while let Some(_) = queue.dequeue().unwrap() {
+    }
+}
+
+#[test]
+fn enqueue_and_dequeue_objects() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+
+    let msgid = queue.enqueue(&new_book(&objtype, "Book 1", 10.5)).unwrap();
+    assert_eq!(msgid.len(), 16);
+    queue.enqueue(&new_book(&objtype, "Book 2", 20.0)).unwrap();
+
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgid[..]);
+    let book = msg.payload().as_object().unwrap();
+    assert_eq!(book.get::<String>("TITLE").unwrap(), "Book 1");
+    assert_eq!(book.get::<f64>("PRICE").unwrap(), 10.5);
+    match queue.dequeue().unwrap().unwrap().into_payload() {
+        Payload::Object(book) => assert_eq!(book.get::<String>("TITLE").unwrap(), "Book 2"),
+        payload => panic!("unexpected payload: {:?}", payload),
+    }
+    assert!(queue.dequeue().unwrap().is_none());
+    conn.rollback().unwrap();
+
+    let subobjtype = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let subobj = subobjtype.new_object().unwrap();
+    let err = queue.enqueue(&subobj).unwrap_err();
+    assert_eq!(err.to_string(), format!("invalid type conversion from {} to {}", subobjtype, objtype));
+}
+
+#[test]
+fn enqueue_and_dequeue_raw() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let mut queue = conn.raw_queue("RAW_QUEUE");
+    clear_queue(&mut queue);
+    assert!(queue.payload_type().is_none());
+
+    let msgid = queue.enqueue_raw(b"message 1").unwrap();
+    assert_eq!(msgid.len(), 16);
+    queue.enqueue_raw(&[0u8, 1, 2, 255]).unwrap();
+
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgid[..]);
+    assert_eq!(msg.payload().as_bytes().unwrap(), b"message 1");
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.payload().as_bytes().unwrap(), &[0u8, 1, 2, 255]);
+    assert!(queue.dequeue().unwrap().is_none());
+    conn.rollback().unwrap();
+
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let err = queue.enqueue(&objtype.new_object().unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), format!("invalid type conversion from {} to RAW", objtype));
+    let queue = conn.queue("BOOKS", &objtype);
+    let err = queue.enqueue_raw(b"x").unwrap_err();
+    assert_eq!(err.to_string(), format!("invalid type conversion from RAW to {}", objtype));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn enqueue_and_dequeue_json() {
+    extern crate serde_json;
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    if conn.server_version().unwrap().0.major() < 21 {
+        return;
+    }
+    let mut queue = conn.json_queue("JSON_QUEUE");
+    clear_queue(&mut queue);
+
+    let value = serde_json::json!({"id": 1, "tags": ["a", "b"], "done": false});
+    queue.enqueue_json(&value).unwrap();
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.payload().as_json().unwrap(), &value);
+    assert!(queue.dequeue().unwrap().is_none());
+
+    let values: Vec<serde_json::Value> = (0..3).map(|i| serde_json::json!({"id": i})).collect();
+    queue.enqueue_many(&values).unwrap();
+    let msgs = queue.dequeue_many(10).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.payload().as_json().unwrap().clone()).collect::<Vec<_>>(), values);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn enqueue_and_dequeue_many() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+
+    let books: Vec<Object> = (0..5).map(|i| new_book(&objtype, &format!("Book {}", i), i as f64)).collect();
+    let msgids = queue.enqueue_many(&books).unwrap();
+    assert_eq!(msgids.len(), 5);
+
+    let msgs = queue.dequeue_many(3).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(), &msgids[0..3]);
+    let msgs = queue.dequeue_many(10).unwrap();
+    assert_eq!(msgs.len(), 2);
+    let title: String = msgs[1].payload().as_object().unwrap().get("TITLE").unwrap();
+    assert_eq!(title, "Book 4");
+    assert!(queue.dequeue_many(10).unwrap().is_empty());
+
+    // more messages than those sent in one round trip
+    let books: Vec<Object> = (0..150).map(|i| new_book(&objtype, &format!("Book {}", i), i as f64)).collect();
+    let msgids = queue.enqueue_many(&books).unwrap();
+    assert_eq!(msgids.len(), 150);
+    let msgs = queue.dequeue_many(200).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(), msgids);
+    let title: String = msgs[149].payload().as_object().unwrap().get("TITLE").unwrap();
+    assert_eq!(title, "Book 149");
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn enqueue_and_dequeue_many_raw() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let mut queue = conn.raw_queue("RAW_QUEUE");
+    clear_queue(&mut queue);
+
+    let payloads: Vec<Vec<u8>> = (0..150).map(|i| format!("message {}", i).into_bytes()).collect();
+    let msgids = queue.enqueue_many(&payloads).unwrap();
+    assert_eq!(msgids.len(), 150);
+    let msgs = queue.dequeue_many(200).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(), msgids);
+    assert_eq!(msgs[149].payload().as_bytes().unwrap(), b"message 149");
+
+    let payloads: Vec<&[u8]> = vec![b"a", b"b"];
+    assert_eq!(queue.enqueue_many(&payloads).unwrap().len(), 2);
+    assert_eq!(queue.dequeue_many(10).unwrap().len(), 2);
+    conn.rollback().unwrap();
+
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let books = vec![new_book(&objtype, "Book", 1.0)];
+    let err = queue.enqueue_many(&books).unwrap_err();
+    assert_eq!(err.to_string(), format!("invalid type conversion from {} to RAW", objtype));
+}
+
+#[test]
+fn dequeue_options() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+    let books: Vec<Object> = (0..3).map(|i| new_book(&objtype, &format!("Book {}", i), i as f64)).collect();
+    let msgids = queue.enqueue_many(&books).unwrap();
+
+    // browse messages without removing them
+    queue.deq_options_mut()
+        .mode(DeqMode::Browse)
+        .navigation(DeqNavigation::FirstMessage);
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgids[0][..]);
+    queue.deq_options_mut().navigation(DeqNavigation::NextMessage);
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgids[1][..]);
+
+    // remove the second message selected by its msgid
+    queue.deq_options_mut()
+        .mode(DeqMode::Remove)
+        .navigation(DeqNavigation::FirstMessage)
+        .msgid(&msgids[1]);
+    let msg = queue.dequeue().unwrap().unwrap();
+    let title: String = msg.payload().as_object().unwrap().get("TITLE").unwrap();
+    assert_eq!(title, "Book 1");
+
+    queue.deq_options_mut().clear_msgid();
+    let msgs = queue.dequeue_many(10).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(),
+               vec![msgids[0].clone(), msgids[2].clone()]);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn browse_many() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+    let books: Vec<Object> = (0..150).map(|i| new_book(&objtype, &format!("Book {}", i), i as f64)).collect();
+    let msgids = queue.enqueue_many(&books).unwrap();
+
+    queue.deq_options_mut()
+        .mode(DeqMode::Browse)
+        .navigation(DeqNavigation::FirstMessage);
+    let msgs = queue.dequeue_many(200).unwrap();
+    let browsed: Vec<Vec<u8>> = msgs.iter().map(|msg| msg.msgid().to_vec()).collect();
+    let mut distinct = browsed.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 150);
+    assert_eq!(browsed, msgids);
+
+    // browsed messages are still in the queue
+    queue.deq_options_mut()
+        .mode(DeqMode::Remove)
+        .navigation(DeqNavigation::FirstMessage);
+    assert_eq!(queue.dequeue_many(200).unwrap().len(), 150);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn message_properties() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+    queue.enq_options_mut().visibility(Visibility::OnCommit);
+    let mut props = MsgProps::new();
+    props.set_priority(5);
+    props.set_expiration(3600);
+    props.set_correlation("book-b");
+    queue.enqueue(&new_book(&objtype, "Book A", 1.0)).unwrap();
+    let msgid = queue.enqueue_with(&new_book(&objtype, "Book B", 2.0), &props).unwrap();
+
+    queue.deq_options_mut().correlation("book-b");
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgid[..]);
+    assert_eq!(msg.props().priority(), 5);
+    assert_eq!(msg.props().delay(), 0);
+    assert_eq!(msg.props().expiration(), 3600);
+    assert_eq!(msg.props().correlation(), Some("book-b"));
+    assert_eq!(msg.props().num_attempts(), 0);
+    assert_eq!(msg.props().state(), Some(MessageState::Ready));
+    assert!(msg.props().enq_time().is_some());
+    conn.rollback().unwrap();
+
+    let mut queue = conn.raw_queue("RAW_QUEUE");
+    clear_queue(&mut queue);
+    queue.enqueue_raw_with(b"raw", &props).unwrap();
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.payload().as_bytes(), Some(&b"raw"[..]));
+    assert_eq!(msg.props().priority(), 5);
+    assert_eq!(msg.props().expiration(), 3600);
+    assert_eq!(msg.props().correlation(), Some("book-b"));
+    assert!(msg.props().enq_time().is_some());
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn recipient_lists() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let queue = conn.raw_queue("MULTI_RAW_QUEUE");
+    let mut props = MsgProps::new();
+    props.set_recipients(&["CONSUMER1", "CONSUMER2"]);
+    assert_eq!(props.recipients(), &["CONSUMER1".to_string(), "CONSUMER2".to_string()]);
+    let msgid1 = queue.enqueue_raw_with(b"to 1 and 2", &props).unwrap();
+    props.set_recipients(&["CONSUMER2"]);
+    let msgid2 = queue.enqueue_raw_with(b"to 2", &props).unwrap();
+
+    let mut queue = conn.raw_queue("MULTI_RAW_QUEUE");
+    queue.deq_options_mut().wait(0).consumer_name("CONSUMER1");
+    let msgs = queue.dequeue_many(10).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(),
+               vec![msgid1.clone()]);
+
+    queue.deq_options_mut().consumer_name("CONSUMER2");
+    let msgs = queue.dequeue_many(10).unwrap();
+    assert_eq!(msgs.iter().map(|msg| msg.msgid().to_vec()).collect::<Vec<_>>(),
+               vec![msgid1, msgid2]);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn buffered_messages() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut queue = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut queue);
+    queue.enq_options_mut()
+        .visibility(Visibility::Immediate)
+        .delivery_mode(DeliveryMode::Buffered);
+    let msgid = queue.enqueue(&new_book(&objtype, "Buffered Book", 1.0)).unwrap();
+
+    queue.deq_options_mut()
+        .visibility(Visibility::Immediate)
+        .delivery_mode(DeliveryMode::Persistent);
+    assert!(queue.dequeue().unwrap().is_none());
+
+    queue.deq_options_mut().delivery_mode(DeliveryMode::PersistentOrBuffered);
+    let msg = queue.dequeue().unwrap().unwrap();
+    assert_eq!(msg.msgid(), &msgid[..]);
+    assert_eq!(msg.props().delivery_mode(), Some(DeliveryMode::Buffered));
+}
+
+#[test]
+fn process_messages() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let mut queue = conn.raw_queue("RAW_QUEUE");
+    clear_queue(&mut queue);
+    conn.commit().unwrap();
+    queue.enqueue_raw(b"message").unwrap();
+    conn.commit().unwrap();
+
+    // rolled back when the closure fails
+    let res: Result<Option<()>> = queue.process(|msg| {
+        assert_eq!(msg.payload().as_bytes().unwrap(), b"message");
+        Err(Error::NoMoreData)
+    });
+    assert_eq!(res.unwrap_err().to_string(), Error::NoMoreData.to_string());
+
+    // committed when the closure succeeds
+    let res = queue.process(|msg| Ok::<_, Error>(msg.payload().as_bytes().unwrap().len()));
+    assert_eq!(res.unwrap(), Some(7));
+    conn.rollback().unwrap();
+    assert!(queue.process(|_| Ok::<_, Error>(())).unwrap().is_none());
+}
+
+#[test]
+fn listen_queues() {
+    let _lock = lock_queues();
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_BOOK").unwrap();
+    let mut books = conn.queue("BOOKS", &objtype);
+    clear_queue(&mut books);
+    let mut raw_queue = conn.raw_queue("RAW_QUEUE");
+    clear_queue(&mut raw_queue);
+    conn.commit().unwrap();
+
+    let agents = [Agent::new("BOOKS"), Agent::new("RAW_QUEUE")];
+    assert_eq!(conn.listen(&agents, Some(0)).unwrap(), None);
+
+    raw_queue.enqueue_raw(b"message").unwrap();
+    conn.commit().unwrap();
+    let agent = conn.listen(&agents, Some(0)).unwrap().unwrap();
+    assert_eq!(agent.name(), None);
+    assert!(agent.address().contains("RAW_QUEUE"), "address: {}", agent.address());
+    assert!(raw_queue.dequeue().unwrap().is_some());
+    conn.commit().unwrap();
+}
@@ -0,0 +1,198 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+#[macro_use]
+extern crate lazy_static;
+extern crate oracle;
+mod common;
+
+use std::sync::{Mutex, MutexGuard};
+
+lazy_static! {
+    // TestTempTable is a normal table shared by all sessions.
+    static ref TEMP_TABLE: Mutex<()> = Mutex::new(());
+}
+
+// Serializes tests using TestTempTable.
+fn lock_temp_table() -> MutexGuard<'static, ()> {
+    TEMP_TABLE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[test]
+fn batch_insert() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+
+    let mut batch = conn.batch("insert into TestTempTable values (:1, :2)", 3).unwrap();
+    for i in 0..10 {
+        // String lengths grow to force bind variables to be recreated.
+        let s = "x".repeat(i + 1);
+        batch.append_row(&[&(i as i32), &s]).unwrap();
+    }
+    assert!(batch.pending_rows() > 0);
+    batch.execute().unwrap();
+    assert_eq!(batch.pending_rows(), 0);
+
+    let mut stmt = conn.execute("select count(*), max(length(StringCol)) from TestTempTable", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let cnt: i32 = row.get(0).unwrap();
+    let maxlen: i32 = row.get(1).unwrap();
+    assert_eq!(cnt, 10);
+    assert_eq!(maxlen, 10);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn batch_keeps_rows_on_error() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+
+    let mut batch = conn.batch("insert into TestTempTable values (:1, :2)", 3).unwrap();
+    batch.append_row(&[&1, &"one"]).unwrap();
+    batch.append_row(&[&1, &"duplicated"]).unwrap();
+    batch.append_row(&[&2, &"two"]).unwrap_err();
+    // ORA-00001: unique constraint violated at the second row.
+    // The first row is removed from the buffer.
+    assert_eq!(batch.sent_rows(), 1);
+    assert_eq!(batch.pending_rows(), 2);
+    // The rows aren't sent again automatically.
+    match batch.append_row(&[&3, &"three"]) {
+        Err(oracle::Error::UnsentBatchRows(2)) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+    assert!(batch.execute().is_err());
+    assert_eq!(batch.sent_rows(), 1);
+    assert_eq!(batch.pending_rows(), 2);
+    batch.clear();
+    assert_eq!(batch.pending_rows(), 0);
+
+    batch.append_row(&[&2, &"two"]).unwrap();
+    batch.execute().unwrap();
+    assert_eq!(batch.sent_rows(), 2);
+    assert_eq!(batch.pending_rows(), 0);
+
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    let cnt: i32 = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(cnt, 2);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn batch_requires_all_values() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    let mut batch = conn.batch("insert into TestTempTable values (:1, :2)", 10).unwrap();
+    match batch.append_row(&[&1]) {
+        Err(oracle::Error::InvalidBindIndex(2)) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+    match batch.append_row(&[&1, &"one", &"extra"]) {
+        Err(oracle::Error::InvalidBindIndex(3)) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+    assert_eq!(batch.pending_rows(), 0);
+}
+
+#[test]
+fn copy_rows() {
+    let _lock = lock_temp_table();
+    let src_conn = common::connect().unwrap();
+    let dst_conn = common::connect().unwrap();
+    dst_conn.execute("delete from TestTempTable", &[]).unwrap();
+
+    let mut stmt = src_conn.execute("select level, 'row ' || level from dual connect by level <= 250", &[]).unwrap();
+    let num_rows = oracle::copy_rows(&mut stmt, &dst_conn, "insert into TestTempTable values (:1, :2)").unwrap();
+    assert_eq!(num_rows, 250);
+
+    let mut stmt = dst_conn.execute("select count(*) from TestTempTable where StringCol = 'row ' || IntCol", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let cnt: i32 = row.get(0).unwrap();
+    assert_eq!(cnt, 250);
+    dst_conn.rollback().unwrap();
+}
+
+#[test]
+fn batch_objects() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    let objtype = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let null = oracle::OracleType::Object(objtype.clone());
+
+    let mut batch = conn.batch("declare obj UDT_SUBOBJECT := :1; begin insert into TestTempTable values (nvl(obj.SubNumberValue, -1), obj.SubStringValue); end;", 3).unwrap();
+    for i in 0..7 {
+        if i == 5 {
+            batch.append_row(&[&null]).unwrap();
+        } else {
+            let mut obj = objtype.new_object().unwrap();
+            obj.set("SUBNUMBERVALUE", &i).unwrap();
+            obj.set("SUBSTRINGVALUE", &format!("STRVAL:{}", i)).unwrap();
+            batch.append_row(&[&obj]).unwrap();
+        }
+    }
+    batch.execute().unwrap();
+
+    let mut stmt = conn.execute("select count(*), sum(IntCol) from TestTempTable where StringCol = 'STRVAL:' || IntCol", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let cnt: i32 = row.get(0).unwrap();
+    let sum: i32 = row.get(1).unwrap();
+    assert_eq!(cnt, 6);
+    assert_eq!(sum, 0 + 1 + 2 + 3 + 4 + 6);
+    let mut stmt = conn.execute("select count(*) from TestTempTable where IntCol = -1 and StringCol is null", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let cnt: i32 = row.get(0).unwrap();
+    assert_eq!(cnt, 1);
+    conn.rollback().unwrap();
+}
+
+#[test]
+fn batch_collections() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    let objtype = conn.object_type("UDT_ARRAY").unwrap();
+
+    let mut batch = conn.batch("declare ary UDT_ARRAY := :1; begin insert into TestTempTable values (ary.count, :2); end;", 4).unwrap();
+    for i in 0..10 {
+        let coll = objtype.new_collection_from_iter(0..i).unwrap();
+        batch.append_row(&[&coll, &format!("row {}", i)]).unwrap();
+    }
+    batch.execute().unwrap();
+
+    let mut stmt = conn.execute("select count(*) from TestTempTable where StringCol = 'row ' || IntCol", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let cnt: i32 = row.get(0).unwrap();
+    assert_eq!(cnt, 10);
+    conn.rollback().unwrap();
+}
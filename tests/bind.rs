@@ -41,6 +41,20 @@ fn in_out_same_values() {
     test_in_out!(stmt, IntervalYM, IntervalYM::new(10, 2));
 }
 
+#[test]
+fn in_out_bool() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :1 := :2; end;").unwrap();
+
+    test_in_out!(stmt, bool, true);
+    test_in_out!(stmt, bool, false);
+    test_in_out!(stmt, bool, true, &OracleType::Boolean);
+
+    let mut stmt = conn.prepare("begin :1 := not :2; end;").unwrap();
+    stmt.execute(&[&OracleType::Boolean, &true]).unwrap();
+    assert_eq!(stmt.bind_value::<_, bool>(1).unwrap(), false);
+}
+
 macro_rules! test_to_string {
     ($stmt:expr, $val:expr) => {
         $stmt.bind(1, &oracle::OracleType::Varchar2(4000)).expect("bind(1)");
@@ -171,3 +185,103 @@ fn bind_named() {
     let outval: Option<String> = stmt.bind_value("out").unwrap();
     assert_eq!(outval, None);
 }
+
+#[test]
+fn bind_owned_and_borrowed_strings_and_bytes() {
+    use std::borrow::Cow;
+
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :out := :in; end;").unwrap();
+    let out_str = oracle::OracleType::Varchar2(10);
+    let out_raw = oracle::OracleType::Raw(10);
+
+    let cow_borrowed: Cow<str> = Cow::Borrowed("abc");
+    let cow_owned: Cow<str> = Cow::Owned("abc".to_string());
+    let boxed: Box<str> = "abc".to_string().into_boxed_str();
+    for s in &[&cow_borrowed as &oracle::ToSql, &cow_owned, &boxed] {
+        stmt.execute(&[&out_str, *s]).unwrap();
+        assert_eq!(stmt.bind_value::<_, String>(1).unwrap(), "abc");
+    }
+
+    let bytes = vec![1u8, 2, 255];
+    let slice: &[u8] = &bytes;
+    let cow: Cow<[u8]> = Cow::Borrowed(&bytes);
+    let boxed: Box<[u8]> = bytes.clone().into_boxed_slice();
+    for b in &[&bytes as &oracle::ToSql, &slice, &cow, &boxed] {
+        stmt.execute(&[&out_raw, *b]).unwrap();
+        assert_eq!(stmt.bind_value::<_, Vec<u8>>(1).unwrap(), bytes);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct UserId(i64);
+impl_sql_for_newtype!(UserId(i64));
+
+#[derive(Debug, PartialEq)]
+struct UserName(String);
+impl_sql_for_newtype!(UserName(String));
+
+#[test]
+fn bind_newtype() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :out := :in; end;").unwrap();
+
+    stmt.execute(&[&None::<UserId>, &UserId(123)]).unwrap();
+    assert_eq!(stmt.bind_value::<_, UserId>(1).unwrap(), UserId(123));
+
+    stmt.execute(&[&oracle::OracleType::Varchar2(10), &UserName("scott".to_string())]).unwrap();
+    assert_eq!(stmt.bind_value::<_, UserName>(1).unwrap(), UserName("scott".to_string()));
+    assert_eq!(stmt.bind_value::<_, Option<UserName>>(1).unwrap(), Some(UserName("scott".to_string())));
+}
+
+#[derive(Debug, PartialEq)]
+enum Status { Active, Inactive }
+impl_sql_for_enum!(Status: String {
+    Active => "A",
+    Inactive => "I",
+});
+
+#[derive(Debug, PartialEq)]
+enum Priority { Low, High }
+impl_sql_for_enum!(Priority: i32 {
+    Low => 1,
+    High => 9,
+});
+
+#[test]
+fn bind_enum_code() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :out := :in; end;").unwrap();
+
+    stmt.execute(&[&None::<Status>, &Status::Inactive]).unwrap();
+    assert_eq!(stmt.bind_value::<_, Status>(1).unwrap(), Status::Inactive);
+    stmt.execute(&[&None::<Priority>, &Priority::High]).unwrap();
+    assert_eq!(stmt.bind_value::<_, Priority>(1).unwrap(), Priority::High);
+
+    stmt.execute(&[&None::<Status>, &"X"]).unwrap();
+    match stmt.bind_value::<_, Status>(1) {
+        Err(Error::UnknownEnumCode(ref code, name)) => {
+            assert_eq!(code, "X");
+            assert_eq!(name, "Status");
+        },
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn bind_float_as_binary_float() {
+    let conn = common::connect().unwrap();
+    let dump_type = |conn: &Connection| {
+        let mut stmt = conn.prepare("select substr(dump(:1), 1, 7), substr(dump(:2), 1, 7), substr(dump(:3), 1, 6) from dual").unwrap();
+        stmt.execute(&[&1.5f64, &1.5f32, &1i32]).unwrap();
+        let row = stmt.fetch().unwrap();
+        row.get_as::<(String, String, String)>().unwrap()
+    };
+
+    assert_eq!(conn.binary_float_bind(), false);
+    assert_eq!(dump_type(&conn), ("Typ=2 L".to_string(), "Typ=2 L".to_string(), "Typ=2 ".to_string()));
+
+    conn.set_binary_float_bind(true);
+    assert_eq!(dump_type(&conn), ("Typ=101".to_string(), "Typ=100".to_string(), "Typ=2 ".to_string()));
+    conn.set_binary_float_bind(false);
+}
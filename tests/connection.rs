@@ -30,9 +30,23 @@
 // authors and should not be interpreted as representing official policies, either expressed
 // or implied, of the authors.
 
+#[macro_use]
+extern crate lazy_static;
 extern crate oracle;
 mod common;
 
+use std::sync::{Mutex, MutexGuard};
+
+lazy_static! {
+    // TestTempTable is a normal table shared by all sessions.
+    static ref TEMP_TABLE: Mutex<()> = Mutex::new(());
+}
+
+// Serializes tests using TestTempTable.
+fn lock_temp_table() -> MutexGuard<'static, ()> {
+    TEMP_TABLE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
 #[test]
 fn app_context() {
     let mut connector = oracle::Connector::new(&common::main_user(), &common::main_password(), &common::connect_string());
@@ -43,3 +57,263 @@ fn app_context() {
     let val: String = row.get(0).unwrap();
     assert_eq!(val, "bar");
 }
+
+#[test]
+fn dbms_output() {
+    let conn = common::connect().unwrap();
+    conn.enable_dbms_output().unwrap();
+    conn.execute("begin
+                    for i in 1..250 loop
+                      dbms_output.put_line('line ' || i);
+                    end loop;
+                    dbms_output.put_line(null);
+                  end;", &[]).unwrap();
+    let lines = conn.dbms_output_lines().unwrap();
+    assert_eq!(lines.len(), 250);
+    assert_eq!(lines[0], "line 1");
+    assert_eq!(lines[248], "line 249");
+    assert_eq!(lines[249], "");
+    assert_eq!(conn.dbms_output_lines().unwrap().len(), 0);
+}
+
+#[test]
+fn session_time_zone() {
+    let conn = common::connect().unwrap();
+    conn.set_session_time_zone("+09:00").unwrap();
+    assert_eq!(conn.session_time_zone().unwrap(), "+09:00");
+    conn.set_session_time_zone("UTC").unwrap();
+    assert_eq!(conn.session_time_zone().unwrap(), "UTC");
+    assert!(conn.set_session_time_zone("no'such zone").is_err());
+
+    let mut stmt = conn.execute("select cast(TIMESTAMP '2012-03-04 05:06:07 +01:00' as timestamp with local time zone) from dual", &[]).unwrap();
+    let ts: oracle::Timestamp = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(ts.to_string(), "2012-03-04 04:06:07.000000 +00:00");
+}
+
+#[test]
+fn savepoint() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'first')", &[]).unwrap();
+    conn.savepoint("sp1").unwrap();
+    conn.execute("insert into TestTempTable values (2, 'second')", &[]).unwrap();
+    conn.rollback_to("sp1").unwrap();
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+    conn.rollback().unwrap();
+
+    match conn.savepoint("sp1; rollback").unwrap_err() {
+        oracle::Error::InvalidIdentifier(ref name) => assert_eq!(name, "sp1; rollback"),
+        err => panic!("unexpected error: {}", err),
+    }
+    assert!(conn.rollback_to("no_such_savepoint").is_err());
+}
+
+#[test]
+fn autocommit() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    let conn2 = common::connect().unwrap();
+    let count_sql = "select count(*) from TestTempTable";
+    assert!(!conn.autocommit());
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+
+    conn.set_autocommit(true);
+    conn.execute("insert into TestTempTable values (1, 'committed')", &[]).unwrap();
+    conn.rollback().unwrap();
+    let mut stmt = conn2.execute(count_sql, &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+
+    conn.set_autocommit(false);
+    conn.execute("insert into TestTempTable values (2, 'rolled back')", &[]).unwrap();
+    conn.rollback().unwrap();
+    let mut stmt = conn2.execute(count_sql, &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+
+    // SET TRANSACTION executed internally isn't committed.
+    conn.set_autocommit(true);
+    conn.set_transaction_read_only().unwrap();
+    assert!(conn.execute("insert into TestTempTable values (3, 'read only')", &[]).is_err());
+    conn.rollback().unwrap();
+    conn.set_autocommit(false);
+
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+}
+
+#[test]
+fn reset_session_state() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+
+    conn.execute("insert into TestTempTable values (1, 'rolled back')", &[]).unwrap();
+    conn.set_module("module").unwrap();
+    conn.set_action("action").unwrap();
+    conn.set_client_identifier("client").unwrap();
+    conn.execute("alter session set current_schema = SYS", &[]).unwrap();
+    conn.reset_session_state().unwrap();
+
+    assert!(!conn.transaction_in_progress().unwrap());
+    let mut stmt = conn.execute("select sys_context('USERENV', 'MODULE'), sys_context('USERENV', 'ACTION'), sys_context('USERENV', 'CLIENT_IDENTIFIER'), sys_context('USERENV', 'SESSION_USER'), sys_context('USERENV', 'CURRENT_SCHEMA') from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let (module, action, client_id, user, schema) = row.get_as::<(Option<String>, Option<String>, Option<String>, String, String)>().unwrap();
+    assert_ne!(module, Some("module".to_string()));
+    assert_ne!(action, Some("action".to_string()));
+    assert_eq!(client_id, None);
+    assert_eq!(schema, user);
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 0);
+}
+
+#[test]
+fn distributed_transaction() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+
+    let xid = oracle::Xid::new(0x1234, b"rust-oracle-gtrid", b"branch-1");
+    assert_eq!(xid.format_id(), 0x1234);
+    assert_eq!(xid.global_transaction_id(), b"rust-oracle-gtrid");
+    assert_eq!(xid.branch_qualifier(), b"branch-1");
+    conn.begin_distrib_trans(&xid).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'distributed')", &[]).unwrap();
+    assert_eq!(conn.prepare_distrib_trans().unwrap(), true);
+    conn.commit().unwrap();
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+
+    // no commit is needed when nothing changed
+    let xid = oracle::Xid::new(0x1234, b"rust-oracle-gtrid", b"branch-2");
+    conn.begin_distrib_trans(&xid).unwrap();
+    assert_eq!(conn.prepare_distrib_trans().unwrap(), false);
+
+    // XAER_NOTA for unknown transactions
+    let xid = oracle::Xid::new(0x1234, b"no-such-gtrid", b"branch-1");
+    match conn.rollback_prepared(&xid).unwrap_err() {
+        oracle::Error::XaError(code) => assert_eq!(code, oracle::XaErrorCode::NoTa),
+        err => panic!("unexpected error: {}", err),
+    }
+
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+}
+
+#[test]
+fn suspend_and_resume_distributed_transaction() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+
+    let xid = oracle::Xid::new(0x1234, b"rust-oracle-gtrid-suspend", b"branch-1");
+    conn.begin_distrib_trans(&xid).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'branch')", &[]).unwrap();
+    conn.suspend_distrib_trans(&xid).unwrap();
+    conn.resume_distrib_trans(&xid).unwrap();
+    conn.execute("insert into TestTempTable values (2, 'branch')", &[]).unwrap();
+    assert_eq!(conn.prepare_distrib_trans().unwrap(), true);
+    conn.rollback_prepared(&xid).unwrap();
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 0);
+}
+
+// This needs SELECT privilege on DBA_PENDING_TRANSACTIONS, which isn't
+// granted to the test user by default. Run it by `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn recover_distributed_transaction() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    let xid = oracle::Xid::new(0x1234, b"rust-oracle-gtrid-recover", b"branch-1");
+    conn.begin_distrib_trans(&xid).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'branch')", &[]).unwrap();
+    assert_eq!(conn.prepare_distrib_trans().unwrap(), true);
+    let result = conn.recover_distrib_trans();
+    conn.rollback_prepared(&xid).unwrap();
+    assert!(result.unwrap().contains(&xid));
+}
+
+// The test service doesn't enable COMMIT_OUTCOME.
+#[test]
+fn ltxid_without_commit_outcome() {
+    let conn = common::connect().unwrap();
+    assert!(conn.ltxid().unwrap().is_empty());
+}
+
+// This needs a test service whose COMMIT_OUTCOME is enabled and EXECUTE
+// privilege on DBMS_APP_CONT. Run it by `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn ltxid_outcome() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'ltxid')", &[]).unwrap();
+    let ltxid = conn.ltxid().unwrap();
+    assert!(!ltxid.is_empty());
+    conn.commit().unwrap();
+    let conn2 = common::connect().unwrap();
+    let outcome = conn2.ltxid_outcome(&ltxid).unwrap();
+    assert!(outcome.committed());
+    assert!(outcome.user_call_completed());
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit().unwrap();
+}
+
+#[test]
+fn transaction_isolation() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    let conn2 = common::connect().unwrap();
+    conn.rollback().unwrap();
+    let count_sql = "select count(*) from TestTempTable";
+
+    conn.set_transaction_read_only().unwrap();
+    let mut stmt = conn.execute(count_sql, &[]).unwrap();
+    let count: i32 = stmt.fetch().unwrap().get(0).unwrap();
+    conn2.execute("insert into TestTempTable values (1, 'invisible')", &[]).unwrap();
+    conn2.commit().unwrap();
+    let mut stmt = conn.execute(count_sql, &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), count);
+    assert!(conn.execute("delete from TestTempTable", &[]).is_err());
+    conn.commit().unwrap();
+
+    conn.set_transaction_isolation(oracle::IsolationLevel::Serializable).unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    match conn.set_transaction_isolation(oracle::IsolationLevel::ReadCommitted).unwrap_err() {
+        oracle::Error::TransactionInProgress => (),
+        err => panic!("unexpected error: {}", err),
+    }
+    conn.commit().unwrap();
+}
+
+#[test]
+fn commit_with_options() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'nowait batch')", &[]).unwrap();
+    conn.commit_with(oracle::CommitOptions::new().wait(false).batch(true)).unwrap();
+    conn.rollback().unwrap();
+    let mut stmt = conn.execute("select count(*) from TestTempTable", &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.commit_with(&oracle::CommitOptions::new()).unwrap();
+}
+
+#[test]
+fn transaction_in_progress() {
+    let _lock = lock_temp_table();
+    let conn = common::connect().unwrap();
+    conn.rollback().unwrap();
+    assert_eq!(conn.transaction_in_progress().unwrap(), false);
+    conn.execute("insert into TestTempTable values (1, 'in progress')", &[]).unwrap();
+    assert_eq!(conn.transaction_in_progress().unwrap(), true);
+    conn.rollback().unwrap();
+    assert_eq!(conn.transaction_in_progress().unwrap(), false);
+}
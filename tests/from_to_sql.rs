@@ -33,6 +33,7 @@
 extern crate oracle;
 #[macro_use]
 mod common;
+use std::time::Duration;
 use oracle::*;
 
 //
@@ -230,6 +231,48 @@ fn interval_ds_to_sql() {
                  "-123456789 02:03:04.123456789");
 }
 
+//
+// std::time::Duration and SignedDuration
+//
+
+#[test]
+fn duration_from_sql() {
+    let conn = common::connect().unwrap();
+
+    let d = Duration::new(93784, 123456789);
+    test_from_sql!(&conn,
+                   "INTERVAL '+1 02:03:04.123456789' DAY TO SECOND(9)",
+                   &OracleType::IntervalDS(2, 9), &d);
+
+    let d = SignedDuration::negative(Duration::new(93784, 123456789));
+    test_from_sql!(&conn,
+                   "INTERVAL '-1 02:03:04.123456789' DAY TO SECOND(9)",
+                   &OracleType::IntervalDS(2, 9), &d);
+
+    let mut stmt = conn.prepare("select INTERVAL '-1 02:03:04' DAY TO SECOND from dual").unwrap();
+    stmt.execute(&[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    match row.get::<usize, Duration>(0) {
+        Err(Error::Overflow(_, "Duration")) => (),
+        x => panic!("Unexpected result: {:?}", x),
+    }
+}
+
+#[test]
+fn duration_to_sql() {
+    let conn = common::connect().unwrap();
+
+    let d = Duration::new(93784, 123456789);
+    test_to_sql!(&conn, &d,
+                 "TO_CHAR(:1)",
+                 "+000000001 02:03:04.123456789");
+
+    let d = SignedDuration::negative(Duration::new(93784, 123456789));
+    test_to_sql!(&conn, &d,
+                 "TO_CHAR(:1)",
+                 "-000000001 02:03:04.123456789");
+}
+
 //
 // IntervalYM
 //
@@ -283,12 +326,496 @@ fn interval_ym_to_sql() {
                  "-123456789-02");
 }
 
+//
+// LOB
+//
+
+#[test]
+fn lob_from_sql() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); for i in 1..9 loop dbms_lob.append(:1, to_clob(:2)); end loop; end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"abc".repeat(1000)]).unwrap();
+    let clob: String = stmt.bind_value(1).unwrap();
+    assert_eq!(clob, "abc".repeat(10000));
+
+    let bytes: Vec<u8> = (0..2000).map(|i| (i % 256) as u8).collect();
+    let mut stmt = conn.prepare("begin :1 := to_blob(:2); for i in 1..9 loop dbms_lob.append(:1, to_blob(:2)); end loop; end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &bytes]).unwrap();
+    let blob: Vec<u8> = stmt.bind_value(1).unwrap();
+    let expected: Vec<u8> = (0..10).flat_map(|_| bytes.iter().cloned()).collect();
+    assert_eq!(blob, expected);
+}
+
+#[test]
+fn lob_fetch_size() {
+    let conn = common::connect().unwrap();
+    let sql = "select to_clob(rpad('x', 100, 'x')), to_blob(hextoraw(rpad('01', 200, '01'))) from dual";
+
+    // typed row mapping reads LOBs as a whole
+    let mut stmt = conn.execute(sql, &[]).unwrap();
+    let (clob, blob) = stmt.fetch().unwrap().get_as::<(String, Vec<u8>)>().unwrap();
+    assert_eq!(clob, "x".repeat(100));
+    assert_eq!(blob, vec![1u8; 100]);
+
+    conn.set_max_lob_fetch_size(Some(100));
+    assert_eq!(conn.max_lob_fetch_size(), Some(100));
+    let mut stmt = conn.execute(sql, &[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<usize, String>(0).unwrap(), "x".repeat(100));
+
+    conn.set_max_lob_fetch_size(Some(99));
+    let mut stmt = conn.execute(sql, &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    match row.get::<usize, String>(0).unwrap_err() {
+        Error::LobTooLarge(100, 99) => (),
+        err => panic!("unexpected error: {:?}", err),
+    }
+    match row.get::<usize, Vec<u8>>(1).unwrap_err() {
+        Error::LobTooLarge(100, 99) => (),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn lob_as_stream() {
+    use std::io::{Read, Seek, SeekFrom, Write};
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"a\u{3042}c".repeat(1000)]).unwrap();
+    let mut clob: Clob = stmt.bind_value(1).unwrap();
+    assert_eq!(clob.size().unwrap(), 3000);
+    let mut text = String::new();
+    clob.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "a\u{3042}c".repeat(1000));
+    clob.seek(SeekFrom::Start(1)).unwrap();
+    let mut buf = [0u8; 1];
+    let mut bytes = Vec::new();
+    for _ in 0..3 {
+        assert_eq!(clob.read(&mut buf).unwrap(), 1);
+        bytes.push(buf[0]);
+    }
+    assert_eq!(bytes, "\u{3042}".as_bytes());
+    clob.seek(SeekFrom::End(0)).unwrap();
+    let multibyte = "\u{3044}".as_bytes();
+    clob.write_all(&multibyte[..1]).unwrap();
+    clob.write_all(&multibyte[1..]).unwrap();
+    clob.flush().unwrap();
+    assert_eq!(clob.size().unwrap(), 3001);
+
+    let bytes: Vec<u8> = (0..20000).map(|i| (i % 256) as u8).collect();
+    let mut stmt = conn.prepare("begin :1 := to_blob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &bytes[..10000].to_vec()]).unwrap();
+    let mut blob: Blob = stmt.bind_value(1).unwrap();
+    blob.seek(SeekFrom::End(0)).unwrap();
+    blob.write_all(&bytes[10000..]).unwrap();
+    assert_eq!(blob.seek(SeekFrom::Start(0)).unwrap(), 0);
+    let mut content = Vec::new();
+    blob.read_to_end(&mut content).unwrap();
+    assert_eq!(content, bytes);
+}
+
+#[test]
+fn lob_append_and_write_at() {
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"abcdef"]).unwrap();
+    let mut clob: Clob = stmt.bind_value(1).unwrap();
+    assert!(clob.chunk_size().unwrap() > 0);
+    clob.append_bytes("\u{3042}ghi".as_bytes()).unwrap();
+    assert_eq!(clob.is_resource_open().unwrap(), false);
+    clob.open_resource().unwrap();
+    assert_eq!(clob.is_resource_open().unwrap(), true);
+    clob.write_at(1, b"BC").unwrap();
+    clob.close_resource().unwrap();
+    assert_eq!(clob.is_resource_open().unwrap(), false);
+    assert_eq!(clob.size().unwrap(), 10);
+    let text: String = stmt.bind_value(1).unwrap();
+    assert_eq!(text, "aBCdef\u{3042}ghi");
+
+    let mut stmt = conn.prepare("begin :1 := to_blob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &vec![1u8, 2, 3]]).unwrap();
+    let mut blob: Blob = stmt.bind_value(1).unwrap();
+    blob.append_bytes(&[4, 5]).unwrap();
+    blob.write_at(0, &[9]).unwrap();
+    let bytes: Vec<u8> = stmt.bind_value(1).unwrap();
+    assert_eq!(bytes, vec![9, 2, 3, 4, 5]);
+}
+
+#[test]
+fn lob_chunks() {
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"ab\u{3042}".repeat(10)]).unwrap();
+    let clob: Clob = stmt.bind_value(1).unwrap();
+    let chunks: Vec<String> = clob.chunks(7).collect::<Result<_>>().unwrap();
+    assert_eq!(chunks.len(), 5);
+    assert_eq!(chunks[0], "ab\u{3042}ab\u{3042}a");
+    assert_eq!(chunks.concat(), "ab\u{3042}".repeat(10));
+
+    let bytes: Vec<u8> = (0..100).collect();
+    let mut stmt = conn.prepare("begin :1 := to_blob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &bytes]).unwrap();
+    let blob: Blob = stmt.bind_value(1).unwrap();
+    let chunks: Vec<Vec<u8>> = blob.chunks(30).collect::<Result<_>>().unwrap();
+    assert_eq!(chunks.iter().map(|c| c.len()).collect::<Vec<_>>(), vec![30, 30, 30, 10]);
+    assert_eq!(chunks.concat(), bytes);
+}
+
+#[test]
+fn blob_from_reader_to_writer() {
+    let conn = common::connect().unwrap();
+    let bytes: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+
+    let mut stmt = conn.prepare("begin :1 := to_blob(hextoraw('FF')); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB]).unwrap();
+    let mut blob: Blob = stmt.bind_value(1).unwrap();
+    assert_eq!(blob.write_from_reader(&bytes[..]).unwrap(), 100000);
+    assert_eq!(blob.size().unwrap(), 100000);
+
+    let mut content = Vec::new();
+    assert_eq!(blob.read_to_writer(&mut content).unwrap(), 100000);
+    assert_eq!(content, bytes);
+}
+
+#[test]
+fn clob_text_writer() {
+    use std::fmt::Write;
+    use std::io::{Seek, SeekFrom};
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob('header\n'); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB]).unwrap();
+    let mut clob: Clob = stmt.bind_value(1).unwrap();
+    clob.seek(SeekFrom::End(0)).unwrap();
+    let mut expected = "header\n".to_string();
+    {
+        let mut writer = clob.text_writer().unwrap();
+        for i in 0..10000 {
+            write!(writer, "{}\u{3042}\n", i).unwrap();
+            expected.push_str(&format!("{}\u{3042}\n", i));
+        }
+        writer.finish().unwrap();
+    }
+    let text: String = stmt.bind_value(1).unwrap();
+    assert_eq!(text, expected);
+}
+
+#[test]
+fn lob_metadata() {
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); :3 := to_nclob(:2); :4 := to_blob(hextoraw('0102')); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"a\u{3042}", &OracleType::NCLOB, &OracleType::BLOB]).unwrap();
+    let clob: Clob = stmt.bind_value(1).unwrap();
+    let nclob: Clob = stmt.bind_value(3).unwrap();
+    let blob: Blob = stmt.bind_value(4).unwrap();
+    assert_eq!(*clob.oracle_type(), OracleType::CLOB);
+    assert_eq!(*nclob.oracle_type(), OracleType::NCLOB);
+    assert_eq!(*blob.oracle_type(), OracleType::BLOB);
+    assert_eq!(clob.size().unwrap(), 2);
+    assert!(clob.byte_size().unwrap() >= 4);
+    assert_eq!(blob.byte_size().unwrap(), 2);
+    assert_eq!(clob.is_temporary().unwrap(), true);
+    assert_eq!(blob.is_temporary().unwrap(), true);
+
+    let mut stmt = conn.execute("select empty_blob() from dual", &[]).unwrap();
+    let blob: Blob = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(blob.is_temporary().unwrap(), false);
+}
+
+#[test]
+fn bind_lob_locator() {
+    let conn = common::connect().unwrap();
+
+    let mut stmt = conn.prepare("begin :1 := to_clob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::CLOB, &"abc".repeat(100)]).unwrap();
+    let clob: Clob = stmt.bind_value(1).unwrap();
+    let mut stmt = conn.execute("select dbms_lob.getlength(:1) from dual", &[&clob]).unwrap();
+    let len: i32 = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(len, 300);
+
+    let mut stmt = conn.prepare("begin :1 := to_blob(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &vec![1u8, 2, 3]]).unwrap();
+    let blob: Blob = stmt.bind_value(1).unwrap();
+    let mut stmt = conn.prepare("begin :1 := :2; dbms_lob.append(:1, :2); end;").unwrap();
+    stmt.execute(&[&OracleType::BLOB, &blob]).unwrap();
+    let bytes: Vec<u8> = stmt.bind_value(1).unwrap();
+    assert_eq!(bytes, vec![1, 2, 3, 1, 2, 3]);
+}
+
+#[test]
+fn bfile_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select bfilename('RUST_ORACLE_DIR', 'no_such_file.txt') from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(*row.columns()[0].oracle_type().unwrap(), OracleType::BFILE);
+    let mut bfile: Bfile = row.get(0).unwrap();
+    assert_eq!(bfile.directory_and_file_name().unwrap(),
+               ("RUST_ORACLE_DIR".to_string(), "no_such_file.txt".to_string()));
+    assert_eq!(bfile.is_open().unwrap(), false);
+
+    bfile.set_directory_and_file_name("OTHER_DIR", "other.txt").unwrap();
+    let mut stmt = conn.prepare("begin dbms_lob.filegetname(:1, :2, :3); end;").unwrap();
+    stmt.execute(&[&bfile, &OracleType::Varchar2(30), &OracleType::Varchar2(255)]).unwrap();
+    let dir: String = stmt.bind_value(2).unwrap();
+    let name: String = stmt.bind_value(3).unwrap();
+    assert_eq!(dir, "OTHER_DIR");
+    assert_eq!(name, "other.txt");
+}
+
+#[test]
+fn national_character_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select UNISTR('\\00e9t\\00e9'), TO_NCHAR('abc'), TO_NCLOB(UNISTR('\\00e9t\\00e9')) from dual", &[]).unwrap();
+    assert!(stmt.column_info()[0].is_national());
+    assert!(stmt.column_info()[1].is_national());
+    assert_eq!(*stmt.column_info()[2].oracle_type(), OracleType::NCLOB);
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get::<usize, String>(0).unwrap(), "\u{e9}t\u{e9}");
+    assert_eq!(row.get::<usize, String>(1).unwrap(), "abc");
+    assert_eq!(row.get::<usize, String>(2).unwrap(), "\u{e9}t\u{e9}");
+
+    // Strings are bound as NVARCHAR2.
+    let s = "\u{e9}t\u{e9}";
+    assert_eq!(s.oratype().unwrap(), OracleType::NVarchar2(s.len() as u32));
+    let mut stmt = conn.prepare("begin :1 := :2 || TO_NCHAR('!'); end;").unwrap();
+    stmt.execute(&[&OracleType::NVarchar2(10), &s]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, String>(1).unwrap(), "\u{e9}t\u{e9}!");
+
+    // Strings longer than 32767 bytes are bound as NCLOB.
+    let s = "\u{e9}".repeat(20000);
+    assert_eq!(s.oratype().unwrap(), OracleType::NCLOB);
+    let mut stmt = conn.prepare("begin :1 := DBMS_LOB.GETLENGTH(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::Int64, &s]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, i64>(1).unwrap(), 20000);
+}
+
+#[test]
+fn long_raw_and_long_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+
+    // Vec<u8> longer than 32767 bytes is bound as LONG RAW.
+    let bytes: Vec<u8> = (0..40000).map(|i| (i % 256) as u8).collect();
+    conn.execute("delete from TestLongRaws", &[]).unwrap();
+    conn.execute("insert into TestLongRaws values (1, :1)", &[&bytes]).unwrap();
+    let mut stmt = conn.execute("select LongRawCol from TestLongRaws", &[]).unwrap();
+    let val: Vec<u8> = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(val, bytes);
+
+    // VARCHAR2 larger than 32767 bytes is bound as LONG.
+    let s = "x".repeat(40000);
+    conn.execute("delete from TestLongs", &[]).unwrap();
+    conn.execute("insert into TestLongs values (1, :1)", &[&(&s, &OracleType::Varchar2(40000))]).unwrap();
+    let mut stmt = conn.execute("select LongCol from TestLongs", &[]).unwrap();
+    let val: String = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(val, s);
+}
+
+//
+// NUMBER
+//
+
+#[test]
+fn oracle_number_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    let values = ["0", "1234567890123456789012345678901234567890", "-0.000000000123456789",
+                  "12345678901234567890.12345678901234567890", "1E+125", "-1E-130"];
+    for value in values.iter() {
+        let expected: oracle::OracleNumber = value.parse().unwrap();
+        let mut stmt = conn.execute(&format!("select {} from dual", value), &[]).unwrap();
+        let num: oracle::OracleNumber = stmt.fetch().unwrap().get(0).unwrap();
+        assert_eq!(num, expected);
+
+        let mut stmt = conn.prepare("begin :1 := to_char(:2, 'TME'); end;").unwrap();
+        stmt.execute(&[&oracle::OracleType::Varchar2(100), &expected]).unwrap();
+        let s: String = stmt.bind_value(1).unwrap();
+        assert_eq!(s.parse::<oracle::OracleNumber>().unwrap(), expected);
+    }
+}
+
+#[test]
+fn number_to_integer_and_float() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select -999999999999999999, 9223372036854775807, 1.5, 1E+20, 9223372036854775808 from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get::<_, i64>(0).unwrap(), -999999999999999999);
+    assert_eq!(row.get::<_, i64>(1).unwrap(), 9223372036854775807);
+    assert_eq!(row.get::<_, u8>(0).is_err(), true);
+    assert_eq!(row.get::<_, i64>(2).is_err(), true);
+    assert_eq!(row.get::<_, i64>(4).is_err(), true);
+    assert_eq!(row.get::<_, f64>(0).unwrap(), -999999999999999999f64);
+    assert_eq!(row.get::<_, f64>(2).unwrap(), 1.5);
+    assert_eq!(row.get::<_, f32>(3).unwrap(), 1E+20);
+}
+
+#[test]
+fn float_nan_and_infinity() {
+    let conn = common::connect().unwrap();
+
+    // infinity of NUMBER is fetched as '~' and '-~' in text.
+    let mut stmt = conn.execute("select CAST(BINARY_DOUBLE_INFINITY AS NUMBER), CAST(-BINARY_DOUBLE_INFINITY AS NUMBER), BINARY_DOUBLE_NAN from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get::<usize, String>(0).unwrap(), "~");
+    assert_eq!(row.get::<usize, f64>(0).unwrap(), f64::INFINITY);
+    assert_eq!(row.get::<usize, f32>(1).unwrap(), f32::NEG_INFINITY);
+    assert!(row.get::<usize, f64>(2).unwrap().is_nan());
+    match row.get::<usize, i64>(2) {
+        Err(Error::Overflow(_, "i64")) => (),
+        x => panic!("Unexpected result: {:?}", x),
+    }
+
+    // NaN and infinity are bound as BINARY_DOUBLE.
+    let mut stmt = conn.prepare("begin :1 := to_char(:2); end;").unwrap();
+    stmt.execute(&[&OracleType::Varchar2(10), &f64::INFINITY]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, String>(1).unwrap(), "Inf");
+    stmt.execute(&[&OracleType::Varchar2(10), &f64::NAN]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, String>(1).unwrap(), "Nan");
+
+    // round trip through NUMBER
+    let mut stmt = conn.prepare("begin :1 := CAST(:2 AS NUMBER); end;").unwrap();
+    stmt.execute(&[&OracleType::Number(0, 0), &f64::NEG_INFINITY]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, f64>(1).unwrap(), f64::NEG_INFINITY);
+
+    // Integers out of range
+    let mut stmt = conn.execute("select CAST(9223372036854775808 AS BINARY_DOUBLE), CAST(255.5 AS BINARY_DOUBLE), CAST(-128.5 AS BINARY_DOUBLE) from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert!(row.get::<usize, i64>(0).is_err());
+    assert_eq!(row.get::<usize, u64>(0).unwrap(), 9223372036854775808);
+    assert_eq!(row.get::<usize, u8>(1).unwrap(), 255);
+    assert!(row.get::<usize, i8>(1).is_err());
+    // fractions are truncated symmetrically.
+    assert_eq!(row.get::<usize, i8>(2).unwrap(), -128);
+    assert!(row.get::<usize, u8>(2).is_err());
+}
+
+//
+// BOOLEAN
+//
+
+#[test]
+fn boolean_column() {
+    let conn = common::connect().unwrap();
+    let (server_version, _) = conn.server_version().unwrap();
+    if server_version.major() < 23 || oracle::client_version().unwrap().major() < 23 {
+        // SQL BOOLEAN data type isn't available.
+        return;
+    }
+    // The bundled ODPI-C cannot describe BOOLEAN columns.
+    assert!(conn.execute("select true from dual", &[]).is_err());
+
+    let mut stmt = conn.execute("select case when :1 then 1 else 0 end from dual", &[&true]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+    let mut stmt = conn.execute("select count(*) from dual where :1 = true", &[&false]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 0);
+}
+
+//
+// VECTOR
+//
+
+#[test]
+fn vector_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    let (server_version, _) = conn.server_version().unwrap();
+    if server_version.major() < 23 {
+        // VECTOR data type isn't available.
+        return;
+    }
+    let mut stmt = conn.execute("select from_vector(to_vector('[1, 2.5, -3]', 3, float32)), from_vector(to_vector('[1, -2]', 2, int8)) from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let (f, i) = row.get_as::<(Vec<f32>, Vec<i8>)>().unwrap();
+    assert_eq!(f, vec![1.0, 2.5, -3.0]);
+    assert_eq!(i, vec![1, -2]);
+
+    let vec = vec![0.5f64, -1.25];
+    let mut stmt = conn.execute("select vector_dimension_count(to_vector(:1)), from_vector(to_vector(:1, *, float64)) from dual", &[&vec, &vec]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let (dim, text) = row.get_as::<(i32, Vec<f64>)>().unwrap();
+    assert_eq!(dim, 2);
+    assert_eq!(text, vec);
+
+    let vec = vec![1.0f32, ::std::f32::NAN];
+    assert!(conn.execute("select from_vector(to_vector(:1)) from dual", &[&vec]).is_err());
+}
+
+//
+// bool
+//
+
+#[test]
+fn bool_convention_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    {
+        let mut stmt = conn.execute("select 1, 'Y' from dual", &[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        assert!(row.get::<_, bool>(0).is_err());
+        assert!(row.get::<_, bool>(1).is_err());
+    }
+
+    let mut conv = oracle::BoolConvention::new();
+    conv.numbers(true).chars("Y", "N");
+    conn.set_bool_convention(conv.clone());
+    assert_eq!(conn.bool_convention(), conv);
+
+    let mut stmt = conn.execute("select 1, 0, 'Y', 'N ', 2, 'X' from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let (t1, f1, t2, f2) = row.get_as::<(bool, bool, bool, bool)>().unwrap();
+    assert_eq!((t1, f1, t2, f2), (true, false, true, false));
+    assert!(row.get::<_, bool>(4).is_err());
+    assert!(row.get::<_, bool>(5).is_err());
+
+    let mut stmt = conn.execute("select :1 + 1, :2 || '!' from dual",
+                                &[&(&true, &oracle::OracleType::Number(1, 0)),
+                                  &(&false, &oracle::OracleType::Char(1))]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get_as::<(i32, String)>().unwrap(), (2, "N!".to_string()));
+
+    // BINARY_DOUBLE
+    let mut stmt = conn.execute("select :1 + 1, CAST(0.5 AS BINARY_DOUBLE) from dual",
+                                &[&(&true, &oracle::OracleType::BinaryDouble)]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get::<_, f64>(0).unwrap(), 2.0);
+    assert!(row.get::<_, bool>(1).is_err());
+
+    conn.set_bool_convention(oracle::BoolConvention::new());
+}
+
+//
+// ROWID
+//
+
+#[test]
+fn rowid_from_and_to_sql() {
+    let conn = common::connect().unwrap();
+    conn.execute("delete from TestTempTable", &[]).unwrap();
+    conn.execute("insert into TestTempTable values (1, 'before')", &[]).unwrap();
+
+    let rowid: oracle::RowId = {
+        let mut stmt = conn.execute("select rowid, rowidtochar(rowid) from TestTempTable", &[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        let (rowid, rowid_str) = row.get_as::<(oracle::RowId, String)>().unwrap();
+        assert_eq!(rowid.to_string(), rowid_str);
+        assert_eq!(row.get::<_, String>(0).unwrap(), rowid_str);
+        rowid
+    };
+
+    conn.execute("update TestTempTable set StringCol = 'after' where rowid = :1", &[&rowid]).unwrap();
+    let mut stmt = conn.execute("select StringCol from TestTempTable where IntCol = 1", &[]).unwrap();
+    let s: String = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(s, "after");
+    conn.rollback().unwrap();
+}
+
 #[cfg(feature = "chrono")]
 mod chrono {
     extern crate chrono;
     use self::chrono::prelude::*;
     use self::chrono::Duration;
+    use self::chrono::Months;
     use self::chrono::naive::NaiveDate;
+    use self::chrono::naive::NaiveTime;
     use common;
     use oracle::*;
 
@@ -359,6 +886,29 @@ mod chrono {
                        &OracleType::TimestampTZ(9), &dttm);
     }
 
+    #[test]
+    fn datetime_from_timestamp_ltz() {
+        let conn = common::connect().unwrap();
+        let sql = "select cast(TO_TIMESTAMP_TZ('2012-03-04 05:06:07 +01:00', 'YYYY-MM-DD HH24:MI:SS TZH:TZM') as timestamp with local time zone) from dual";
+        let expected = Utc.ymd(2012, 3, 4).and_hms(4, 6, 7);
+        for tz in &["+09:00", "-05:00", "UTC"] {
+            conn.set_session_time_zone(tz).unwrap();
+            let mut stmt = conn.execute(sql, &[]).unwrap();
+            let row = stmt.fetch().unwrap();
+            assert_eq!(row.get::<_, DateTime<Utc>>(0).unwrap(), expected);
+            assert_eq!(row.get::<_, DateTime<FixedOffset>>(0).unwrap(), expected);
+            assert_eq!(row.get::<_, DateTime<Local>>(0).unwrap(), expected);
+        }
+
+        // The local date in the session time zone is the next day.
+        let sql = "select cast(TO_TIMESTAMP_TZ('2012-03-04 23:06:07 +00:00', 'YYYY-MM-DD HH24:MI:SS TZH:TZM') as timestamp with local time zone) from dual";
+        conn.set_session_time_zone("+09:00").unwrap();
+        let mut stmt = conn.execute(sql, &[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        assert_eq!(row.get::<_, Date<Utc>>(0).unwrap(), Utc.ymd(2012, 3, 4));
+        assert_eq!(row.get::<_, Date<FixedOffset>>(0).unwrap(), FixedOffset::east_opt(9 * 3600).unwrap().ymd(2012, 3, 5));
+    }
+
     #[test]
     fn datetime_to_sql() {
         let conn = common::connect().unwrap();
@@ -624,4 +1174,335 @@ mod chrono {
             panic!("Duration 1000000000 days should not be converted to interval day to second!");
         }
     }
+
+    //
+    // chrono::naive::NaiveTime
+    //
+
+    #[test]
+    fn naive_time_from_sql() {
+        let conn = common::connect().unwrap();
+
+        // INTERVAL DAY TO SECOND -> NaiveTime
+        let t = NaiveTime::from_hms_nano(2, 3, 4, 123456789);
+        test_from_sql!(&conn,
+                       "INTERVAL '+0 02:03:04.123456789' DAY TO SECOND(9)",
+                       &OracleType::IntervalDS(2, 9), &t);
+
+        // Out of range
+        let mut stmt = conn.prepare("select INTERVAL '+1 02:03:04' DAY TO SECOND from dual").unwrap();
+        stmt.execute(&[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        if let Err(Error::Overflow(_, _)) = row.get::<usize, NaiveTime>(0) {
+            ; /* OK */
+        } else {
+            panic!("INTERVAL '+1 02:03:04' should not be converted to NaiveTime!");
+        }
+    }
+
+    #[test]
+    fn naive_time_to_sql() {
+        let conn = common::connect().unwrap();
+
+        // NaiveTime -> INTERVAL DAY TO SECOND
+        let t = NaiveTime::from_hms_nano(2, 3, 4, 123456789);
+        test_to_sql!(&conn, &t,
+                     "TO_CHAR(:1)",
+                     "+000000000 02:03:04.123456789");
+    }
+
+    //
+    // chrono::Months
+    //
+
+    #[test]
+    fn months_from_sql() {
+        let conn = common::connect().unwrap();
+
+        // INTERVAL YEAR TO MONTH -> Months
+        let m = Months::new(14);
+        test_from_sql!(&conn,
+                       "INTERVAL '+1-02' YEAR TO MONTH",
+                       &OracleType::IntervalYM(2), &m);
+    }
+
+    #[test]
+    fn months_to_sql() {
+        let conn = common::connect().unwrap();
+
+        // Months -> INTERVAL YEAR TO MONTH
+        let m = Months::new(14);
+        test_to_sql!(&conn, &m,
+                     "TO_CHAR(:1)",
+                     "+000000001-02");
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+mod chrono_tz {
+    extern crate chrono;
+    extern crate chrono_tz;
+    use self::chrono::prelude::*;
+    use self::chrono_tz::Europe::Paris;
+    use self::chrono_tz::Tz;
+    use common;
+
+    #[test]
+    fn tz_from_sql() {
+        let conn = common::connect().unwrap();
+
+        let mut stmt = conn.prepare("select EXTRACT(TIMEZONE_REGION FROM TIMESTAMP '2018-03-04 05:06:07 Europe/Paris') from dual").unwrap();
+        stmt.execute(&[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        let result: Tz = row.get(0).unwrap();
+        assert_eq!(result, Paris);
+    }
+
+    #[test]
+    fn datetime_from_sql() {
+        let conn = common::connect().unwrap();
+
+        let dt = Paris.with_ymd_and_hms(2018, 3, 4, 5, 6, 7).unwrap();
+        let mut stmt = conn.prepare("select TO_CHAR(TIMESTAMP '2018-03-04 05:06:07 Europe/Paris', 'YYYY-MM-DD HH24:MI:SS.FF9 TZR') from dual").unwrap();
+        stmt.execute(&[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        let result: DateTime<Tz> = row.get(0).unwrap();
+        assert_eq!(result, dt);
+        assert_eq!(result.timezone(), Paris);
+
+        // The region name is lost without TO_CHAR.
+        let mut stmt = conn.prepare("select TIMESTAMP '2018-03-04 05:06:07 Europe/Paris' from dual").unwrap();
+        stmt.execute(&[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        let result: DateTime<Tz> = row.get(0).unwrap();
+        assert_eq!(result, dt);
+        assert_eq!(result.timezone(), Tz::UTC);
+    }
+
+    #[test]
+    fn datetime_to_sql() {
+        let conn = common::connect().unwrap();
+
+        let dt = Paris.with_ymd_and_hms(2018, 3, 4, 5, 6, 7).unwrap();
+        test_to_sql!(&conn, &dt,
+                     "TO_CHAR(:1, 'YYYY-MM-DD HH24:MI:SS TZH:TZM')",
+                     "2018-03-04 05:06:07 +01:00");
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal {
+    extern crate rust_decimal;
+    use self::rust_decimal::Decimal;
+    use common;
+    use oracle::*;
+
+    #[test]
+    fn decimal_from_sql() {
+        let conn = common::connect().unwrap();
+
+        let d: Decimal = "1234567890123456.789012345678".parse().unwrap();
+        test_from_sql!(&conn,
+                       "CAST(1234567890123456.789012345678 AS NUMBER(28,12))",
+                       &OracleType::Number(28, 12), &d);
+        let d: Decimal = "-0.0000000000000000000000000001".parse().unwrap();
+        test_from_sql!(&conn,
+                       "-1E-28",
+                       &OracleType::Number(0, -127), &d);
+        let d = Decimal::from(-123456789012345678i64);
+        test_from_sql!(&conn,
+                       "CAST(-123456789012345678 AS NUMBER(18))",
+                       &OracleType::Number(18, 0), &d);
+
+        // too many fractional digits
+        let mut stmt = conn.execute("select 1E-29 from dual", &[]).unwrap();
+        match stmt.fetch().unwrap().get::<_, Decimal>(0) {
+            Err(Error::Overflow(ref s, "Decimal")) => assert_eq!(s, "0.00000000000000000000000000001"),
+            x => panic!("unexpected result: {:?}", x),
+        }
+    }
+
+    #[test]
+    fn decimal_to_sql() {
+        let conn = common::connect().unwrap();
+
+        let d: Decimal = "-1234567890123456.789012345678".parse().unwrap();
+        test_to_sql!(&conn, &d,
+                     "TO_CHAR(:1)",
+                     "-1234567890123456.789012345678");
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal {
+    extern crate bigdecimal;
+    use self::bigdecimal::BigDecimal;
+    use common;
+    use oracle::*;
+
+    #[test]
+    fn bigdecimal_from_sql() {
+        let conn = common::connect().unwrap();
+
+        let d: BigDecimal = "1234567890123456789012345678901234567890".parse().unwrap();
+        test_from_sql!(&conn,
+                       "1234567890123456789012345678901234567890",
+                       &OracleType::Number(0, -127), &d);
+
+        // The scale is taken from the column.
+        let mut stmt = conn.execute("select cast(1.5 as number(10, 3)) from dual", &[]).unwrap();
+        let d: BigDecimal = stmt.fetch().unwrap().get(0).unwrap();
+        assert_eq!(d.to_string(), "1.500");
+        assert_eq!(d.fractional_digit_count(), 3);
+        let mut stmt = conn.execute("select cast(2 as number(10, 2)) from dual", &[]).unwrap();
+        let d: BigDecimal = stmt.fetch().unwrap().get(0).unwrap();
+        assert_eq!(d.to_string(), "2.00");
+    }
+
+    #[test]
+    fn bigdecimal_to_sql() {
+        let conn = common::connect().unwrap();
+
+        let d: BigDecimal = "-123456789012345678901234567890.1234567890".parse().unwrap();
+        test_to_sql!(&conn, &d,
+                     "TO_CHAR(:1)",
+                     "-123456789012345678901234567890.123456789");
+        let d: BigDecimal = "1.5E+100".parse().unwrap();
+        test_to_sql!(&conn, &d,
+                     "TO_CHAR(:1, 'TME')",
+                     "1.5E+100");
+    }
+}
+
+#[cfg(feature = "serde_json")]
+mod serde_json {
+    extern crate serde_json;
+    use self::serde_json::json;
+    use self::serde_json::Value;
+    use common;
+    use oracle::*;
+
+    #[test]
+    fn json_from_sql() {
+        let conn = common::connect().unwrap();
+
+        let mut stmt = conn.execute("select '{\"a\":1,\"b\":[true,null,\"x\"]}', to_clob('[1.5]') from dual", &[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        let (obj, ary) = row.get_as::<(Value, Value)>().unwrap();
+        assert_eq!(obj, json!({"a": 1, "b": [true, null, "x"]}));
+        assert_eq!(ary, json!([1.5]));
+
+        let mut stmt = conn.execute("select 'not json' from dual", &[]).unwrap();
+        match stmt.fetch().unwrap().get::<_, Value>(0) {
+            Err(Error::ParseError(_)) => (),
+            x => panic!("unexpected result: {:?}", x),
+        }
+    }
+
+    #[test]
+    fn json_to_sql() {
+        let conn = common::connect().unwrap();
+
+        let val = json!({"key": "value"});
+        test_to_sql!(&conn, &val,
+                     ":1",
+                     "{\"key\":\"value\"}");
+
+        let long_str = "x".repeat(40000);
+        let val = json!([long_str]);
+        assert_eq!(val.oratype().unwrap(), OracleType::CLOB);
+        let mut stmt = conn.prepare("begin :1 := dbms_lob.getlength(:2); end;").unwrap();
+        stmt.execute(&[&OracleType::Int64, &val]).unwrap();
+        let len: u64 = stmt.bind_value(1).unwrap();
+        assert_eq!(len, 40004);
+    }
+
+    #[test]
+    fn rows_to_json_lines() {
+        let conn = common::connect().unwrap();
+
+        let mut stmt = conn.execute("select cast(level as number(9)) n, cast(level + 0.5 as number(10,1)) f, \
+                                            cast(level as number) big, 'row ' || level s, \
+                                            cast(null as varchar2(1)) nul, 'dup' s \
+                                       from dual connect by level <= 2", &[]).unwrap();
+        let mut buf = Vec::new();
+        let num_rows = stmt.to_json_lines(&mut buf).unwrap();
+        assert_eq!(num_rows, 2);
+        let lines: Vec<Value> = String::from_utf8(buf).unwrap().lines()
+            .map(|line| self::serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines, vec![
+            json!({"N": 1, "F": 1.5, "BIG": "1", "S": "row 1", "NUL": null, "S_1": "dup"}),
+            json!({"N": 2, "F": 2.5, "BIG": "2", "S": "row 2", "NUL": null, "S_1": "dup"}),
+        ]);
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    extern crate uuid;
+    use self::uuid::Uuid;
+    use common;
+    use oracle::*;
+
+    #[test]
+    fn uuid_from_sql() {
+        let conn = common::connect().unwrap();
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        test_from_sql!(&conn,
+                       "HEXTORAW('67E5504410B1426F9247BB680E5FE0C8')",
+                       &OracleType::Raw(16), &uuid);
+        test_from_sql!(&conn,
+                       "'67e55044-10b1-426f-9247-bb680e5fe0c8'",
+                       &OracleType::Char(36), &uuid);
+        test_from_sql!(&conn,
+                       "'67E5504410B1426F9247BB680E5FE0C8'",
+                       &OracleType::Char(32), &uuid);
+    }
+
+    #[test]
+    fn uuid_to_sql() {
+        let conn = common::connect().unwrap();
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        test_to_sql!(&conn, &uuid,
+                     "RAWTOHEX(:1)",
+                     "67E5504410B1426F9247BB680E5FE0C8");
+        test_to_sql!(&conn, &(&uuid, &OracleType::Varchar2(36)),
+                     ":1",
+                     "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    extern crate tokio;
+    use self::tokio::io::AsyncWriteExt;
+    use common;
+    use oracle::*;
+
+    #[test]
+    fn async_lob_read_and_write() {
+        let mut conn = common::connect().unwrap();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let bytes: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+
+        let blob: Blob = {
+            let mut stmt = conn.prepare("begin :1 := to_blob(hextoraw('00')); end;").unwrap();
+            stmt.execute(&[&OracleType::BLOB]).unwrap();
+            stmt.bind_value(1).unwrap()
+        };
+        let blob = {
+            let mut writer = AsyncLob::new(&mut conn, blob);
+            rt.block_on(writer.write_all(&bytes)).unwrap();
+            rt.block_on(writer.flush()).unwrap();
+            writer.into_inner().unwrap()
+        };
+        assert_eq!(blob.size().unwrap(), 100000);
+
+        let mut reader = AsyncLob::new(&mut conn, blob);
+        let mut content = Vec::new();
+        rt.block_on(tokio::io::copy(&mut reader, &mut content)).unwrap();
+        assert_eq!(content, bytes);
+    }
 }
@@ -47,8 +47,8 @@ fn collection_udt_nestedarray() {
     subobj1.set("SUBNUMBERVALUE", &1).unwrap();
     subobj1.set("SUBSTRINGVALUE", &"STRVAL:1").unwrap();
 
-    assert_eq!(obj.exist(0).unwrap(), false);
-    assert_eq!(obj.exist(1).unwrap(), false);
+    assert_eq!(obj.exists(0).unwrap(), false);
+    assert_eq!(obj.exists(1).unwrap(), false);
     assert_eq!(obj.size().unwrap(), 0);
     let err = obj.trim(1).unwrap_err();
     assert_eq!(err.to_string(), "OCI Error: OCI-22167: given trim size [1] must be less than or equal to [0]");
@@ -56,13 +56,13 @@ fn collection_udt_nestedarray() {
     assert_eq!(err.to_string(), "OCI Error: OCI-22160: element at index [0] does not exist");
 
     obj.push(&subobj1).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), true);
-    assert_eq!(obj.exist(1).unwrap(), false);
+    assert_eq!(obj.exists(0).unwrap(), true);
+    assert_eq!(obj.exists(1).unwrap(), false);
     assert_eq!(obj.size().unwrap(), 1);
 
     obj.push(&subobj2).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), true);
-    assert_eq!(obj.exist(1).unwrap(), true);
+    assert_eq!(obj.exists(0).unwrap(), true);
+    assert_eq!(obj.exists(1).unwrap(), true);
     assert_eq!(obj.size().unwrap(), 2);
 
     let subobj: oracle::Object = obj.get(0).unwrap();
@@ -97,18 +97,18 @@ fn collection_udt_nestedarray() {
                format!("Object({}.UDT_SUBOBJECT(SUBNUMBERVALUE(NUMBER): NULL, SUBSTRINGVALUE(VARCHAR2(60)): NULL))", username));
 
     obj.remove(0).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), false);
-    assert_eq!(obj.exist(1).unwrap(), true);
+    assert_eq!(obj.exists(0).unwrap(), false);
+    assert_eq!(obj.exists(1).unwrap(), true);
     assert_eq!(obj.size().unwrap(), 2); // This counts also deleted elements. See "Comments" about OCICollSize() in OCI manual.
 
     obj.trim(1).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), false);
-    assert_eq!(obj.exist(1).unwrap(), false);
+    assert_eq!(obj.exists(0).unwrap(), false);
+    assert_eq!(obj.exists(1).unwrap(), false);
     assert_eq!(obj.size().unwrap(), 1);
 
     obj.trim(1).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), false);
-    assert_eq!(obj.exist(1).unwrap(), false);
+    assert_eq!(obj.exists(0).unwrap(), false);
+    assert_eq!(obj.exists(1).unwrap(), false);
     assert_eq!(obj.size().unwrap(), 0);
 
     let mut obj = objtype.new_collection().unwrap();
@@ -123,6 +123,22 @@ fn collection_udt_nestedarray() {
     assert_eq!(obj2.size().unwrap(), 2);
 }
 
+#[test]
+fn modify_nested_table_out_param() {
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_NESTEDARRAY").unwrap();
+    let stmt = conn.execute("begin :1 := udt_nestedarray(udt_subobject(1, 'a'), udt_subobject(2, 'b'), udt_subobject(3, 'c')); end;",
+                            &[&OracleType::Object(objtype)]).unwrap();
+    let mut obj: Collection = stmt.bind_value(1).unwrap();
+    obj.remove(1).unwrap();
+    obj.trim(1).unwrap();
+    assert_eq!(obj.iter::<Object>().count(), 1);
+
+    let mut stmt = conn.execute("select count(*), max(subnumbervalue) from table(:1)", &[&obj]).unwrap();
+    let (count, max) = stmt.fetch().unwrap().get_as::<(i32, i32)>().unwrap();
+    assert_eq!((count, max), (1, 1));
+}
+
 #[test]
 fn udt_array() {
     let conn = common::connect().unwrap();
@@ -133,11 +149,32 @@ fn udt_array() {
     obj.push(&10).unwrap();
     assert_eq!(obj.get::<i32>(0).unwrap(), 10);
     obj.push(&11).unwrap();
-    assert_eq!(obj.exist(0).unwrap(), true);
-    assert_eq!(obj.exist(1).unwrap(), true);
-    assert_eq!(obj.exist(2).unwrap(), false);
+    assert_eq!(obj.exists(0).unwrap(), true);
+    assert_eq!(obj.exists(1).unwrap(), true);
+    assert_eq!(obj.exists(2).unwrap(), false);
     obj.set(0, &12).unwrap();
     assert_eq!(obj.get::<i32>(0).unwrap(), 12);
+
+    let vals = obj.iter::<i32>().collect::<oracle::Result<Vec<_>>>().unwrap();
+    assert_eq!(vals, vec![12, 11]);
+    let vals = obj.iter::<String>().collect::<oracle::Result<Vec<_>>>().unwrap();
+    assert_eq!(vals, vec!["12", "11"]);
+    let empty = objtype.new_collection().unwrap();
+    assert_eq!(empty.iter::<i32>().count(), 0);
+}
+
+#[test]
+fn collection_from_iter() {
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_ARRAY").unwrap();
+    let mut obj = objtype.new_collection_from_iter(vec![1, 2, 3]).unwrap();
+    obj.extend(vec!["4", "5"]).unwrap();
+    let vals = obj.iter::<i32>().collect::<oracle::Result<Vec<_>>>().unwrap();
+    assert_eq!(vals, vec![1, 2, 3, 4, 5]);
+
+    let objtype = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let err = objtype.new_collection_from_iter(vec![1]).unwrap_err();
+    assert_eq!(err.to_string(), format!("invalid type conversion from {} to Collection", objtype));
 }
 
 #[test]
@@ -182,6 +219,79 @@ fn udt_object() {
     assert_eq!(err.to_string(), "invalid type conversion from NUMBER to Collection");
 }
 
+#[test]
+fn object_null_attributes() {
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_OBJECT").unwrap();
+    let subobjtype = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let mut obj = objtype.new_object().unwrap();
+    let subobj = subobjtype.new_object().unwrap();
+
+    obj.set("NUMBERVALUE", &Some(1)).unwrap();
+    obj.set("STRINGVALUE", &None::<&str>).unwrap();
+    obj.set("DATEVALUE", &None::<Timestamp>).unwrap();
+    obj.set("SUBOBJECTVALUE", &subobj).unwrap();
+    assert_eq!(obj.get::<Option<i32>>("NUMBERVALUE").unwrap(), Some(1));
+    assert_eq!(obj.get::<Option<String>>("STRINGVALUE").unwrap(), None);
+    assert_eq!(obj.get::<Option<Timestamp>>("DATEVALUE").unwrap(), None);
+    assert!(obj.get::<Option<Object>>("SUBOBJECTVALUE").unwrap().is_some());
+    match obj.get::<String>("STRINGVALUE") {
+        Err(oracle::Error::NullValue) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    obj.set("NUMBERVALUE", &None::<i32>).unwrap();
+    obj.set_null("SUBOBJECTVALUE").unwrap();
+    obj.set_null("SUBOBJECTARRAY").unwrap();
+    assert_eq!(obj.get::<Option<i32>>("NUMBERVALUE").unwrap(), None);
+    assert!(obj.get::<Option<Object>>("SUBOBJECTVALUE").unwrap().is_none());
+    assert!(obj.get::<Option<Collection>>("SUBOBJECTARRAY").unwrap().is_none());
+}
+
+#[test]
+fn object_clone_value() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select UDT_SUBOBJECT(1, 'STRVAL:1') from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let obj: Object = row.get(0).unwrap();
+    let mut copied = obj.clone_value().unwrap();
+    copied.set("SUBNUMBERVALUE", &2).unwrap();
+    assert_eq!(obj.get::<i32>("SUBNUMBERVALUE").unwrap(), 1);
+    assert_eq!(copied.get::<i32>("SUBNUMBERVALUE").unwrap(), 2);
+    assert_eq!(copied.get::<String>("SUBSTRINGVALUE").unwrap(), "STRVAL:1");
+
+    let stmt = conn.execute("declare obj UDT_SUBOBJECT := :1; begin :2 := obj.SubNumberValue; end;",
+                            &[&copied, &OracleType::Int64]).unwrap();
+    assert_eq!(stmt.bind_value::<usize, i32>(2).unwrap(), 2);
+
+    let objtype = conn.object_type("UDT_ARRAY").unwrap();
+    let coll = objtype.new_collection_from_iter(vec![1, 2]).unwrap();
+    let mut copied = coll.clone_value().unwrap();
+    copied.push(&3).unwrap();
+    assert_eq!(coll.size().unwrap(), 2);
+    assert_eq!(copied.size().unwrap(), 3);
+}
+
+#[test]
+fn object_typed_attributes() {
+    let conn = common::connect().unwrap();
+    let objtype = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let mut obj = objtype.new_object().unwrap();
+
+    obj.set("subnumbervalue", &"123").unwrap();
+    obj.set("SubStringValue", &None::<String>).unwrap();
+    assert_eq!(obj.get::<f64>("SUBNUMBERVALUE").unwrap(), 123.0);
+    assert_eq!(obj.get::<String>("subnumbervalue").unwrap(), "123");
+    assert_eq!(obj.get::<Option<String>>("SUBSTRINGVALUE").unwrap(), None);
+
+    let attr = objtype.attributes().iter().find(|attr| attr.name() == "SUBSTRINGVALUE").unwrap();
+    obj.set_by_attr(attr, &"abc").unwrap();
+    assert_eq!(obj.get_by_attr::<String>(attr).unwrap(), "abc");
+
+    let err = obj.get::<i32>("NO_SUCH_ATTR").unwrap_err();
+    assert_eq!(err.to_string(), "invalid attribute name: NO_SUCH_ATTR");
+}
+
 #[test]
 fn udt_stringlist() {
     if oracle::client_version().unwrap().major() < 12 {
@@ -225,14 +335,18 @@ fn udt_stringlist() {
     // previous indexes from last
     let idx = obj.last_index().unwrap();
     assert_eq!(idx, 8388608);
-    let idx = obj.prev_index(idx).unwrap();
+    let idx = obj.prior_index(idx).unwrap();
     assert_eq!(idx, 284);
-    let idx = obj.prev_index(idx).unwrap();
+    let idx = obj.prior_index(idx).unwrap();
     assert_eq!(idx, -576);
-    let idx = obj.prev_index(idx).unwrap();
+    let idx = obj.prior_index(idx).unwrap();
     assert_eq!(idx, -1048576);
-    let err = obj.prev_index(idx).unwrap_err();
+    let err = obj.prior_index(idx).unwrap_err();
     assert_eq!(err.to_string(), "No more data to be fetched");
+
+    // iterator over sparse indexes
+    let vals = obj.iter::<String>().collect::<oracle::Result<Vec<_>>>().unwrap();
+    assert_eq!(vals, vec!["First element", "Second element", "Third element", "Fourth element"]);
 }
 
 #[test]
@@ -267,3 +381,71 @@ fn sdo_geometry() {
     let obj: Object = stmt.bind_value(1).unwrap();
     assert_eq!(obj.to_string(), text);
 }
+
+#[cfg(feature = "sdo_geometry")]
+#[test]
+fn sdo_geometry_fields() {
+    let conn = common::connect().unwrap();
+    let text = "MDSYS.SDO_GEOMETRY(2003, 8307, NULL, MDSYS.SDO_ELEM_INFO_ARRAY(1, 1003, 3), MDSYS.SDO_ORDINATE_ARRAY(1, 1, 5, 7))";
+    let mut stmt = conn.execute(&format!("select {} from dual", text), &[]).unwrap();
+    let geom: SdoGeometry = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(geom, SdoGeometry {
+        gtype: 2003,
+        srid: Some(8307),
+        point: None,
+        elem_info: vec![1, 1003, 3],
+        ordinates: vec![1.0, 1.0, 5.0, 7.0],
+    });
+    let obj = geom.to_object(&conn).unwrap();
+    assert_eq!(obj.to_string(), text);
+
+    let mut stmt = conn.execute("select MDSYS.SDO_GEOMETRY(2001, NULL, MDSYS.SDO_POINT_TYPE(12, 14, NULL), NULL, NULL) from dual", &[]).unwrap();
+    let geom: SdoGeometry = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(geom.point, Some(SdoPoint { x: Some(12.0), y: Some(14.0), z: None }));
+    assert!(geom.elem_info.is_empty());
+    assert!(geom.ordinates.is_empty());
+}
+
+#[derive(Debug, PartialEq)]
+struct SubObject {
+    number_value: Option<i32>,
+    string_value: Option<String>,
+}
+
+impl FromObject for SubObject {
+    fn from_object(obj: &Object) -> oracle::Result<SubObject> {
+        Ok(SubObject {
+            number_value: obj.get("SUBNUMBERVALUE")?,
+            string_value: obj.get("SUBSTRINGVALUE")?,
+        })
+    }
+}
+
+#[test]
+fn as_object_of() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select udt_subobject(1, 'STRVAL:1'), udt_subobject(null, null), 1 from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let values = row.columns();
+    assert_eq!(values[0].as_object_of::<SubObject>().unwrap(),
+               SubObject { number_value: Some(1), string_value: Some("STRVAL:1".to_string()) });
+    assert_eq!(values[1].as_object_of::<SubObject>().unwrap(),
+               SubObject { number_value: None, string_value: None });
+    assert!(values[2].as_object_of::<SubObject>().is_err());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn object_to_json() {
+    extern crate serde_json;
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select udt_nestedarray(udt_subobject(1, 'STRVAL:1'), udt_subobject(null, null)) from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let coll: Collection = row.get(0).unwrap();
+    assert_eq!(coll.to_json().unwrap(), serde_json::json!([
+        {"SUBNUMBERVALUE": "1", "SUBSTRINGVALUE": "STRVAL:1"},
+        {"SUBNUMBERVALUE": null, "SUBSTRINGVALUE": null},
+    ]));
+    let obj: Object = coll.get(0).unwrap();
+    assert_eq!(obj.to_json().unwrap().to_string(), r#"{"SUBNUMBERVALUE":"1","SUBSTRINGVALUE":"STRVAL:1"}"#);
+}
@@ -259,3 +259,45 @@ fn pkg_testrecords_udt_record() {
     assert_eq!(*attrs[4].name(), "BOOLEANVALUE");
     assert_eq!(*attrs[4].oracle_type(), oracle::OracleType::Boolean);
 }
+
+#[test]
+fn offline_object_type() {
+    let attrs = vec![oracle::ObjectTypeAttr::new_offline("ID", oracle::OracleType::Number(9, 0)),
+                     oracle::ObjectTypeAttr::new_offline("NAME", oracle::OracleType::Varchar2(30))];
+    let objtype = oracle::ObjectType::new_offline("SCOTT", "PERSON", attrs);
+    assert!(objtype.is_offline());
+    assert_eq!(*objtype.schema(), "SCOTT");
+    assert_eq!(*objtype.name(), "PERSON");
+    assert_eq!(objtype.is_collection(), false);
+    assert_eq!(objtype.num_attributes(), 2);
+    assert_eq!(*objtype.attributes()[1].oracle_type(), oracle::OracleType::Varchar2(30));
+    assert_eq!(*objtype.attribute("NAME").unwrap().oracle_type(), oracle::OracleType::Varchar2(30));
+    assert_eq!(*objtype.attribute("id").unwrap().name(), "ID");
+    assert!(objtype.attribute("AGE").is_none());
+    assert!(objtype.new_object().is_none());
+
+    let elem_type = oracle::OracleType::Object(objtype.clone());
+    let colltype = oracle::ObjectType::new_offline_collection("SCOTT", "PERSON_ARRAY", elem_type.clone());
+    assert_eq!(colltype.is_collection(), true);
+    assert_eq!(colltype.element_oracle_type(), Some(&elem_type));
+    assert!(colltype.new_collection().is_none());
+    assert!(colltype != objtype);
+
+    let colinfo = oracle::ColumnInfo::new_offline("PERSONS", oracle::OracleType::Object(colltype), true);
+    assert_eq!(colinfo.to_string(), "PERSONS SCOTT.PERSON_ARRAY");
+}
+
+#[test]
+fn object_type_cache() {
+    let conn = common::connect().unwrap();
+    let objtype1 = conn.object_type("UDT_SUBOBJECT").unwrap();
+    let objtype2 = conn.object_type("UDT_SUBOBJECT").unwrap();
+    assert!(objtype1 == objtype2);
+    assert_eq!(objtype1.num_attributes(), 2);
+
+    conn.invalidate_object_type("UDT_SUBOBJECT");
+    let objtype3 = conn.object_type("UDT_SUBOBJECT").unwrap();
+    assert_eq!(*objtype3.name(), "UDT_SUBOBJECT");
+    conn.clear_object_type_cache();
+    assert!(conn.object_type("NO_SUCH_TYPE_IN_DB").is_err());
+}
@@ -0,0 +1,182 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+extern crate oracle;
+mod common;
+
+#[test]
+fn get_null_value() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 1, null from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+
+    assert_eq!(row.get_opt::<_, i32>(0).unwrap(), Some(1));
+    assert_eq!(row.get_opt::<_, i32>(1).unwrap(), None);
+    assert_eq!(row.get_or(0, 10).unwrap(), 1);
+    assert_eq!(row.get_or(1, 10).unwrap(), 10);
+    assert_eq!(row.get_or(1, "default".to_string()).unwrap(), "default");
+    match row.get::<_, i32>(1) {
+        Err(oracle::Error::NullValue) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+    // Errors other than NullValue are not replaced by the default value.
+    assert!(row.get_or(2, 10).is_err());
+}
+
+#[test]
+fn iterate_row() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 1 as num, 'str' as str, null as nul from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+
+    let names: Vec<&str> = row.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["NUM", "STR", "NUL"]);
+    assert_eq!(row.iter().len(), 3);
+
+    let mut iter = row.into_iter();
+    let (name, val) = iter.next().unwrap();
+    assert_eq!(name, "NUM");
+    assert_eq!(val.as_i32().unwrap(), 1);
+    let (name, val) = iter.next().unwrap();
+    assert_eq!(name, "STR");
+    assert_eq!(val.as_string().unwrap(), "str");
+    let (name, val) = iter.next().unwrap();
+    assert_eq!(name, "NUL");
+    assert!(val.is_null().unwrap());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn row_to_map() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 1 as num, 'str' as str, null as nul from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+
+    let map = row.to_map().unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["NUM"], Some("1".to_string()));
+    assert_eq!(map["STR"], Some("str".to_string()));
+    assert_eq!(map["NUL"], None);
+
+    let mut stmt = conn.execute("select 1 as one, 2.5 as two, null as nul from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let map = row.to_map_as::<f64>().unwrap();
+    assert_eq!(map["ONE"], Some(1.0));
+    assert_eq!(map["TWO"], Some(2.5));
+    assert_eq!(map["NUL"], None);
+}
+
+#[test]
+fn row_get_as_tuple() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 1, 'str', null from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+
+    let (num, s, nul) = row.get_as::<(i64, String, Option<f64>)>().unwrap();
+    assert_eq!(num, 1);
+    assert_eq!(s, "str");
+    assert_eq!(nul, None);
+
+    // fewer elements than columns
+    let (num,) = row.get_as::<(i64,)>().unwrap();
+    assert_eq!(num, 1);
+
+    // more elements than columns
+    match row.get_as::<(i64, String, Option<f64>, i32)>() {
+        Err(oracle::Error::InvalidColumnIndex(3)) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+}
+
+#[test]
+fn duplicated_column_names() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 1 as a, 2 as a, 3 as a_1, 4 as b from dual", &[]).unwrap();
+    assert_eq!(stmt.column_names(), vec!["A", "A_2", "A_1", "B"]);
+    let row = stmt.fetch().unwrap();
+
+    match row.get::<_, i32>("A") {
+        Err(oracle::Error::AmbiguousColumnName(ref name)) => assert_eq!(name, "A"),
+        x => panic!("unexpected result: {:?}", x),
+    }
+    assert_eq!(row.get::<_, i32>("a_2").unwrap(), 2);
+    assert_eq!(row.get::<_, i32>("A_1").unwrap(), 3);
+    assert_eq!(row.get::<_, i32>("B").unwrap(), 4);
+    assert_eq!(row.get::<_, i32>(0).unwrap(), 1);
+    let names: Vec<&str> = row.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["A", "A_2", "A_1", "B"]);
+}
+
+#[test]
+fn get_str_and_bytes_ref() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.execute("select 'str', hextoraw('0102ff'), null, 123, to_clob('clob') from dual", &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+
+    let s: &str = row.get(0).unwrap();
+    assert_eq!(s, "str");
+    let bytes: &[u8] = row.get(1).unwrap();
+    assert_eq!(bytes, &[0x01, 0x02, 0xff]);
+    assert_eq!(row.get::<_, Option<&str>>(2).unwrap(), None);
+    assert_eq!(row.get::<_, &str>(3).unwrap(), "123");
+    // LOBs cannot be borrowed.
+    assert!(row.get::<_, &str>(4).is_err());
+    assert_eq!(row.get::<_, String>(4).unwrap(), "clob");
+}
+
+#[test]
+fn strict_utf8() {
+    let sql = "select utl_raw.cast_to_varchar2(hextoraw('41FF42')) bad_col from dual";
+    let conn = common::connect().unwrap();
+    {
+        let mut stmt = conn.execute(sql, &[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        assert_eq!(row.get::<_, String>(0).unwrap(), "A\u{FFFD}B");
+        // &str cannot replace invalid sequences.
+        match row.get::<_, &str>(0) {
+            Err(oracle::Error::InvalidUtf8(ref name, 1)) if name == "BAD_COL" => (),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    conn.set_strict_utf8(true);
+    let mut stmt = conn.execute(sql, &[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    match row.get::<_, String>(0) {
+        Err(oracle::Error::InvalidUtf8(ref name, offset)) => {
+            assert_eq!(name, "BAD_COL");
+            assert_eq!(offset, 1);
+        },
+        res => panic!("unexpected result: {:?}", res),
+    }
+    conn.set_strict_utf8(false);
+}
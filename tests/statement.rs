@@ -98,3 +98,313 @@ fn bind_names() {
     assert_eq!(bind_names[1], "VAL2");
     assert_eq!(bind_names[2], "aàáâãäå".to_uppercase());
 }
+
+#[test]
+fn reexecute_query() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select :1, 'x' || :2 from dual").unwrap();
+
+    for i in 0..3 {
+        stmt.execute(&[&i, &i]).unwrap();
+        {
+            let row = stmt.fetch().unwrap();
+            let val: i32 = row.get(0).unwrap();
+            let s: String = row.get(1).unwrap();
+            assert_eq!(val, i);
+            assert_eq!(s, format!("x{}", i));
+        }
+        assert!(stmt.fetch().is_err());
+        assert_eq!(stmt.column_count(), 2);
+    }
+}
+
+#[test]
+fn reexecute_query_with_other_bind_types() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select :1 from dual").unwrap();
+
+    stmt.execute(&[&1]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<usize, i32>(0).unwrap(), 1);
+
+    stmt.execute(&[&"short"]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<usize, String>(0).unwrap(), "short");
+
+    // longer than the size of the previous define
+    let long_str = "x".repeat(1000);
+    stmt.execute(&[&long_str]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<usize, String>(0).unwrap(), long_str);
+}
+
+#[test]
+fn column_info_only() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select :1 as col1, 'x' as col2 from dual").unwrap();
+
+    {
+        let column_info = stmt.column_info_only().unwrap();
+        assert_eq!(column_info.len(), 2);
+        assert_eq!(column_info[0].name(), "COL1");
+        assert_eq!(column_info[1].name(), "COL2");
+        assert_eq!(*column_info[1].oracle_type(), oracle::OracleType::Char(1));
+    }
+
+    // The statement can be executed after describe-only.
+    stmt.execute(&[&1]).unwrap();
+    let row = stmt.fetch().unwrap();
+    let val: i32 = row.get(0).unwrap();
+    assert_eq!(val, 1);
+
+    let mut stmt = conn.prepare("begin null; end;").unwrap();
+    assert_eq!(stmt.column_info_only().unwrap().len(), 0);
+}
+
+#[test]
+fn column_metadata() {
+    let conn = common::connect().unwrap();
+    let sql = "select cast(1 as number(10,2)) num, cast(1 as float(20)) flt, \
+               cast('x' as varchar2(10 char)) vc, cast(n'x' as nvarchar2(5)) nvc, \
+               udt_SubObject(1, 'x') obj \
+               from dual";
+    let mut stmt = conn.prepare(sql).unwrap();
+    let column_info = stmt.column_info_only().unwrap();
+
+    assert_eq!(column_info[0].precision(), 10);
+    assert_eq!(column_info[0].scale(), 2);
+    assert_eq!(column_info[0].size_in_chars(), 0);
+    assert!(!column_info[0].is_national());
+
+    assert_eq!(column_info[1].precision(), 20);
+    assert_eq!(column_info[1].scale(), -127);
+
+    assert_eq!(column_info[2].size_in_chars(), 10);
+    assert!(column_info[2].size_in_bytes() >= 10);
+    assert!(!column_info[2].is_national());
+    assert!(column_info[2].object_type().is_none());
+
+    assert_eq!(column_info[3].size_in_chars(), 5);
+    assert!(column_info[3].is_national());
+
+    assert_eq!(column_info[4].object_type().unwrap().name(), "UDT_SUBOBJECT");
+}
+
+#[test]
+fn execute_with_timeout() {
+    use std::time::Duration;
+
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("begin loop null; end loop; end;").unwrap();
+    match stmt.execute_with_timeout(&[], Duration::from_millis(500)) {
+        Err(oracle::Error::OciError(ref dberr)) => assert_eq!(dberr.code(), 1013),
+        x => panic!("unexpected result: {:?}", x),
+    }
+
+    // The connection is still usable and the timeout doesn't affect
+    // executions finished in time.
+    let mut stmt = conn.prepare("select 1 from dual").unwrap();
+    stmt.execute_with_timeout(&[], Duration::from_secs(10)).unwrap();
+    let val: i32 = stmt.fetch().unwrap().get(0).unwrap();
+    assert_eq!(val, 1);
+}
+
+#[test]
+fn fetch_all() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select level, 'row ' || level from dual connect by level <= 250").unwrap();
+    stmt.set_fetch_array_size(100);
+    stmt.execute(&[]).unwrap();
+    let rows = stmt.fetch_all::<(i32, String)>().unwrap();
+    assert_eq!(rows.len(), 250);
+    for (i, &(ref num, ref s)) in rows.iter().enumerate() {
+        assert_eq!(*num, i as i32 + 1);
+        assert_eq!(*s, format!("row {}", i + 1));
+    }
+    assert_eq!(stmt.metrics().round_trips(), 4);
+    assert_eq!(stmt.fetch_all::<(i32,)>().unwrap().len(), 0);
+}
+
+#[test]
+fn set_fetch_array_size_while_fetching() {
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select level from dual connect by level <= 30").unwrap();
+    stmt.set_fetch_array_size(10);
+    stmt.execute(&[]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<usize, i32>(0).unwrap(), 1);
+    // takes effect at the next execution
+    stmt.set_fetch_array_size(20);
+    assert_eq!(stmt.fetch_array_size(), 20);
+    let rows = stmt.fetch_all::<(i32,)>().unwrap();
+    assert_eq!(rows.len(), 29);
+    assert_eq!(rows[0].0, 2);
+
+    stmt.execute(&[]).unwrap();
+    assert_eq!(stmt.fetch_all::<(i32,)>().unwrap().len(), 30);
+}
+
+#[test]
+fn error_context() {
+    let conn = common::connect().unwrap();
+    let sql = "select * from table_which_does_not_exist";
+    let mut stmt = conn.prepare(sql).unwrap();
+    match stmt.execute(&[]) {
+        Err(oracle::Error::OciError(ref dberr)) => {
+            assert_eq!(dberr.code(), 942);
+            assert_eq!(dberr.context(), &vec![format!("executing \"{}\"", sql)]);
+        },
+        Err(err) => panic!("unexpected error: {:?}", err),
+        Ok(_) => panic!("no error"),
+    }
+}
+
+#[test]
+fn metrics() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let conn = common::connect().unwrap();
+    let called = Rc::new(Cell::new(0));
+    let called_in_callback = called.clone();
+    conn.set_metrics_callback(move |sql, metrics| {
+        assert_eq!(sql, "select level from dual connect by level <= 10");
+        assert_eq!(metrics.fetched_rows(), 10);
+        called_in_callback.set(called_in_callback.get() + 1);
+    });
+    {
+        let mut stmt = conn.execute("select level from dual connect by level <= 10", &[]).unwrap();
+        while let Ok(_) = stmt.fetch() {
+        }
+        let metrics = stmt.metrics();
+        assert_eq!(metrics.execute_count(), 1);
+        assert_eq!(metrics.fetched_rows(), 10);
+        assert_eq!(metrics.round_trips(), 2);
+    }
+    assert_eq!(called.get(), 1);
+
+    // statements executed internally aren't reported.
+    conn.session_time_zone().unwrap();
+    assert_eq!(called.get(), 1);
+
+    // a panic in the callback doesn't escape from drop.
+    conn.set_metrics_callback(|_, _| panic!("panic in the metrics callback"));
+    conn.execute("select 1 from dual", &[]).unwrap();
+    conn.unset_metrics_callback();
+}
+
+#[test]
+fn number_define() {
+    use oracle::{NumberDefine, OracleType};
+
+    let conn = common::connect().unwrap();
+    let sql = "select cast(123 as number(5)), cast(1.5 as number(5,1)), 12345678901234567890 from dual";
+    let oratypes = |stmt: &mut oracle::Statement| {
+        stmt.execute(&[]).unwrap();
+        let row = stmt.fetch().unwrap();
+        row.columns().iter().map(|v| v.oracle_type().unwrap().clone()).collect::<Vec<_>>()
+    };
+
+    let mut stmt = conn.prepare(sql).unwrap();
+    assert_eq!(stmt.number_define(), NumberDefine::Auto);
+    assert_eq!(oratypes(&mut stmt), vec![OracleType::Int64, OracleType::Number(5, 1), OracleType::Number(0, -127)]);
+
+    stmt.set_number_define(NumberDefine::Text);
+    assert_eq!(oratypes(&mut stmt), vec![OracleType::Number(5, 0), OracleType::Number(5, 1), OracleType::Number(0, -127)]);
+
+    stmt.set_number_define(NumberDefine::Native);
+    assert_eq!(oratypes(&mut stmt), vec![OracleType::Int64, OracleType::BinaryDouble, OracleType::BinaryDouble]);
+
+    conn.set_number_define(NumberDefine::Text);
+    let mut stmt = conn.prepare(sql).unwrap();
+    assert_eq!(stmt.number_define(), NumberDefine::Text);
+    stmt.execute(&[]).unwrap();
+    let num: oracle::OracleNumber = stmt.fetch().unwrap().get(2).unwrap();
+    assert_eq!(num.to_string(), "12345678901234567890");
+    conn.set_number_define(NumberDefine::Auto);
+}
+
+#[test]
+fn fetch_lob_inline() {
+    use oracle::OracleType;
+
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select to_clob(rpad('a', 4000, 'b')), to_blob(hextoraw('0102')) from dual").unwrap();
+    assert_eq!(stmt.fetch_lob_inline(), false);
+    stmt.set_fetch_lob_inline(true);
+    stmt.execute(&[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(*row.columns()[0].oracle_type().unwrap(), OracleType::Long);
+    assert_eq!(*row.columns()[1].oracle_type().unwrap(), OracleType::LongRaw);
+    let (text, bytes) = row.get_as::<(String, Vec<u8>)>().unwrap();
+    assert_eq!(text, format!("a{}", "b".repeat(3999)));
+    assert_eq!(bytes, vec![1, 2]);
+
+    stmt.set_fetch_lob_inline(false);
+    stmt.execute(&[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(*row.columns()[0].oracle_type().unwrap(), OracleType::CLOB);
+}
+
+#[test]
+fn strict_conversion() {
+    let conn = common::connect().unwrap();
+    let sql = "select '123', 1.5d, 2.0d, cast(123 as number(10)), '2012-03-04 05:06:07' from dual";
+
+    let mut stmt = conn.execute(sql, &[]).unwrap();
+    {
+        let row = stmt.fetch().unwrap();
+        assert_eq!(row.get::<_, i32>(0).unwrap(), 123);
+        assert_eq!(row.get::<_, i32>(1).unwrap(), 1);
+        assert_eq!(row.get::<_, i64>(2).unwrap(), 2);
+        assert!(row.get::<_, oracle::Timestamp>(4).is_ok());
+    }
+
+    stmt.set_strict_conversion(true);
+    stmt.execute(&[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert!(row.get::<_, i32>(0).is_err());
+    assert!(row.get::<_, f64>(0).is_err());
+    assert!(row.get::<_, i32>(1).is_err());
+    assert!(row.get::<_, i64>(2).is_err());
+    assert!(row.get::<_, oracle::Timestamp>(4).is_err());
+    assert_eq!(row.get::<_, String>(0).unwrap(), "123");
+    assert_eq!(row.get::<_, f64>(1).unwrap(), 1.5);
+    assert_eq!(row.get::<_, i32>(3).unwrap(), 123);
+    assert_eq!(row.get::<_, oracle::OracleNumber>(3).unwrap().to_string(), "123");
+
+    conn.set_strict_conversion(true);
+    let stmt = conn.prepare(sql).unwrap();
+    assert!(stmt.strict_conversion());
+    conn.set_strict_conversion(false);
+}
+
+#[test]
+fn float_to_int() {
+    use oracle::FloatToInt;
+
+    let conn = common::connect().unwrap();
+    let mut stmt = conn.prepare("select 2.5d, -2.5d, 300.7d, -1e300d, binary_double_infinity, binary_double_nan from dual").unwrap();
+    assert_eq!(stmt.float_to_int(), FloatToInt::Checked);
+
+    stmt.execute(&[]).unwrap();
+    {
+        let row = stmt.fetch().unwrap();
+        assert_eq!(row.get_as::<(i32, i32)>().unwrap(), (2, -2));
+        assert!(row.get::<_, u8>(2).is_err());
+        assert!(row.get::<_, i64>(3).is_err());
+    }
+
+    stmt.set_float_to_int(FloatToInt::Round);
+    stmt.execute(&[]).unwrap();
+    {
+        let row = stmt.fetch().unwrap();
+        assert_eq!(row.get_as::<(i32, i32, i32)>().unwrap(), (3, -3, 301));
+        assert!(row.get::<_, u8>(2).is_err());
+    }
+
+    stmt.set_float_to_int(FloatToInt::Saturate);
+    stmt.execute(&[]).unwrap();
+    let row = stmt.fetch().unwrap();
+    assert_eq!(row.get_as::<(i32, i32)>().unwrap(), (2, -2));
+    assert_eq!(row.get::<_, u8>(2).unwrap(), 255);
+    assert_eq!(row.get::<_, i64>(3).unwrap(), i64::min_value());
+    assert_eq!(row.get::<_, u32>(4).unwrap(), u32::max_value());
+    assert!(row.get::<_, i32>(5).is_err());
+}
@@ -0,0 +1,139 @@
+// Rust-oracle - Rust binding for Oracle database
+//
+// URL: https://github.com/kubo/rust-oracle
+//
+// ------------------------------------------------------
+//
+// Copyright 2017 Kubo Takehiro <kubo@jiubao.org>
+//
+// Redistribution and use in source and binary forms, with or without modification, are
+// permitted provided that the following conditions are met:
+//
+//    1. Redistributions of source code must retain the above copyright notice, this list of
+//       conditions and the following disclaimer.
+//
+//    2. Redistributions in binary form must reproduce the above copyright notice, this list
+//       of conditions and the following disclaimer in the documentation and/or other materials
+//       provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHORS ''AS IS'' AND ANY EXPRESS OR IMPLIED
+// WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND
+// FITNESS FOR A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL <COPYRIGHT HOLDER> OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF
+// ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+// The views and conclusions contained in the software and documentation are those of the
+// authors and should not be interpreted as representing official policies, either expressed
+// or implied, of the authors.
+
+extern crate oracle;
+mod common;
+
+use std::sync::mpsc;
+use std::time::Duration;
+use oracle::*;
+
+// The database server must be able to connect to the client to send
+// notifications.
+#[test]
+fn row_level_changes() {
+    let conn2 = common::connect().unwrap();
+    conn2.execute("delete from TestTempTable", &[]).unwrap();
+    conn2.commit().unwrap();
+
+    let conn = Connector::new(&common::main_user(), &common::main_password(), &common::connect_string())
+        .events(true)
+        .connect().unwrap();
+    let (tx, rx) = mpsc::channel();
+    let mut opts = SubscrOptions::new();
+    opts.rowids(true)
+        .operations(&[Operation::Insert, Operation::Delete]);
+    let subscr = conn.subscribe(&opts, move |event| {
+        let _ = tx.send(event.clone());
+    }).unwrap();
+    subscr.register_query("select * from TestTempTable", &[]).unwrap();
+
+    conn2.execute("insert into TestTempTable values (1, 'row 1')", &[]).unwrap();
+    let mut stmt = conn2.execute("select rowid from TestTempTable where IntCol = 1", &[]).unwrap();
+    let rowid: RowId = stmt.fetch().unwrap().get(0).unwrap();
+    conn2.commit().unwrap();
+
+    let event = rx.recv_timeout(Duration::from_secs(30)).unwrap();
+    assert_eq!(event.event_type(), EventType::ObjChange);
+    assert_eq!(event.tables().len(), 1);
+    let table = &event.tables()[0];
+    assert_eq!(table.name(), format!("{}.TESTTEMPTABLE", common::main_user().to_uppercase()));
+    assert_eq!(table.operations(), &[Operation::Insert]);
+    assert!(!table.all_rows());
+    assert_eq!(table.rows().len(), 1);
+    assert_eq!(table.rows()[0].operation(), Operation::Insert);
+    assert_eq!(table.rows()[0].rowid(), &rowid);
+
+    conn2.execute("delete from TestTempTable", &[]).unwrap();
+    conn2.commit().unwrap();
+    let event = rx.recv_timeout(Duration::from_secs(30)).unwrap();
+    assert_eq!(event.tables()[0].rows()[0].operation(), Operation::Delete);
+}
+
+#[test]
+fn unsubscribe() {
+    let conn = Connector::new(&common::main_user(), &common::main_password(), &common::connect_string())
+        .events(true)
+        .connect().unwrap();
+    let sql = "select count(*) from user_change_notification_regs where regid = :1";
+    let subscr = conn.subscribe(&SubscrOptions::new(), |_| {}).unwrap();
+    subscr.set_error_callback(|err| panic!("unexpected error: {}", err));
+    subscr.register_query("select * from TestTempTable", &[]).unwrap();
+    let id = subscr.id();
+    let mut stmt = conn.execute(sql, &[&id]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 1);
+
+    subscr.unsubscribe().unwrap();
+    let mut stmt = conn.execute(sql, &[&id]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 0);
+
+    // deregistered when dropped
+    let id = {
+        let subscr = conn.subscribe(&SubscrOptions::new(), |_| {}).unwrap();
+        subscr.register_query("select * from TestTempTable", &[]).unwrap();
+        subscr.id()
+    };
+    let mut stmt = conn.execute(sql, &[&id]).unwrap();
+    assert_eq!(stmt.fetch().unwrap().get::<_, i32>(0).unwrap(), 0);
+}
+
+#[test]
+fn query_level_changes() {
+    let conn2 = common::connect().unwrap();
+    conn2.execute("delete from TestTempTable", &[]).unwrap();
+    conn2.commit().unwrap();
+
+    let conn = Connector::new(&common::main_user(), &common::main_password(), &common::connect_string())
+        .events(true)
+        .connect().unwrap();
+    let (tx, rx) = mpsc::channel();
+    let mut opts = SubscrOptions::new();
+    opts.query(true);
+    let subscr = conn.subscribe(&opts, move |event| {
+        let _ = tx.send(event.clone());
+    }).unwrap();
+    let query1 = subscr.register_query("select * from TestTempTable where IntCol = 1", &[]).unwrap();
+    let query2 = subscr.register_query("select * from TestTempTable where IntCol = 2", &[]).unwrap();
+    assert!(query1 != 0);
+    assert!(query1 != query2);
+
+    conn2.execute("insert into TestTempTable values (2, 'row 2')", &[]).unwrap();
+    conn2.commit().unwrap();
+
+    let event = rx.recv_timeout(Duration::from_secs(30)).unwrap();
+    assert_eq!(event.event_type(), EventType::QueryChange);
+    assert!(event.query(query1).is_none());
+    assert_eq!(event.query(query2).unwrap().id(), query2);
+
+    conn2.execute("delete from TestTempTable", &[]).unwrap();
+    conn2.commit().unwrap();
+}